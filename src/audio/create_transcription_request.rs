@@ -1,7 +1,4 @@
-use reqwest::Body;
-use tokio_util::codec::BytesCodec;
-
-use crate::common::{AudioModel, OpenAIError, OpenAIFile};
+use crate::common::{AudioModel, MultipartForm, OpenAIFile};
 
 use super::AudioResponseFormat;
 
@@ -35,19 +32,10 @@ pub struct CreateTranscriptionRequest {
     pub temperature: Option<f32>,
 }
 
-impl TryFrom<CreateTranscriptionRequest> for reqwest::multipart::Form {
-    type Error = OpenAIError;
-
-    fn try_from(request: CreateTranscriptionRequest) -> Result<Self, Self::Error> {
-        let file_name = request.file.name.to_owned();
-        let file_body = Body::wrap_stream(request.file.into_stream(BytesCodec::new()));
-
-        let file_part = reqwest::multipart::Part::stream(file_body)
-            .file_name(file_name)
-            .mime_str("application/octet-stream")?;
-
-        let mut form = reqwest::multipart::Form::new()
-            .part("file", file_part)
+impl From<CreateTranscriptionRequest> for MultipartForm {
+    fn from(request: CreateTranscriptionRequest) -> Self {
+        let mut form = MultipartForm::new()
+            .file("file", request.file)
             .text("model", request.model.to_string())
             .text("response_format", request.response_format.to_string());
 
@@ -63,6 +51,6 @@ impl TryFrom<CreateTranscriptionRequest> for reqwest::multipart::Form {
             form = form.text("language", language);
         }
 
-        Ok(form)
+        form
     }
 }
@@ -1,7 +1,4 @@
-use reqwest::Body;
-use tokio_util::codec::BytesCodec;
-
-use crate::common::{AudioModel, OpenAIError, OpenAIFile};
+use crate::common::{AudioModel, MultipartForm, OpenAIFile};
 
 use super::AudioResponseFormat;
 
@@ -30,19 +27,10 @@ pub struct CreateTranslationRequest {
     pub temperature: Option<f32>,
 }
 
-impl TryFrom<CreateTranslationRequest> for reqwest::multipart::Form {
-    type Error = OpenAIError;
-
-    fn try_from(request: CreateTranslationRequest) -> Result<Self, Self::Error> {
-        let file_name = request.file.name.to_owned();
-        let file_body = Body::wrap_stream(request.file.into_stream(BytesCodec::new()));
-
-        let file_part = reqwest::multipart::Part::stream(file_body)
-            .file_name(file_name)
-            .mime_str("application/octet-stream")?;
-
-        let mut form = reqwest::multipart::Form::new()
-            .part("file", file_part)
+impl From<CreateTranslationRequest> for MultipartForm {
+    fn from(request: CreateTranslationRequest) -> Self {
+        let mut form = MultipartForm::new()
+            .file("file", request.file)
             .text("model", request.model.to_string())
             .text("response_format", request.response_format.to_string());
 
@@ -54,6 +42,6 @@ impl TryFrom<CreateTranslationRequest> for reqwest::multipart::Form {
             form = form.text("temperature", temperature.to_string());
         }
 
-        Ok(form)
+        form
     }
 }
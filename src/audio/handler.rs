@@ -1,6 +1,10 @@
 use reqwest::Method;
 
-use crate::{base_client::BaseClient, common::OpenAIError, OpenAIRequest};
+use crate::{
+    base_client::BaseClient,
+    common::{AudioModel, OpenAIError, UsageRecord},
+    OpenAIRequest,
+};
 
 use super::{
     AudioResponse, CreateSpeechRequest, CreateSpeechResponse, CreateTranscriptionRequest,
@@ -28,12 +32,17 @@ impl<'a> AudioHandler<'a> {
         &self,
         request: CreateSpeechRequest,
     ) -> Result<CreateSpeechResponse, OpenAIError> {
+        let usage = UsageRecord::new(request.model.clone())
+            .with_tts_characters(request.input.chars().count() as u64);
         let openai_request =
-            OpenAIRequest::with_body(Method::POST, AUDIO_CREATE_SPEECH_URL.to_string(), request);
+            OpenAIRequest::with_body(Method::POST, AUDIO_CREATE_SPEECH_URL.to_string(), request)?;
 
         let response = self.client.send(openai_request).await;
+        let response = CreateSpeechResponse(response?.bytes().await?);
+
+        self.client.track_usage(usage);
 
-        Ok(CreateSpeechResponse(response?.bytes().await?))
+        Ok(response)
     }
 
     /// Transcribes audio into the input language.
@@ -41,12 +50,16 @@ impl<'a> AudioHandler<'a> {
         &self,
         request: CreateTranscriptionRequest,
     ) -> Result<AudioResponse, OpenAIError> {
+        let model = request.model.clone();
         let openai_request =
             OpenAIRequest::with_form(Method::POST, AUDIO_TRANSCRIPTION_URL.to_string(), request);
 
-        let response = self.client.send_form(openai_request).await;
+        let response = self.client.send(openai_request).await;
+        let response: AudioResponse = response?.json().await?;
 
-        Ok(response?.json().await?)
+        self.track_audio_usage(model, &response);
+
+        Ok(response)
     }
 
     /// Translates audio into English.
@@ -54,11 +67,27 @@ impl<'a> AudioHandler<'a> {
         &self,
         request: CreateTranslationRequest,
     ) -> Result<AudioResponse, OpenAIError> {
+        let model = request.model.clone();
         let openai_request =
             OpenAIRequest::with_form(Method::POST, AUDIO_TRANSLATION_URL.to_string(), request);
 
-        let response = self.client.send_form(openai_request).await;
+        let response = self.client.send(openai_request).await;
+        let response: AudioResponse = response?.json().await?;
+
+        self.track_audio_usage(model, &response);
+
+        Ok(response)
+    }
+
+    /// Records the duration of the audio, which is only known for the `verbose_json` response format.
+    /// Without it the call is recorded as incomplete, and left unpriced.
+    fn track_audio_usage(&self, model: AudioModel, response: &AudioResponse) {
+        let record = UsageRecord::new(model);
+        let record = match response.duration {
+            Some(duration) => record.with_audio_seconds(duration),
+            None => record.incomplete(),
+        };
 
-        Ok(response?.json().await?)
+        self.client.track_usage(record);
     }
 }
@@ -6,6 +6,10 @@ use serde::{Deserialize, Serialize};
 pub struct AudioResponse {
     /// The text that was used to generate the audio
     pub text: String,
+
+    /// The duration of the audio in seconds, only returned with the `verbose_json` response format
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub duration: Option<f64>,
 }
 
 #[cfg(test)]
@@ -16,6 +20,7 @@ mod tests {
     fn deserializes_response_correctly() {
         let audio_response = AudioResponse {
             text: "Hello, world!".to_string(),
+            duration: None,
         };
 
         assert_eq!(audio_response.text, "Hello, world!");
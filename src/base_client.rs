@@ -1,108 +1,270 @@
-use reqwest::{Client, Response};
-use reqwest_eventsource::{EventSource, RequestBuilderExt};
-use serde::Serialize;
-use url::Url;
+use std::{
+    collections::HashMap,
+    future::Future,
+    sync::{Arc, RwLock},
+    time::Duration,
+};
 
-use crate::{common::OpenAIError, OpenAIRequest};
+use reqwest::{header::HeaderMap, Response};
+
+use crate::common::{
+    capture_response_meta, estimate_tokens, ApiError, Middleware, ModelRegistry, OpenAIError,
+    OpenAIRequest, OpenAIStream, RateLimitInfo, RateLimiter, RequestBody, RetryPolicy,
+    StreamInspector, Transport, UsageRecord, UsageTracker,
+};
 
 /// A wrapper for request client
 #[derive(Debug, Clone)]
 pub(crate) struct BaseClient {
-    /// The request client
-    client: Client,
+    /// Sends the requests
+    transport: Arc<dyn Transport>,
+
+    /// How failed requests are retried
+    retry_policy: RetryPolicy,
+
+    /// Optional client-side rate limiter
+    rate_limiter: Option<RateLimiter>,
+
+    /// The latest rate limit information reported by OpenAI, per model
+    rate_limits: Arc<RwLock<HashMap<String, RateLimitInfo>>>,
+
+    /// Middlewares every request passes through
+    middlewares: Vec<Arc<dyn Middleware>>,
+
+    /// How long a stream may go without data before it fails
+    stream_idle_timeout: Option<Duration>,
+
+    /// Capabilities and prices of the models
+    model_registry: Arc<ModelRegistry>,
 
-    /// The host of the API
-    host: Url,
+    /// Optional tracker of the usage and costs
+    usage_tracker: Option<UsageTracker>,
 }
 
 impl BaseClient {
     /// Create a new client
-    pub(crate) fn new(client: Client, host: Url) -> Self {
-        Self { client, host }
+    pub(crate) fn new(
+        transport: Arc<dyn Transport>,
+        retry_policy: RetryPolicy,
+        rate_limiter: Option<RateLimiter>,
+        middlewares: Vec<Arc<dyn Middleware>>,
+        stream_idle_timeout: Option<Duration>,
+        model_registry: ModelRegistry,
+        usage_tracker: Option<UsageTracker>,
+    ) -> Self {
+        Self {
+            transport,
+            retry_policy,
+            rate_limiter,
+            rate_limits: Default::default(),
+            middlewares,
+            stream_idle_timeout,
+            model_registry: Arc::new(model_registry),
+            usage_tracker,
+        }
     }
 
-    /// Send a an API request without a body
-    pub(crate) async fn send<T>(&self, request: OpenAIRequest<T>) -> Result<Response, OpenAIError>
-    where
-        T: Serialize + std::fmt::Debug,
-    {
-        let url = self.host.join(&request.url)?;
-        let query_parameters = request.query_parameters.to_query();
-        let mut request_builder = self.client.request(request.method, url);
+    /// Returns the capabilities and prices of the models
+    pub(crate) fn model_registry(&self) -> &ModelRegistry {
+        &self.model_registry
+    }
 
-        if let Some(body) = request.body {
-            request_builder = request_builder.json(&body);
-        }
+    /// Returns the usage tracker, if installed
+    pub(crate) fn usage_tracker(&self) -> Option<&UsageTracker> {
+        self.usage_tracker.as_ref()
+    }
 
-        if !query_parameters.is_empty() {
-            request_builder = request_builder.query(&query_parameters);
+    /// Records the usage of a call, if a usage tracker is installed, pricing it with the model registry
+    pub(crate) fn track_usage(&self, record: UsageRecord) {
+        if let Some(usage_tracker) = &self.usage_tracker {
+            usage_tracker.record_with(record, &self.model_registry);
         }
+    }
 
-        let response = request_builder.send().await?;
-
-        if !response.status().is_success() {
-            let error = response.text().await?;
-            warn!(error = %error, "OpenAI responded with an error");
-
-            return Err(OpenAIError::Exception(error));
+    /// Records the usage of a call once per id, if a usage tracker is installed
+    pub(crate) fn track_usage_once(&self, id: &str, record: UsageRecord) {
+        if let Some(usage_tracker) = &self.usage_tracker {
+            usage_tracker.record_once_with(id, record, &self.model_registry);
         }
+    }
 
-        Ok(response)
+    /// Send an API request, retrying it according to the retry policy
+    pub(crate) async fn send(&self, request: OpenAIRequest) -> Result<Response, OpenAIError> {
+        self.send_with_retries(request, false).await
     }
 
-    /// Send a an API request with a form
-    pub(crate) async fn send_form<T>(
+    /// Send a body to create an event stream, recording the usage reported by its values.
+    ///
+    /// Only opening the stream is retried. Once it's open, failures end the stream,
+    /// since reopening it would send the request again. The usage is recorded as soon as the value
+    /// carrying it is read from the connection, so a stream dropped before its end isn't recorded.
+    pub(crate) async fn create_stream<T, F>(
         &self,
-        request: OpenAIRequest<T>,
-    ) -> Result<Response, OpenAIError>
+        request: OpenAIRequest,
+        usage: F,
+    ) -> Result<OpenAIStream<T>, OpenAIError>
     where
-        T: TryInto<reqwest::multipart::Form, Error = OpenAIError> + std::fmt::Debug,
+        T: serde::de::DeserializeOwned + std::fmt::Debug + Send + Sync + 'static,
+        F: Fn(&T) -> Option<UsageRecord> + Send + Sync + 'static,
     {
-        let url = self.host.join(&request.url)?;
-        let form = request
-            .body
-            .ok_or_else(|| OpenAIError::Exception("No body provided".to_string()))?
-            .try_into()?;
-
-        let response = self
-            .client
-            .request(request.method.clone(), url)
-            .multipart(form)
-            .send()
-            .await?;
+        let response = self.send_with_retries(request, true).await?;
 
-        if !response.status().is_success() {
-            let error = response.text().await?;
-            warn!(error = %error, "OpenAI responded with an error");
+        // The stream is read by a spawned task, outside of the labeled scope of the caller
+        let label = UsageTracker::current_label();
+        let inspector = self.usage_tracker.clone().map(|usage_tracker| {
+            let model_registry = self.model_registry.clone();
+            let inspector: StreamInspector<T> = Box::new(move |value: &T| {
+                if let Some(mut record) = usage(value) {
+                    if record.label.is_none() {
+                        record.label = label.clone();
+                    }
 
-            return Err(OpenAIError::Exception(error));
-        }
+                    usage_tracker.record_with(record, &model_registry);
+                }
+            });
 
-        Ok(response)
+            inspector
+        });
+
+        Ok(OpenAIStream::with_inspector(
+            response,
+            self.stream_idle_timeout,
+            inspector,
+        ))
     }
 
-    /// Send a body to create an event stream
-    pub(crate) async fn create_stream<T>(
+    async fn send_with_retries(
         &self,
-        request: OpenAIRequest<T>,
-    ) -> Result<EventSource, OpenAIError>
+        request: OpenAIRequest,
+        stream: bool,
+    ) -> Result<Response, OpenAIError> {
+        self.with_retries(|| async {
+            self.acquire_budget(&request).await;
+            self.execute(&request, stream).await
+        })
+        .await
+    }
+
+    /// Returns the latest rate limit information OpenAI reported for the model
+    pub(crate) fn rate_limit_info(&self, model: &str) -> Option<RateLimitInfo> {
+        self.rate_limits
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .get(model)
+            .cloned()
+    }
+
+    /// Waits for the rate limiter, if one is configured, to allow the request
+    async fn acquire_budget(&self, request: &OpenAIRequest) {
+        let (Some(rate_limiter), Some(model)) = (&self.rate_limiter, request.model()) else {
+            return;
+        };
+
+        let tokens = match &request.body {
+            RequestBody::Json(body) => estimate_tokens(body),
+            _ => 0,
+        };
+
+        rate_limiter.acquire(model, tokens).await;
+    }
+
+    /// Records the rate limit headers of a response
+    fn record_rate_limits(&self, request: &OpenAIRequest, headers: &HeaderMap) {
+        let (Some(model), Some(info)) = (request.model(), RateLimitInfo::from_headers(headers))
+        else {
+            return;
+        };
+
+        if let Some(rate_limiter) = &self.rate_limiter {
+            rate_limiter.update(model, &info);
+        }
+
+        let _ = self
+            .rate_limits
+            .write()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert(model.to_string(), info);
+    }
+
+    /// Runs the operation until it succeeds, fails with an error that should not be retried,
+    /// or the policy runs out of attempts.
+    async fn with_retries<F, Fut, R>(&self, mut operation: F) -> Result<R, OpenAIError>
     where
-        T: Serialize + std::fmt::Debug,
+        F: FnMut() -> Fut,
+        Fut: Future<Output = Result<R, OpenAIError>>,
     {
-        let url = self.host.join(&request.url)?;
-        let query_parameters = request.query_parameters.to_query();
-        let mut request_builder = self.client.request(request.method, url);
+        let mut attempt = 1;
+
+        loop {
+            match operation().await {
+                Err(error)
+                    if attempt < self.retry_policy.attempts()
+                        && self.retry_policy.should_retry(&error) =>
+                {
+                    let delay = self.retry_policy.delay(attempt, &error);
+                    warn!(attempt, ?delay, error = %error, "Retrying OpenAI request");
 
-        if let Some(body) = request.body {
-            request_builder = request_builder.json(&body);
+                    tokio::time::sleep(delay).await;
+                    attempt += 1;
+                }
+                result => return result,
+            }
         }
+    }
+
+    /// Sends a single attempt of the request through the middlewares
+    async fn execute(
+        &self,
+        request: &OpenAIRequest,
+        stream: bool,
+    ) -> Result<Response, OpenAIError> {
+        // Middlewares get their own copy, so changes don't pile up over retries
+        let mut request = request.try_clone().await?;
+        let mut passed = 0;
+        let mut cached_response = None;
 
-        if !query_parameters.is_empty() {
-            request_builder = request_builder.query(&query_parameters);
+        for middleware in &self.middlewares {
+            passed += 1;
+
+            if let Some(response) = middleware.on_request(&mut request).await? {
+                cached_response = Some(response);
+                break;
+            }
+        }
+
+        let mut result = match cached_response {
+            Some(response) => Ok(response),
+            None => self.send_request(&request, stream).await,
+        };
+
+        for middleware in self.middlewares[..passed].iter().rev() {
+            result = middleware.on_response(&request, result).await;
         }
 
-        let response = request_builder.eventsource()?;
+        let response = result?;
+        self.record_rate_limits(&request, response.headers());
+        capture_response_meta(&response);
+
+        if !response.status().is_success() {
+            let error = ApiError::from_response(response).await;
+            warn!(error = %error, "OpenAI responded with an error");
+
+            return Err(error.into());
+        }
 
         Ok(response)
     }
+
+    /// Hands the request over to the transport
+    async fn send_request(
+        &self,
+        request: &OpenAIRequest,
+        stream: bool,
+    ) -> Result<Response, OpenAIError> {
+        match &request.body {
+            RequestBody::Multipart(_) => self.transport.send_multipart(request).await,
+            _ if stream => self.transport.open_stream(request).await,
+            _ => self.transport.send_json(request).await,
+        }
+    }
 }
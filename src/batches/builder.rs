@@ -0,0 +1,175 @@
+use std::{collections::HashSet, path::Path};
+
+use crate::common::OpenAIError;
+
+use super::{BatchEndpoint, BatchInputLine, BatchRequest, CreateBatchRequest};
+
+/// Builds the input file of a batch out of typed requests, each with a unique `custom_id` to match
+/// its result with, see [`BatchHandler::submit`](super::BatchHandler::submit).
+///
+/// All requests of a batch go to the same endpoint, so a builder takes a single request type.
+#[derive(Debug, Clone)]
+pub struct BatchBuilder<R> {
+    requests: Vec<(String, R)>,
+    metadata: Vec<(String, String)>,
+}
+
+impl<R> Default for BatchBuilder<R> {
+    fn default() -> Self {
+        Self {
+            requests: vec![],
+            metadata: vec![],
+        }
+    }
+}
+
+impl<R> BatchBuilder<R>
+where
+    R: BatchRequest,
+{
+    /// Creates an empty batch.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a request with its `custom_id`.
+    pub fn with_request<S>(mut self, custom_id: S, request: R) -> Self
+    where
+        S: Into<String>,
+    {
+        self.push(custom_id, request);
+        self
+    }
+
+    /// Adds a metadata entry to the batch.
+    pub fn with_metadata<K, V>(mut self, key: K, value: V) -> Self
+    where
+        K: Into<String>,
+        V: Into<String>,
+    {
+        self.metadata.push((key.into(), value.into()));
+        self
+    }
+
+    /// Adds a request with its `custom_id`.
+    pub fn push<S>(&mut self, custom_id: S, request: R)
+    where
+        S: Into<String>,
+    {
+        self.requests.push((custom_id.into(), request));
+    }
+
+    /// The number of requests in the batch.
+    pub fn len(&self) -> usize {
+        self.requests.len()
+    }
+
+    /// Whether the batch has no requests.
+    pub fn is_empty(&self) -> bool {
+        self.requests.is_empty()
+    }
+
+    /// The endpoint of the requests.
+    pub fn endpoint(&self) -> BatchEndpoint {
+        R::ENDPOINT
+    }
+
+    /// Writes the requests as a JSONL input file. Fails if a `custom_id` is used more than once.
+    pub fn to_jsonl(&self) -> Result<String, OpenAIError> {
+        let mut custom_ids = HashSet::new();
+        let mut jsonl = String::new();
+
+        for (custom_id, request) in &self.requests {
+            if !custom_ids.insert(custom_id.as_str()) {
+                return Err(OpenAIError::Exception(format!(
+                    "Duplicate batch custom_id: {}",
+                    custom_id
+                )));
+            }
+
+            let line = BatchInputLine {
+                custom_id: custom_id.clone(),
+                method: "POST".to_string(),
+                url: R::ENDPOINT.as_str().to_string(),
+                body: request,
+            };
+
+            jsonl.push_str(&serde_json::to_string(&line)?);
+            jsonl.push('\n');
+        }
+
+        Ok(jsonl)
+    }
+
+    /// Writes the JSONL input file to the path.
+    pub async fn write_jsonl<P>(&self, path: P) -> Result<(), OpenAIError>
+    where
+        P: AsRef<Path>,
+    {
+        tokio::fs::write(path, self.to_jsonl()?).await?;
+
+        Ok(())
+    }
+
+    /// Creates the request to create the batch, once the input file is uploaded.
+    pub fn to_create_request<S>(&self, input_file_id: S) -> CreateBatchRequest
+    where
+        S: Into<String>,
+    {
+        self.metadata.iter().fold(
+            CreateBatchRequest::new(input_file_id, R::ENDPOINT),
+            |request, (key, value)| request.with_metadata(key, value),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::{json, Value};
+
+    use super::*;
+    use crate::embeddings::CreateEmbeddingsRequest;
+
+    #[test]
+    fn writes_jsonl_correctly() {
+        let batch = BatchBuilder::new()
+            .with_request("doc-1", CreateEmbeddingsRequest::from_single_input("Hello"))
+            .with_request("doc-2", CreateEmbeddingsRequest::from_single_input("World"))
+            .with_metadata("job", "nightly");
+
+        let jsonl = batch.to_jsonl().unwrap();
+        let lines: Vec<Value> = jsonl
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+
+        assert_eq!(lines.len(), 2);
+        assert_eq!(
+            lines[0],
+            json!({
+                "custom_id": "doc-1",
+                "method": "POST",
+                "url": "/v1/embeddings",
+                "body": {
+                    "input": "Hello",
+                    "model": "text-embedding-ada-002",
+                    "encoding_format": "float",
+                },
+            })
+        );
+
+        let request = serde_json::to_value(batch.to_create_request("file-abc123")).unwrap();
+        assert_eq!(
+            request,
+            json!({
+                "input_file_id": "file-abc123",
+                "endpoint": "/v1/embeddings",
+                "completion_window": "24h",
+                "metadata": { "job": "nightly" },
+            })
+        );
+
+        let duplicate = batch.with_request("doc-1", CreateEmbeddingsRequest::default());
+        assert!(duplicate.to_jsonl().is_err());
+    }
+}
@@ -0,0 +1,129 @@
+use futures_util::Stream;
+use reqwest::Method;
+
+use crate::{
+    base_client::BaseClient,
+    common::{paginate, CursorParams, OpenAIError, OpenAIFile, PaginatedParams},
+    files::{FileHandler, Purpose, UploadFileRequest},
+    OpenAIRequest,
+};
+
+use super::{
+    parse_batch_results, Batch, BatchBuilder, BatchListResponse, BatchRequest, BatchResults,
+    CreateBatchRequest,
+};
+
+const BATCHES_URL: &str = "/v1/batches";
+const BATCH_INPUT_FILE_NAME: &str = "batch_input.jsonl";
+
+/// Batch handler for OpenAI API
+#[derive(Debug, Clone)]
+pub struct BatchHandler<'a> {
+    client: &'a BaseClient,
+}
+
+impl<'a> BatchHandler<'a> {
+    pub(crate) fn new(client: &'a BaseClient) -> Self {
+        Self { client }
+    }
+
+    /// Creates and executes a batch from an uploaded file of requests.
+    pub async fn create_batch(&self, request: CreateBatchRequest) -> Result<Batch, OpenAIError> {
+        let openai_request =
+            OpenAIRequest::with_body(Method::POST, BATCHES_URL.to_string(), request)?;
+
+        let response = self.client.send(openai_request).await;
+
+        Ok(response?.json().await?)
+    }
+
+    /// Retrieves a batch.
+    pub async fn retrieve_batch<S: Into<String>>(&self, batch_id: S) -> Result<Batch, OpenAIError> {
+        let url = format!("{}/{}", BATCHES_URL, batch_id.into());
+        let openai_request = OpenAIRequest::new(Method::GET, url);
+
+        let response = self.client.send(openai_request).await;
+
+        Ok(response?.json().await?)
+    }
+
+    /// List your organization's batches.
+    pub async fn list_batches(
+        &self,
+        parameters: CursorParams,
+    ) -> Result<BatchListResponse, OpenAIError> {
+        let openai_request = OpenAIRequest::new(Method::GET, BATCHES_URL.to_string())
+            .with_query_parameters(parameters);
+
+        let response = self.client.send(openai_request).await;
+
+        Ok(response?.json().await?)
+    }
+
+    /// Streams your organization's batches, fetching the pages lazily.
+    /// Stops after `max_items`, if set.
+    pub fn list_batches_auto_paginate(
+        &self,
+        parameters: CursorParams,
+        max_items: Option<usize>,
+    ) -> impl Stream<Item = Result<Batch, OpenAIError>> + 'a {
+        let client = self.client;
+
+        paginate(max_items, move |after| {
+            let handler = Self::new(client);
+            let parameters = parameters.with_after(after);
+
+            async move { handler.list_batches(parameters).await }
+        })
+    }
+
+    /// Cancels an in-progress batch. The batch will be in status `cancelling` for up to 10 minutes,
+    /// before changing to `cancelled`, where it will have partial results (if any) available in the output file.
+    pub async fn cancel_batch<S: Into<String>>(&self, batch_id: S) -> Result<Batch, OpenAIError> {
+        let url = format!("{}/{}/cancel", BATCHES_URL, batch_id.into());
+        let openai_request = OpenAIRequest::new(Method::POST, url);
+
+        let response = self.client.send(openai_request).await;
+
+        Ok(response?.json().await?)
+    }
+
+    /// Uploads the input file of the batch from memory with the purpose `batch`, and creates the batch.
+    /// The file can be kept with [`BatchBuilder::write_jsonl`].
+    pub async fn submit<R>(&self, batch: &BatchBuilder<R>) -> Result<Batch, OpenAIError>
+    where
+        R: BatchRequest,
+    {
+        let file = FileHandler::new(self.client)
+            .upload_file(UploadFileRequest {
+                file: OpenAIFile::from_bytes(BATCH_INPUT_FILE_NAME, batch.to_jsonl()?),
+                purpose: Purpose::Batch,
+            })
+            .await?;
+
+        self.create_batch(batch.to_create_request(file.id)).await
+    }
+
+    /// Downloads the output and error files of the batch, and parses them into the results of its requests,
+    /// keyed by `custom_id`. `R` is the request type of the batch, e.g. [`CreateChatCompletionRequest`](crate::chat::CreateChatCompletionRequest),
+    /// whose [response](BatchRequest::Response) the results are parsed into.
+    ///
+    /// Requests that haven't finished yet are missing, so this should be called once the batch status is terminal.
+    pub async fn results<R>(&self, batch: &Batch) -> Result<BatchResults<R::Response>, OpenAIError>
+    where
+        R: BatchRequest,
+    {
+        let files = FileHandler::new(self.client);
+        let mut results = BatchResults::new();
+
+        for file_id in [&batch.output_file_id, &batch.error_file_id]
+            .into_iter()
+            .flatten()
+        {
+            let content = files.files_retrieve_content(file_id.as_str()).await?;
+            results.extend(parse_batch_results(&content)?);
+        }
+
+        Ok(results)
+    }
+}
@@ -0,0 +1,14 @@
+//! Create large batches of API requests for asynchronous processing. The Batch API returns completions within 24 hours
+//! for a 50% discount. Related guide: [Batch](https://platform.openai.com/docs/guides/batch)
+
+mod builder;
+mod handler;
+mod request;
+mod response;
+mod results;
+
+pub use self::builder::*;
+pub use self::handler::*;
+pub use self::request::*;
+pub use self::response::*;
+pub use self::results::*;
@@ -0,0 +1,122 @@
+use std::collections::HashMap;
+
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+
+/// POST https://api.openai.com/v1/batches
+/// Creates and executes a batch from an uploaded file of requests
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct CreateBatchRequest {
+    /// The ID of an uploaded file that contains requests for the new batch.
+    ///
+    /// The file must be a JSONL file uploaded with the purpose `batch`, and can contain up to 50,000 requests.
+    pub input_file_id: String,
+
+    /// The endpoint to be used for all requests in the batch.
+    pub endpoint: BatchEndpoint,
+
+    /// The time frame within which the batch should be processed. Currently only `24h` is supported.
+    pub completion_window: String,
+
+    /// Set of 16 key-value pairs that can be attached to the batch.
+    #[serde(skip_serializing_if = "HashMap::is_empty")]
+    pub metadata: HashMap<String, String>,
+}
+
+impl CreateBatchRequest {
+    /// Creates a new [`CreateBatchRequest`] for the uploaded file, processed within 24 hours.
+    pub fn new<S>(input_file_id: S, endpoint: BatchEndpoint) -> Self
+    where
+        S: Into<String>,
+    {
+        Self {
+            input_file_id: input_file_id.into(),
+            endpoint,
+            completion_window: "24h".to_string(),
+            ..Default::default()
+        }
+    }
+
+    /// Adds a metadata entry.
+    pub fn with_metadata<K, V>(mut self, key: K, value: V) -> Self
+    where
+        K: Into<String>,
+        V: Into<String>,
+    {
+        let _ = self.metadata.insert(key.into(), value.into());
+        self
+    }
+}
+
+/// The endpoints supported by the Batch API
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum BatchEndpoint {
+    /// Chat completions
+    #[default]
+    #[serde(rename = "/v1/chat/completions")]
+    ChatCompletions,
+
+    /// Embeddings
+    #[serde(rename = "/v1/embeddings")]
+    Embeddings,
+
+    /// Legacy completions
+    #[serde(rename = "/v1/completions")]
+    Completions,
+}
+
+impl BatchEndpoint {
+    /// The URL of the endpoint
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::ChatCompletions => "/v1/chat/completions",
+            Self::Embeddings => "/v1/embeddings",
+            Self::Completions => "/v1/completions",
+        }
+    }
+}
+
+/// A request that can be sent through the Batch API, along with the response it's answered with.
+pub trait BatchRequest: Serialize {
+    /// The response of the endpoint
+    type Response: DeserializeOwned;
+
+    /// The endpoint the request is sent to
+    const ENDPOINT: BatchEndpoint;
+}
+
+#[cfg(feature = "chat")]
+impl BatchRequest for crate::chat::CreateChatCompletionRequest {
+    type Response = crate::chat::ChatCompletionResponse;
+
+    const ENDPOINT: BatchEndpoint = BatchEndpoint::ChatCompletions;
+}
+
+#[cfg(feature = "embeddings")]
+impl BatchRequest for crate::embeddings::CreateEmbeddingsRequest {
+    type Response = crate::embeddings::EmbeddingResponse;
+
+    const ENDPOINT: BatchEndpoint = BatchEndpoint::Embeddings;
+}
+
+#[cfg(feature = "completions")]
+impl BatchRequest for crate::completions::CreateCompletionRequest {
+    type Response = crate::completions::CompletionResponse;
+
+    const ENDPOINT: BatchEndpoint = BatchEndpoint::Completions;
+}
+
+/// A line of a batch input file
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct BatchInputLine<T> {
+    /// A developer-provided per-request id that will be used to match outputs to inputs. Must be unique for each request in a batch.
+    pub custom_id: String,
+
+    /// The HTTP method to be used for the request. Currently only `POST` is supported.
+    pub method: String,
+
+    /// The OpenAI API relative URL to be used for the request.
+    pub url: String,
+
+    /// The body of the request
+    pub body: T,
+}
@@ -0,0 +1,224 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::common::{ListItem, ListResponse};
+
+use super::BatchEndpoint;
+
+/// Returns a list of the organization's batches.
+pub type BatchListResponse = ListResponse<Batch>;
+
+/// A batch of requests processed asynchronously.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Batch {
+    /// The batch identifier, which can be referenced in the API endpoints.
+    pub id: String,
+
+    /// The object type, which is always `batch`.
+    pub object: String,
+
+    /// The OpenAI API endpoint used by the batch.
+    pub endpoint: BatchEndpoint,
+
+    /// The errors of the input file, if it failed validation.
+    #[serde(default)]
+    pub errors: Option<BatchErrors>,
+
+    /// The ID of the input file for the batch.
+    pub input_file_id: String,
+
+    /// The time frame within which the batch should be processed.
+    pub completion_window: String,
+
+    /// The current status of the batch.
+    pub status: BatchStatus,
+
+    /// The ID of the file containing the outputs of successfully executed requests.
+    #[serde(default)]
+    pub output_file_id: Option<String>,
+
+    /// The ID of the file containing the outputs of requests with errors.
+    #[serde(default)]
+    pub error_file_id: Option<String>,
+
+    /// The Unix timestamp (in seconds) for when the batch was created.
+    pub created_at: u64,
+
+    /// The Unix timestamp (in seconds) for when the batch started processing.
+    #[serde(default)]
+    pub in_progress_at: Option<u64>,
+
+    /// The Unix timestamp (in seconds) for when the batch will expire.
+    #[serde(default)]
+    pub expires_at: Option<u64>,
+
+    /// The Unix timestamp (in seconds) for when the batch started finalizing.
+    #[serde(default)]
+    pub finalizing_at: Option<u64>,
+
+    /// The Unix timestamp (in seconds) for when the batch was completed.
+    #[serde(default)]
+    pub completed_at: Option<u64>,
+
+    /// The Unix timestamp (in seconds) for when the batch failed.
+    #[serde(default)]
+    pub failed_at: Option<u64>,
+
+    /// The Unix timestamp (in seconds) for when the batch expired.
+    #[serde(default)]
+    pub expired_at: Option<u64>,
+
+    /// The Unix timestamp (in seconds) for when the batch started cancelling.
+    #[serde(default)]
+    pub cancelling_at: Option<u64>,
+
+    /// The Unix timestamp (in seconds) for when the batch was cancelled.
+    #[serde(default)]
+    pub cancelled_at: Option<u64>,
+
+    /// The request counts for different statuses within the batch.
+    #[serde(default)]
+    pub request_counts: BatchRequestCounts,
+
+    /// Set of 16 key-value pairs attached to the batch.
+    #[serde(default)]
+    pub metadata: Option<HashMap<String, String>>,
+}
+
+impl ListItem for Batch {
+    fn id(&self) -> &str {
+        &self.id
+    }
+}
+
+/// The status of a batch
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BatchStatus {
+    /// The input file is being validated before the batch can begin
+    Validating,
+
+    /// The input file has failed the validation process
+    Failed,
+
+    /// The input file was successfully validated and the batch is currently being run
+    InProgress,
+
+    /// The batch has completed and the results are being prepared
+    Finalizing,
+
+    /// The batch has been completed and the results are ready
+    Completed,
+
+    /// The batch was not able to be completed within the 24-hour time window
+    Expired,
+
+    /// The batch is being cancelled (may take up to 10 minutes)
+    Cancelling,
+
+    /// The batch was cancelled
+    Cancelled,
+}
+
+impl BatchStatus {
+    /// Whether the batch won't change anymore. Expired and cancelled batches may still have results
+    /// for the requests completed in time.
+    pub fn is_terminal(&self) -> bool {
+        matches!(
+            self,
+            Self::Failed | Self::Completed | Self::Expired | Self::Cancelled
+        )
+    }
+}
+
+/// The request counts for different statuses within a batch.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct BatchRequestCounts {
+    /// Total number of requests in the batch.
+    pub total: u32,
+
+    /// Number of requests that have been completed successfully.
+    pub completed: u32,
+
+    /// Number of requests that have failed.
+    pub failed: u32,
+}
+
+/// The errors of a batch input file.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct BatchErrors {
+    /// The object type, which is always `list`.
+    pub object: String,
+
+    /// The errors
+    #[serde(default)]
+    pub data: Vec<BatchError>,
+}
+
+/// An error of a batch input file.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct BatchError {
+    /// An error code identifying the error type.
+    #[serde(default)]
+    pub code: Option<String>,
+
+    /// A human-readable message providing more details about the error.
+    #[serde(default)]
+    pub message: Option<String>,
+
+    /// The name of the parameter that caused the error, if applicable.
+    #[serde(default)]
+    pub param: Option<String>,
+
+    /// The line number of the input file where the error occurred, if applicable.
+    #[serde(default)]
+    pub line: Option<u32>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn deserializes_batch_correctly() {
+        let json = json!({
+          "id": "batch_abc123",
+          "object": "batch",
+          "endpoint": "/v1/chat/completions",
+          "errors": null,
+          "input_file_id": "file-abc123",
+          "completion_window": "24h",
+          "status": "completed",
+          "output_file_id": "file-cvaTdG",
+          "error_file_id": "file-HOWS94",
+          "created_at": 1711471533,
+          "in_progress_at": 1711471538,
+          "expires_at": 1711557933,
+          "finalizing_at": 1711493133,
+          "completed_at": 1711493163,
+          "failed_at": null,
+          "expired_at": null,
+          "cancelling_at": null,
+          "cancelled_at": null,
+          "request_counts": {
+            "total": 100,
+            "completed": 95,
+            "failed": 5
+          },
+          "metadata": {
+            "batch_description": "Nightly eval job",
+          }
+        });
+
+        let batch: Batch = serde_json::from_value(json).unwrap();
+
+        assert_eq!(batch.endpoint, BatchEndpoint::ChatCompletions);
+        assert_eq!(batch.status, BatchStatus::Completed);
+        assert!(batch.status.is_terminal());
+        assert_eq!(batch.output_file_id.as_deref(), Some("file-cvaTdG"));
+        assert_eq!(batch.request_counts.failed, 5);
+        assert_eq!(batch.failed_at, None);
+    }
+}
@@ -0,0 +1,132 @@
+use std::collections::HashMap;
+
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::common::OpenAIError;
+
+/// A line of a batch output or error file
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct BatchOutputLine {
+    /// The ID of the batch request
+    pub id: String,
+
+    /// The custom ID of the request, as set in the input file
+    pub custom_id: String,
+
+    /// The response of the endpoint, `None` if the request couldn't be sent
+    #[serde(default)]
+    pub response: Option<BatchOutputResponse>,
+
+    /// The reason the request couldn't be sent
+    #[serde(default)]
+    pub error: Option<BatchItemError>,
+}
+
+/// The response of the endpoint to a batch request
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct BatchOutputResponse {
+    /// The HTTP status code of the response
+    pub status_code: u16,
+
+    /// An unique identifier for the OpenAI API request
+    #[serde(default)]
+    pub request_id: String,
+
+    /// The body of the response, an error object for unsuccessful status codes
+    pub body: Value,
+}
+
+/// The error of a failed batch request
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct BatchItemError {
+    /// The HTTP status code of the response, if the request was sent
+    #[serde(default)]
+    pub status_code: Option<u16>,
+
+    /// A machine-readable error code
+    #[serde(default)]
+    pub code: Option<String>,
+
+    /// A human-readable error message
+    #[serde(default)]
+    pub message: String,
+}
+
+/// The results of a batch keyed by `custom_id`, with the typed responses of successful requests
+/// and the errors of failed ones.
+pub type BatchResults<T> = HashMap<String, Result<T, BatchItemError>>;
+
+impl BatchOutputLine {
+    /// Converts the line into the typed response, or the error of the request.
+    pub fn into_result<T>(self) -> Result<Result<T, BatchItemError>, OpenAIError>
+    where
+        T: DeserializeOwned,
+    {
+        let Some(response) = self.response else {
+            return Ok(Err(self.error.unwrap_or_default()));
+        };
+
+        if !(200..300).contains(&response.status_code) {
+            let error = &response.body["error"];
+
+            return Ok(Err(BatchItemError {
+                status_code: Some(response.status_code),
+                code: error["code"].as_str().map(ToString::to_string),
+                message: error["message"].as_str().unwrap_or_default().to_string(),
+            }));
+        }
+
+        Ok(Ok(serde_json::from_value(response.body)?))
+    }
+}
+
+/// Parses the content of batch output and error files into the results of the requests, keyed by `custom_id`.
+/// Blank lines are skipped.
+pub fn parse_batch_results<T>(jsonl: &str) -> Result<BatchResults<T>, OpenAIError>
+where
+    T: DeserializeOwned,
+{
+    let mut results = HashMap::new();
+
+    for line in jsonl.lines().filter(|line| !line.trim().is_empty()) {
+        let line: BatchOutputLine = serde_json::from_str(line)?;
+        let custom_id = line.custom_id.clone();
+
+        let _ = results.insert(custom_id, line.into_result()?);
+    }
+
+    Ok(results)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::embeddings::EmbeddingResponse;
+
+    #[test]
+    fn parses_results_correctly() {
+        let jsonl = r#"
+{"id": "batch_req_1", "custom_id": "doc-1", "response": {"status_code": 200, "request_id": "req_1", "body": {"object": "list", "data": [{"object": "embedding", "index": 0, "embedding": [0.1, 0.2]}], "model": "text-embedding-3-small", "usage": {"prompt_tokens": 3, "total_tokens": 3}}}, "error": null}
+{"id": "batch_req_2", "custom_id": "doc-2", "response": {"status_code": 400, "request_id": "req_2", "body": {"error": {"message": "Invalid input", "type": "invalid_request_error", "code": "invalid_input"}}}, "error": null}
+{"id": "batch_req_3", "custom_id": "doc-3", "response": null, "error": {"code": "batch_expired", "message": "This request could not be executed before the completion window expired."}}
+"#;
+
+        let results = parse_batch_results::<EmbeddingResponse>(jsonl).unwrap();
+
+        assert_eq!(results.len(), 3);
+        assert_eq!(
+            results["doc-1"].as_ref().unwrap().data[0].embedding,
+            vec![0.1, 0.2]
+        );
+
+        let error = results["doc-2"].as_ref().unwrap_err();
+        assert_eq!(error.status_code, Some(400));
+        assert_eq!(error.code.as_deref(), Some("invalid_input"));
+        assert_eq!(error.message, "Invalid input");
+
+        let error = results["doc-3"].as_ref().unwrap_err();
+        assert_eq!(error.status_code, None);
+        assert_eq!(error.code.as_deref(), Some("batch_expired"));
+    }
+}
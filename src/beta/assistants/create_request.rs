@@ -2,7 +2,10 @@ use std::collections::BTreeMap;
 
 use serde::{Deserialize, Serialize};
 
-use crate::{assistants_common::AssistantTool, common::ChatModel};
+use crate::{
+    assistants_common::AssistantTool,
+    common::{ChatModel, ModelId},
+};
 
 #[derive(Debug, Default, Clone, Serialize, Deserialize)]
 #[serde(default)]
@@ -42,9 +45,13 @@ pub struct CreateAssistantRequest {
 
 impl CreateAssistantRequest {
     /// Create a new assistant request.
-    pub fn with_instructions<S: Into<String>>(model: ChatModel, instructions: S) -> Self {
+    pub fn with_instructions<M, S>(model: M, instructions: S) -> Self
+    where
+        M: Into<ModelId>,
+        S: Into<String>,
+    {
         Self {
-            model,
+            model: model.into().into(),
             instructions: Some(instructions.into()),
             ..Default::default()
         }
@@ -76,8 +83,7 @@ mod test {
             "description": "Test Assistant Description",
             "instructions": "Test Assistant Instructions",
             "tools": [{"type": "code_interpreter"}],
-            "file_ids": ["file-id"],
-            "metadata": {}
+            "file_ids": ["file-id"]
         });
         assert_eq!(request_json, json.to_string());
     }
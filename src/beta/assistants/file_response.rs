@@ -1,14 +1,9 @@
 use serde::{Deserialize, Serialize};
 
-/// A list of [Files](https://platform.openai.com/docs/api-reference/files) attached to an `assistant`.
-#[derive(Debug, Default, PartialEq, Clone, Serialize, Deserialize)]
-pub struct ListAssistantsFilesResponse {
-    /// The object type, which is always list.
-    pub object: String,
+use crate::common::{ListItem, ListResponse};
 
-    /// A list of [`AssistantsResponse`].
-    pub data: Vec<AssistantFileResponse>,
-}
+/// A list of [Files](https://platform.openai.com/docs/api-reference/files) attached to an `assistant`.
+pub type ListAssistantsFilesResponse = ListResponse<AssistantFileResponse>;
 
 /// A list of [Files](https://platform.openai.com/docs/api-reference/files) attached to an `assistant`.
 #[derive(Debug, Default, PartialEq, Clone, Serialize, Deserialize)]
@@ -26,6 +21,12 @@ pub struct AssistantFileResponse {
     pub assistant_id: String,
 }
 
+impl ListItem for AssistantFileResponse {
+    fn id(&self) -> &str {
+        &self.id
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
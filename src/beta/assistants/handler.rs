@@ -1,8 +1,11 @@
+use futures_util::Stream;
 use reqwest::Method;
 
 use crate::{
-    assistants_common::DeletionStatus, base_client::BaseClient, common::OpenAIError,
-    OpenAIQueryParameters, OpenAIRequest,
+    assistants_common::DeletionStatus,
+    base_client::BaseClient,
+    common::{paginate, ListParams, OpenAIError, PaginatedParams},
+    OpenAIRequest,
 };
 
 use super::{
@@ -29,7 +32,7 @@ impl<'a> AssistantsHandler<'a> {
         request: CreateAssistantRequest,
     ) -> Result<AssistantsResponse, OpenAIError> {
         let openai_request =
-            OpenAIRequest::with_body(Method::POST, ASSISTANTS_URL.to_string(), request);
+            OpenAIRequest::with_body(Method::POST, ASSISTANTS_URL.to_string(), request)?;
 
         let response = self.client.send(openai_request).await;
 
@@ -44,7 +47,7 @@ impl<'a> AssistantsHandler<'a> {
         request: CreateAssistantFileRequest,
     ) -> Result<AssistantFileResponse, OpenAIError> {
         let url = format!("{}/{}/files", ASSISTANTS_URL, assistant_id.into());
-        let openai_request = OpenAIRequest::with_body(Method::POST, url, request);
+        let openai_request = OpenAIRequest::with_body(Method::POST, url, request)?;
 
         let response = self.client.send(openai_request).await;
 
@@ -54,9 +57,9 @@ impl<'a> AssistantsHandler<'a> {
     /// Returns a list of assistants.
     pub async fn list_assistants(
         &self,
-        parameters: OpenAIQueryParameters,
+        parameters: ListParams,
     ) -> Result<ListAssistantsResponse, OpenAIError> {
-        let openai_request = OpenAIRequest::<()>::new(Method::GET, ASSISTANTS_URL.to_string())
+        let openai_request = OpenAIRequest::new(Method::GET, ASSISTANTS_URL.to_string())
             .with_query_parameters(parameters);
 
         let response = self.client.send(openai_request).await;
@@ -64,27 +67,63 @@ impl<'a> AssistantsHandler<'a> {
         Ok(response?.json().await?)
     }
 
+    /// Streams the assistants, fetching the pages lazily.
+    /// Stops after `max_items`, if set.
+    pub fn list_assistants_auto_paginate(
+        &self,
+        parameters: ListParams,
+        max_items: Option<usize>,
+    ) -> impl Stream<Item = Result<AssistantsResponse, OpenAIError>> + 'a {
+        let client = self.client;
+
+        paginate(max_items, move |after| {
+            let handler = Self::new(client);
+            let parameters = parameters.with_after(after);
+
+            async move { handler.list_assistants(parameters).await }
+        })
+    }
+
     /// Returns a list of assistant files.
     pub async fn list_assistants_file<S: Into<String>>(
         &self,
         assistant_id: S,
-        parameters: OpenAIQueryParameters,
+        parameters: ListParams,
     ) -> Result<ListAssistantsFilesResponse, OpenAIError> {
         let url = format!("{}/{}/files", ASSISTANTS_URL, assistant_id.into());
-        let openai_request =
-            OpenAIRequest::<()>::new(Method::GET, url).with_query_parameters(parameters);
+        let openai_request = OpenAIRequest::new(Method::GET, url).with_query_parameters(parameters);
 
         let response = self.client.send(openai_request).await;
         Ok(response?.json().await?)
     }
 
+    /// Streams the files of an assistant, fetching the pages lazily.
+    /// Stops after `max_items`, if set.
+    pub fn list_assistants_file_auto_paginate<S: Into<String>>(
+        &self,
+        assistant_id: S,
+        parameters: ListParams,
+        max_items: Option<usize>,
+    ) -> impl Stream<Item = Result<AssistantFileResponse, OpenAIError>> + 'a {
+        let client = self.client;
+        let assistant_id = assistant_id.into();
+
+        paginate(max_items, move |after| {
+            let handler = Self::new(client);
+            let assistant_id = assistant_id.clone();
+            let parameters = parameters.with_after(after);
+
+            async move { handler.list_assistants_file(assistant_id, parameters).await }
+        })
+    }
+
     /// Retrieves an assistant.
     pub async fn retrieve_assistant<S: Into<String>>(
         &self,
         assistant_id: S,
     ) -> Result<AssistantsResponse, OpenAIError> {
         let url = format!("{}/{}", ASSISTANTS_URL, assistant_id.into());
-        let openai_request = OpenAIRequest::<()>::new(Method::GET, url);
+        let openai_request = OpenAIRequest::new(Method::GET, url);
 
         let response = self.client.send(openai_request).await;
 
@@ -103,7 +142,7 @@ impl<'a> AssistantsHandler<'a> {
             assistant_id.into(),
             file_id.into()
         );
-        let openai_request = OpenAIRequest::<()>::new(Method::GET, url);
+        let openai_request = OpenAIRequest::new(Method::GET, url);
 
         let response = self.client.send(openai_request).await;
 
@@ -117,7 +156,7 @@ impl<'a> AssistantsHandler<'a> {
         request: ModifyAssistantRequest,
     ) -> Result<AssistantsResponse, OpenAIError> {
         let url = format!("{}/{}", ASSISTANTS_URL, assistant_id.into());
-        let openai_request = OpenAIRequest::with_body(Method::POST, url, request);
+        let openai_request = OpenAIRequest::with_body(Method::POST, url, request)?;
 
         let response = self.client.send(openai_request).await;
 
@@ -130,7 +169,7 @@ impl<'a> AssistantsHandler<'a> {
         assistant_id: S,
     ) -> Result<DeletionStatus, OpenAIError> {
         let url = format!("{}/{}", ASSISTANTS_URL, assistant_id.into());
-        let openai_request = OpenAIRequest::<()>::new(Method::DELETE, url);
+        let openai_request = OpenAIRequest::new(Method::DELETE, url);
 
         let response = self.client.send(openai_request).await;
 
@@ -149,7 +188,7 @@ impl<'a> AssistantsHandler<'a> {
             assistant_id.into(),
             file_id.into()
         );
-        let openai_request = OpenAIRequest::<()>::new(Method::DELETE, url);
+        let openai_request = OpenAIRequest::new(Method::DELETE, url);
 
         let response = self.client.send(openai_request).await;
 
@@ -2,17 +2,13 @@ use std::collections::BTreeMap;
 
 use serde::{Deserialize, Serialize};
 
-use crate::{assistants_common::AssistantTool, common::ChatModel};
+use crate::{
+    assistants_common::AssistantTool,
+    common::{ChatModel, ListItem, ListResponse},
+};
 
 /// A list of assistants.
-#[derive(Debug, Default, PartialEq, Clone, Serialize, Deserialize)]
-pub struct ListAssistantsResponse {
-    /// The object type, which is always list.
-    pub object: String,
-
-    /// A list of [`AssistantsResponse`].
-    pub data: Vec<AssistantsResponse>,
-}
+pub type ListAssistantsResponse = ListResponse<AssistantsResponse>;
 
 /// Represents an `assistant` that can call the model and use tools.
 #[derive(Debug, Default, PartialEq, Clone, Serialize, Deserialize)]
@@ -52,6 +48,12 @@ pub struct AssistantsResponse {
     pub metadata: BTreeMap<String, String>,
 }
 
+impl ListItem for AssistantsResponse {
+    fn id(&self) -> &str {
+        &self.id
+    }
+}
+
 #[cfg(test)]
 mod test {
     use serde_json::json;
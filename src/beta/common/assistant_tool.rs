@@ -1,5 +1,7 @@
 use serde::{Deserialize, Serialize};
 
+use crate::ToolSchema;
+
 /// A list of tool enabled on the assistant. There can be a maximum of 128 tools per assistant.
 /// Tools can be of types `code_interpreter`, `retrieval`, or `function`.
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -74,4 +76,26 @@ pub struct FunctionObject {
     /// Omitting `parameters` defines a function with an empty parameter list.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub parameters: Option<serde_json::Value>,
+
+    /// Whether the model must follow the schema of the parameters exactly. Requires a schema
+    /// meeting the constraints of the strict mode, see [`strict_schema`](crate::strict_schema).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    pub strict: Option<bool>,
+}
+
+impl FunctionObject {
+    /// Creates a strict function whose parameters are described by the JSON Schema of `T`.
+    pub fn from_schema<T, S>(name: S, description: Option<String>) -> Self
+    where
+        T: ToolSchema,
+        S: Into<String>,
+    {
+        Self {
+            description,
+            name: name.into(),
+            parameters: Some(T::strict_json_schema()),
+            strict: Some(true),
+        }
+    }
 }
@@ -7,7 +7,7 @@ pub type CreateMessageRequest = ThreadMessage;
 mod tests {
     use serde_json::json;
 
-    use crate::ThreadMessageRole;
+    use crate::assistants_common::ThreadMessageRole;
 
     use super::*;
 
@@ -1,14 +1,9 @@
 use serde::{Deserialize, Serialize};
 
-/// A list of files attached to a `message`.
-#[derive(Debug, Default, PartialEq, Clone, Serialize, Deserialize)]
-pub struct ListMessagesFileResponse {
-    /// The object type, which is always `list`.
-    pub object: String,
+use crate::common::{ListItem, ListResponse};
 
-    /// A list of [`MessagesResponse`]
-    pub data: Vec<MessagesFileResponse>,
-}
+/// A list of files attached to a `message`.
+pub type ListMessagesFileResponse = ListResponse<MessagesFileResponse>;
 
 /// A list of files attached to a `message`.
 #[derive(Debug, Default, PartialEq, Clone, Serialize, Deserialize)]
@@ -27,6 +22,12 @@ pub struct MessagesFileResponse {
     pub message_id: String,
 }
 
+impl ListItem for MessagesFileResponse {
+    fn id(&self) -> &str {
+        &self.id
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use serde_json::json;
@@ -1,10 +1,15 @@
+use futures_util::Stream;
 use reqwest::Method;
 
-use crate::{base_client::BaseClient, common::OpenAIError, OpenAIQueryParameters, OpenAIRequest};
+use crate::{
+    base_client::BaseClient,
+    common::{paginate, ListParams, OpenAIError, PaginatedParams},
+    OpenAIRequest,
+};
 
 use super::{
-    CreateMessageRequest, ListMessagesFileResponse, ListMessagesResponse, MessageResponse,
-    MessagesFileResponse, ModifyMessagesRequest,
+    CreateMessageRequest, ListMessagesFileResponse, ListMessagesParams, ListMessagesResponse,
+    MessageResponse, MessagesFileResponse, ModifyMessagesRequest,
 };
 
 const THREADS_URL: &str = "/v1/threads";
@@ -27,7 +32,7 @@ impl<'a> MessagesHandler<'a> {
         request: CreateMessageRequest,
     ) -> Result<MessageResponse, OpenAIError> {
         let url = format!("{}/{}/messages", THREADS_URL, thread_id.into());
-        let openai_request = OpenAIRequest::with_body(Method::POST, url, request);
+        let openai_request = OpenAIRequest::with_body(Method::POST, url, request)?;
 
         let response = self.client.send(openai_request).await;
 
@@ -38,23 +43,42 @@ impl<'a> MessagesHandler<'a> {
     pub async fn list_messages<S: Into<String>>(
         &self,
         thread_id: S,
-        parameters: OpenAIQueryParameters,
+        parameters: ListMessagesParams,
     ) -> Result<ListMessagesResponse, OpenAIError> {
         let url = format!("{}/{}/messages", THREADS_URL, thread_id.into());
-        let openai_request =
-            OpenAIRequest::<()>::new(Method::GET, url).with_query_parameters(parameters);
+        let openai_request = OpenAIRequest::new(Method::GET, url).with_query_parameters(parameters);
 
         let response = self.client.send(openai_request).await;
 
         Ok(response?.json().await?)
     }
 
+    /// Streams the messages of a thread, fetching the pages lazily.
+    /// Stops after `max_items`, if set.
+    pub fn list_messages_auto_paginate<S: Into<String>>(
+        &self,
+        thread_id: S,
+        parameters: ListMessagesParams,
+        max_items: Option<usize>,
+    ) -> impl Stream<Item = Result<MessageResponse, OpenAIError>> + 'a {
+        let client = self.client;
+        let thread_id = thread_id.into();
+
+        paginate(max_items, move |after| {
+            let handler = Self::new(client);
+            let thread_id = thread_id.clone();
+            let parameters = parameters.with_after(after);
+
+            async move { handler.list_messages(thread_id, parameters).await }
+        })
+    }
+
     /// Returns a list of message files.
     pub async fn list_message_files<S: Into<String>>(
         &self,
         thread_id: S,
         message_id: S,
-        parameters: OpenAIQueryParameters,
+        parameters: ListParams,
     ) -> Result<ListMessagesFileResponse, OpenAIError> {
         let url = format!(
             "{}/{}/messages/{}/files",
@@ -62,14 +86,40 @@ impl<'a> MessagesHandler<'a> {
             thread_id.into(),
             message_id.into()
         );
-        let openai_request =
-            OpenAIRequest::<()>::new(Method::GET, url).with_query_parameters(parameters);
+        let openai_request = OpenAIRequest::new(Method::GET, url).with_query_parameters(parameters);
 
         let response = self.client.send(openai_request).await;
 
         Ok(response?.json().await?)
     }
 
+    /// Streams the files of a message, fetching the pages lazily.
+    /// Stops after `max_items`, if set.
+    pub fn list_message_files_auto_paginate<S: Into<String>>(
+        &self,
+        thread_id: S,
+        message_id: S,
+        parameters: ListParams,
+        max_items: Option<usize>,
+    ) -> impl Stream<Item = Result<MessagesFileResponse, OpenAIError>> + 'a {
+        let client = self.client;
+        let thread_id = thread_id.into();
+        let message_id = message_id.into();
+
+        paginate(max_items, move |after| {
+            let handler = Self::new(client);
+            let thread_id = thread_id.clone();
+            let message_id = message_id.clone();
+            let parameters = parameters.with_after(after);
+
+            async move {
+                handler
+                    .list_message_files(thread_id, message_id, parameters)
+                    .await
+            }
+        })
+    }
+
     /// Retrieve a message.
     pub async fn retrieve_message<S: Into<String>>(
         &self,
@@ -82,7 +132,7 @@ impl<'a> MessagesHandler<'a> {
             thread_id.into(),
             message_id.into()
         );
-        let openai_request = OpenAIRequest::<()>::new(Method::GET, url);
+        let openai_request = OpenAIRequest::new(Method::GET, url);
 
         let response = self.client.send(openai_request).await;
 
@@ -103,7 +153,7 @@ impl<'a> MessagesHandler<'a> {
             message_id.into(),
             file_id.into()
         );
-        let openai_request = OpenAIRequest::<()>::new(Method::GET, url);
+        let openai_request = OpenAIRequest::new(Method::GET, url);
 
         let response = self.client.send(openai_request).await;
 
@@ -123,7 +173,7 @@ impl<'a> MessagesHandler<'a> {
             thread_id.into(),
             message_id.into()
         );
-        let openai_request = OpenAIRequest::with_body(Method::POST, url, request);
+        let openai_request = OpenAIRequest::with_body(Method::POST, url, request)?;
 
         let response = self.client.send(openai_request).await;
 
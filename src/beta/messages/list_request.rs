@@ -0,0 +1,64 @@
+use crate::common::{push_query, ListParams, Order, PaginatedParams, QueryParameters};
+
+/// Query parameters to list the messages of a thread.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct ListMessagesParams {
+    /// The pagination and sort order of the list.
+    pub list: ListParams,
+
+    /// Filter messages by the run ID that generated them.
+    pub run_id: Option<String>,
+}
+
+impl ListMessagesParams {
+    /// Create new list parameters.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the after ID for pagination.
+    pub fn after<S: Into<String>>(mut self, after: S) -> Self {
+        self.list = self.list.after(after);
+        self
+    }
+
+    /// Sets the before ID for pagination.
+    pub fn before<S: Into<String>>(mut self, before: S) -> Self {
+        self.list = self.list.before(before);
+        self
+    }
+
+    /// Set a limit on the number of objects to be returned.
+    pub fn limit(mut self, limit: u32) -> Self {
+        self.list = self.list.limit(limit);
+        self
+    }
+
+    /// Set the sort order by the created_at timestamp of the objects.
+    pub fn order(mut self, order: Order) -> Self {
+        self.list = self.list.order(order);
+        self
+    }
+
+    /// Only return the messages generated by the run.
+    pub fn run_id<S: Into<String>>(mut self, run_id: S) -> Self {
+        self.run_id = Some(run_id.into());
+        self
+    }
+}
+
+impl PaginatedParams for ListMessagesParams {
+    fn after_mut(&mut self) -> &mut Option<String> {
+        self.list.after_mut()
+    }
+}
+
+impl QueryParameters for ListMessagesParams {
+    fn to_query(&self) -> Vec<(String, String)> {
+        let mut query = self.list.to_query();
+
+        push_query(&mut query, "run_id", self.run_id.as_ref());
+
+        query
+    }
+}
@@ -4,11 +4,13 @@
 mod create_request;
 mod file_response;
 mod handler;
+mod list_request;
 mod modify_request;
 mod response;
 
 pub use self::create_request::*;
 pub use self::file_response::*;
 pub use self::handler::*;
+pub use self::list_request::*;
 pub use self::modify_request::*;
 pub use self::response::*;
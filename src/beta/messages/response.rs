@@ -2,17 +2,10 @@ use std::collections::BTreeMap;
 
 use serde::{Deserialize, Serialize};
 
-use crate::common::MessageRole;
+use crate::common::{ListItem, ListResponse, MessageRole};
 
 /// A List of messages.
-#[derive(Debug, Default, PartialEq, Clone, Serialize, Deserialize)]
-pub struct ListMessagesResponse {
-    /// The object type, which is always `list`.
-    pub object: String,
-
-    /// A list of [`MessagesResponse`]
-    pub data: Vec<MessageResponse>,
-}
+pub type ListMessagesResponse = ListResponse<MessageResponse>;
 
 /// Represents a message within a [thread](https://platform.openai.com/docs/api-reference/threads).
 #[derive(Debug, Default, PartialEq, Clone, Serialize, Deserialize)]
@@ -53,6 +46,12 @@ pub struct MessageResponse {
     pub metadata: BTreeMap<String, String>,
 }
 
+impl ListItem for MessageResponse {
+    fn id(&self) -> &str {
+        &self.id
+    }
+}
+
 impl MessageResponse {
     /// Get the first text content of the message.
     pub fn first_text_content(&self) -> Option<String> {
@@ -60,7 +60,7 @@ impl CreateThreadAndRunRequest {
 #[cfg(test)]
 mod tests {
 
-    use crate::ThreadMessage;
+    use crate::assistants_common::ThreadMessage;
 
     use super::*;
     use serde_json::json;
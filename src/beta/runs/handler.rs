@@ -1,10 +1,15 @@
+use futures_util::Stream;
 use reqwest::Method;
 
-use crate::{base_client::BaseClient, common::OpenAIError, OpenAIQueryParameters, OpenAIRequest};
+use crate::{
+    base_client::BaseClient,
+    common::{paginate, ListParams, OpenAIError, PaginatedParams, UsageRecord},
+    OpenAIRequest,
+};
 
 use super::{
-    CreateRunRequest, CreateThreadAndRunRequest, ListRunsResponse, ListRunsStepsResponse,
-    ModifyRunRequest, RunsResponse, RunsStepResponse, SubmitToolsRequest,
+    CreateRunRequest, CreateThreadAndRunRequest, ListRunStepsParams, ListRunsResponse,
+    ListRunsStepsResponse, ModifyRunRequest, RunsResponse, RunsStepResponse, SubmitToolsRequest,
 };
 
 const THREADS_URL: &str = "/v1/threads";
@@ -27,11 +32,14 @@ impl<'a> RunsHandler<'a> {
         request: CreateRunRequest,
     ) -> Result<RunsResponse, OpenAIError> {
         let url = format!("{}/{}/runs", THREADS_URL, thread_id.into());
-        let openai_request = OpenAIRequest::with_body(Method::POST, url, request);
+        let openai_request = OpenAIRequest::with_body(Method::POST, url, request)?;
 
         let response = self.client.send(openai_request).await;
+        let response: RunsResponse = response?.json().await?;
 
-        Ok(response?.json().await?)
+        self.track_run_usage(&response);
+
+        Ok(response)
     }
 
     /// Create a thread and run it in one request.
@@ -40,33 +48,56 @@ impl<'a> RunsHandler<'a> {
         request: CreateThreadAndRunRequest,
     ) -> Result<RunsResponse, OpenAIError> {
         let url = format!("{}/runs", THREADS_URL);
-        let openai_request = OpenAIRequest::with_body(Method::POST, url, request);
+        let openai_request = OpenAIRequest::with_body(Method::POST, url, request)?;
 
         let response = self.client.send(openai_request).await;
+        let response: RunsResponse = response?.json().await?;
 
-        Ok(response?.json().await?)
+        self.track_run_usage(&response);
+
+        Ok(response)
     }
 
     /// Returns a list of runs belonging to a thread.
     pub async fn list_runs<S: Into<String>>(
         &self,
         thread_id: S,
-        parameters: OpenAIQueryParameters,
+        parameters: ListParams,
     ) -> Result<ListRunsResponse, OpenAIError> {
         let url = format!("{}/{}/runs", THREADS_URL, thread_id.into());
-        let openai_request =
-            OpenAIRequest::<()>::new(Method::GET, url).with_query_parameters(parameters);
+        let openai_request = OpenAIRequest::new(Method::GET, url).with_query_parameters(parameters);
 
         let response = self.client.send(openai_request).await;
 
         Ok(response?.json().await?)
     }
 
+    /// Streams the runs of a thread, fetching the pages lazily.
+    /// Stops after `max_items`, if set.
+    pub fn list_runs_auto_paginate<S: Into<String>>(
+        &self,
+        thread_id: S,
+        parameters: ListParams,
+        max_items: Option<usize>,
+    ) -> impl Stream<Item = Result<RunsResponse, OpenAIError>> + 'a {
+        let client = self.client;
+        let thread_id = thread_id.into();
+
+        paginate(max_items, move |after| {
+            let handler = Self::new(client);
+            let thread_id = thread_id.clone();
+            let parameters = parameters.with_after(after);
+
+            async move { handler.list_runs(thread_id, parameters).await }
+        })
+    }
+
     /// Returns a list of run steps belonging to a run.
     pub async fn list_run_steps<S: Into<String>>(
         &self,
         thread_id: S,
         run_id: S,
+        parameters: ListRunStepsParams,
     ) -> Result<ListRunsStepsResponse, OpenAIError> {
         let url = format!(
             "{}/{}/runs/{}/steps",
@@ -74,13 +105,36 @@ impl<'a> RunsHandler<'a> {
             thread_id.into(),
             run_id.into()
         );
-        let openai_request = OpenAIRequest::<()>::new(Method::GET, url);
+        let openai_request = OpenAIRequest::new(Method::GET, url).with_query_parameters(parameters);
 
         let response = self.client.send(openai_request).await;
 
         Ok(response?.json().await?)
     }
 
+    /// Streams the steps of a run, fetching the pages lazily.
+    /// Stops after `max_items`, if set.
+    pub fn list_run_steps_auto_paginate<S: Into<String>>(
+        &self,
+        thread_id: S,
+        run_id: S,
+        parameters: ListRunStepsParams,
+        max_items: Option<usize>,
+    ) -> impl Stream<Item = Result<RunsStepResponse, OpenAIError>> + 'a {
+        let client = self.client;
+        let thread_id = thread_id.into();
+        let run_id = run_id.into();
+
+        paginate(max_items, move |after| {
+            let handler = Self::new(client);
+            let thread_id = thread_id.clone();
+            let run_id = run_id.clone();
+            let parameters = parameters.with_after(after);
+
+            async move { handler.list_run_steps(thread_id, run_id, parameters).await }
+        })
+    }
+
     /// Retrieves a run.
     pub async fn retrieve_run<S: Into<String>>(
         &self,
@@ -93,11 +147,14 @@ impl<'a> RunsHandler<'a> {
             thread_id.into(),
             run_id.into()
         );
-        let openai_request = OpenAIRequest::<()>::new(Method::GET, url);
+        let openai_request = OpenAIRequest::new(Method::GET, url);
 
         let response = self.client.send(openai_request).await;
+        let response: RunsResponse = response?.json().await?;
 
-        Ok(response?.json().await?)
+        self.track_run_usage(&response);
+
+        Ok(response)
     }
 
     /// Retrieves a run step.
@@ -114,7 +171,7 @@ impl<'a> RunsHandler<'a> {
             run_id.into(),
             step_id.into()
         );
-        let openai_request = OpenAIRequest::<()>::new(Method::GET, url);
+        let openai_request = OpenAIRequest::new(Method::GET, url);
 
         let response = self.client.send(openai_request).await;
 
@@ -134,11 +191,14 @@ impl<'a> RunsHandler<'a> {
             thread_id.into(),
             run_id.into()
         );
-        let openai_request = OpenAIRequest::with_body(Method::POST, url, request);
+        let openai_request = OpenAIRequest::with_body(Method::POST, url, request)?;
 
         let response = self.client.send(openai_request).await;
+        let response: RunsResponse = response?.json().await?;
 
-        Ok(response?.json().await?)
+        self.track_run_usage(&response);
+
+        Ok(response)
     }
 
     /// When a run has the `status: "requires_action"` and `required_action.type` is `submit_tool_outputs`,
@@ -156,11 +216,14 @@ impl<'a> RunsHandler<'a> {
             thread_id.into(),
             run_id.into()
         );
-        let openai_request = OpenAIRequest::with_body(Method::POST, url, request);
+        let openai_request = OpenAIRequest::with_body(Method::POST, url, request)?;
 
         let response = self.client.send(openai_request).await;
+        let response: RunsResponse = response?.json().await?;
 
-        Ok(response?.json().await?)
+        self.track_run_usage(&response);
+
+        Ok(response)
     }
 
     /// Cancels a run that is `in_progress`.
@@ -175,10 +238,23 @@ impl<'a> RunsHandler<'a> {
             thread_id.into(),
             run_id.into()
         );
-        let openai_request = OpenAIRequest::<()>::new(Method::POST, url);
+        let openai_request = OpenAIRequest::new(Method::POST, url);
 
         let response = self.client.send(openai_request).await;
+        let response: RunsResponse = response?.json().await?;
 
-        Ok(response?.json().await?)
+        self.track_run_usage(&response);
+
+        Ok(response)
+    }
+
+    /// Records the usage of the run, which is only reported once the run ended
+    fn track_run_usage(&self, run: &RunsResponse) {
+        if let Some(usage) = &run.usage {
+            self.client.track_usage_once(
+                &run.id,
+                UsageRecord::new(run.model.clone()).with_tokens(usage),
+            );
+        }
     }
 }
@@ -7,6 +7,7 @@ mod create_thread_run_request;
 mod handler;
 mod modify_request;
 mod respones;
+mod steps_request;
 mod steps_response;
 mod submit_tools_request;
 mod tools;
@@ -17,5 +18,6 @@ pub use self::create_thread_run_request::*;
 pub use self::handler::*;
 pub use self::modify_request::*;
 pub use self::respones::*;
+pub use self::steps_request::*;
 pub use self::steps_response::*;
 pub use self::submit_tools_request::*;
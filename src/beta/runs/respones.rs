@@ -4,20 +4,13 @@ use serde::{Deserialize, Serialize};
 
 use crate::{
     assistants_common::AssistantTool,
-    common::{ChatModel, TokenUsage},
+    common::{ChatModel, ListItem, ListResponse, TokenUsage},
 };
 
 use super::{RunError, RunStatus};
 
 /// A list of runs.
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
-pub struct ListRunsResponse {
-    /// Object type, which is always `list`.
-    pub object: String,
-
-    /// A list of [`RunsResponse`] objects.
-    pub data: Vec<RunsResponse>,
-}
+pub type ListRunsResponse = ListResponse<RunsResponse>;
 
 /// Represents an execution run on a [thread](https://platform.openai.com/docs/api-reference/threads).
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -84,6 +77,12 @@ pub struct RunsResponse {
     pub usage: Option<TokenUsage>,
 }
 
+impl ListItem for RunsResponse {
+    fn id(&self) -> &str {
+        &self.id
+    }
+}
+
 /// Represents an action that can be taken to continue a run.
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct RunsAction {
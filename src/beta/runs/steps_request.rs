@@ -0,0 +1,90 @@
+use crate::common::{push_query, ListParams, Order, PaginatedParams, QueryParameters};
+
+/// Query parameters to list the steps of a run.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct ListRunStepsParams {
+    /// The pagination and sort order of the list.
+    pub list: ListParams,
+
+    /// Additional fields to include in the response,
+    /// e.g. `step_details.tool_calls[*].file_search.results[*].content`.
+    pub include: Vec<String>,
+}
+
+impl ListRunStepsParams {
+    /// Create new list parameters.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the after ID for pagination.
+    pub fn after<S: Into<String>>(mut self, after: S) -> Self {
+        self.list = self.list.after(after);
+        self
+    }
+
+    /// Sets the before ID for pagination.
+    pub fn before<S: Into<String>>(mut self, before: S) -> Self {
+        self.list = self.list.before(before);
+        self
+    }
+
+    /// Set a limit on the number of objects to be returned.
+    pub fn limit(mut self, limit: u32) -> Self {
+        self.list = self.list.limit(limit);
+        self
+    }
+
+    /// Set the sort order by the created_at timestamp of the objects.
+    pub fn order(mut self, order: Order) -> Self {
+        self.list = self.list.order(order);
+        self
+    }
+
+    /// Adds a field to include in the response.
+    pub fn include<S: Into<String>>(mut self, field: S) -> Self {
+        self.include.push(field.into());
+        self
+    }
+}
+
+impl PaginatedParams for ListRunStepsParams {
+    fn after_mut(&mut self) -> &mut Option<String> {
+        self.list.after_mut()
+    }
+}
+
+impl QueryParameters for ListRunStepsParams {
+    fn to_query(&self) -> Vec<(String, String)> {
+        let mut query = self.list.to_query();
+
+        for field in &self.include {
+            push_query(&mut query, "include[]", Some(field));
+        }
+
+        query
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn converts_to_query_correctly() {
+        let parameters = ListRunStepsParams::new()
+            .order(Order::Desc)
+            .include("step_details.tool_calls[*].file_search.results[*].content");
+
+        assert_eq!(
+            parameters.to_query(),
+            [
+                ("order".to_string(), "desc".to_string()),
+                (
+                    "include[]".to_string(),
+                    "step_details.tool_calls[*].file_search.results[*].content".to_string()
+                ),
+            ]
+        );
+    }
+}
@@ -2,19 +2,15 @@ use std::collections::BTreeMap;
 
 use serde::{Deserialize, Serialize};
 
-use crate::{assistants_common::AssistantTool, common::TokenUsage};
+use crate::{
+    assistants_common::AssistantTool,
+    common::{ListItem, ListResponse, TokenUsage},
+};
 
 use super::{RunError, RunStatus};
 
 /// A list of [`RunsResponse`] objects.
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
-pub struct ListRunsStepsResponse {
-    /// Object type, which is always `list`.
-    pub object: String,
-
-    /// A list of [`RunsStepResponse`] objects.
-    pub data: Vec<RunsStepResponse>,
-}
+pub type ListRunsStepsResponse = ListResponse<RunsStepResponse>;
 
 /// Represents an execution run on a [thread](https://platform.openai.com/docs/api-reference/threads).
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -73,6 +69,12 @@ pub struct RunsStepResponse {
     pub usage: Option<TokenUsage>,
 }
 
+impl ListItem for RunsStepResponse {
+    fn id(&self) -> &str {
+        &self.id
+    }
+}
+
 /// Represents the details of a run step.
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(untagged)]
@@ -36,7 +36,7 @@ impl CreateThreadRequest {
 
 #[cfg(test)]
 mod tests {
-    use crate::ThreadMessageRole;
+    use crate::assistants_common::ThreadMessageRole;
 
     use super::*;
     use serde_json::json;
@@ -25,7 +25,7 @@ impl<'a> ThreadsHandler<'a> {
         request: CreateThreadRequest,
     ) -> Result<ThreadsResponse, OpenAIError> {
         let openai_request =
-            OpenAIRequest::with_body(Method::POST, THREADS_URL.to_string(), request);
+            OpenAIRequest::with_body(Method::POST, THREADS_URL.to_string(), request)?;
 
         let response = self.client.send(openai_request).await;
 
@@ -38,7 +38,7 @@ impl<'a> ThreadsHandler<'a> {
         thread_id: S,
     ) -> Result<ThreadsResponse, OpenAIError> {
         let url = format!("{}/{}", THREADS_URL, thread_id.into());
-        let openai_request = OpenAIRequest::<()>::new(Method::GET, url);
+        let openai_request = OpenAIRequest::new(Method::GET, url);
 
         let response = self.client.send(openai_request).await;
 
@@ -52,7 +52,7 @@ impl<'a> ThreadsHandler<'a> {
         request: ModifyThreadRequest,
     ) -> Result<ThreadsResponse, OpenAIError> {
         let url = format!("{}/{}", THREADS_URL, thread_id.into());
-        let openai_request = OpenAIRequest::with_body(Method::POST, url, request);
+        let openai_request = OpenAIRequest::with_body(Method::POST, url, request)?;
 
         let response = self.client.send(openai_request).await;
 
@@ -65,7 +65,7 @@ impl<'a> ThreadsHandler<'a> {
         thread_id: S,
     ) -> Result<DeletionStatus, OpenAIError> {
         let url = format!("{}/{}", THREADS_URL, thread_id.into());
-        let openai_request = OpenAIRequest::<()>::new(Method::DELETE, url);
+        let openai_request = OpenAIRequest::new(Method::DELETE, url);
 
         let response = self.client.send(openai_request).await;
 
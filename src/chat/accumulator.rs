@@ -0,0 +1,334 @@
+use crate::common::{ChatModel, OpenAIError, OpenAIStream, TokenUsage};
+
+use super::{
+    ChatCompletionChoice, ChatCompletionResponse, ChatCompletionStreamResponse, ChatResponseChunk,
+    FunctionCall, ToolCall, ToolCallDelta, ToolType,
+};
+
+/// Folds the chunks of a streamed chat completion into the [`ChatCompletionResponse`]
+/// a non-streaming request would have returned.
+///
+/// The state is available at any point while the chunks arrive, e.g. to render a message as it's generated.
+#[derive(Debug, Clone, Default)]
+pub struct ChatCompletionAccumulator {
+    id: String,
+    created: u32,
+    model: String,
+    system_fingerprint: Option<String>,
+    choices: Vec<ChatCompletionChoice>,
+    usage: Option<TokenUsage>,
+}
+
+impl ChatCompletionAccumulator {
+    /// Creates an empty accumulator.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Reads the whole stream and returns the complete response.
+    pub async fn collect(
+        mut stream: OpenAIStream<ChatCompletionStreamResponse>,
+    ) -> Result<ChatCompletionResponse, OpenAIError> {
+        let mut accumulator = Self::new();
+
+        while let Some(chunk) = stream.next().await {
+            accumulator.push(&chunk?);
+        }
+
+        accumulator.into_response()
+    }
+
+    /// Merges a chunk into the accumulated response.
+    pub fn push(&mut self, chunk: &ChatCompletionStreamResponse) {
+        self.id.clone_from(&chunk.id);
+        self.created = chunk.created;
+        self.model.clone_from(&chunk.model);
+
+        if chunk.system_fingerprint.is_some() {
+            self.system_fingerprint
+                .clone_from(&chunk.system_fingerprint);
+        }
+
+        if chunk.usage.is_some() {
+            self.usage.clone_from(&chunk.usage);
+        }
+
+        for choice_delta in &chunk.choices {
+            let choice = self.choice_mut(choice_delta.index);
+            let delta = &choice_delta.delta;
+
+            if delta.role.is_some() {
+                choice.message.role.clone_from(&delta.role);
+            }
+
+            if let Some(content) = &delta.content {
+                choice
+                    .message
+                    .content
+                    .get_or_insert_with(String::new)
+                    .push_str(content);
+            }
+
+            if let Some(refusal) = &delta.refusal {
+                choice
+                    .message
+                    .refusal
+                    .get_or_insert_with(String::new)
+                    .push_str(refusal);
+            }
+
+            for tool_call_delta in &delta.tool_calls {
+                merge_tool_call(&mut choice.message.tool_calls, tool_call_delta);
+            }
+
+            if let Some(logprobs) = &choice_delta.logprobs {
+                let choice_logprobs = choice.logprobs.get_or_insert_with(Default::default);
+                choice_logprobs
+                    .content
+                    .extend(logprobs.content.iter().cloned());
+                choice_logprobs
+                    .refusal
+                    .extend(logprobs.refusal.iter().cloned());
+            }
+
+            if let Some(finish_reason) = &choice_delta.finish_reason {
+                choice.finish_reason.clone_from(finish_reason);
+            }
+        }
+    }
+
+    /// Returns the choices accumulated so far, ordered by their index.
+    /// The finish reason of a choice is empty until it's finished.
+    pub fn choices(&self) -> &[ChatCompletionChoice] {
+        &self.choices
+    }
+
+    /// Returns the content of the choice at the given index accumulated so far.
+    pub fn content(&self, index: i32) -> Option<&str> {
+        self.choices
+            .iter()
+            .find(|choice| choice.index == index)?
+            .message
+            .content
+            .as_deref()
+    }
+
+    /// Returns the usage statistics, if they were streamed.
+    pub fn usage(&self) -> Option<&TokenUsage> {
+        self.usage.as_ref()
+    }
+
+    /// Returns whether every choice has a finish reason.
+    pub fn is_finished(&self) -> bool {
+        !self.choices.is_empty()
+            && self
+                .choices
+                .iter()
+                .all(|choice| !choice.finish_reason.is_empty())
+    }
+
+    /// Returns the response accumulated so far.
+    pub fn snapshot(&self) -> Result<ChatCompletionResponse, OpenAIError> {
+        self.clone().into_response()
+    }
+
+    /// Returns the accumulated response. The usage is zero unless it was streamed.
+    pub fn into_response(self) -> Result<ChatCompletionResponse, OpenAIError> {
+        let model = ChatModel::from(self.model);
+
+        Ok(ChatCompletionResponse {
+            id: self.id,
+            choices: self.choices,
+            created: self.created,
+            model,
+            system_fingerprint: self.system_fingerprint,
+            object: "chat.completion".to_string(),
+            usage: self.usage.unwrap_or_default(),
+        })
+    }
+
+    fn choice_mut(&mut self, index: i32) -> &mut ChatCompletionChoice {
+        let position = match self
+            .choices
+            .binary_search_by_key(&index, |choice| choice.index)
+        {
+            Ok(position) => position,
+            Err(position) => {
+                let choice = ChatCompletionChoice {
+                    index,
+                    message: ChatResponseChunk::default(),
+                    logprobs: None,
+                    finish_reason: String::new(),
+                };
+
+                self.choices.insert(position, choice);
+                position
+            }
+        };
+
+        &mut self.choices[position]
+    }
+}
+
+fn merge_tool_call(tool_calls: &mut Vec<ToolCall>, delta: &ToolCallDelta) {
+    while tool_calls.len() <= delta.index {
+        tool_calls.push(ToolCall {
+            id: String::new(),
+            _type: ToolType::Function,
+            function: FunctionCall {
+                name: String::new(),
+                arguments: String::new(),
+            },
+        });
+    }
+
+    let tool_call = &mut tool_calls[delta.index];
+
+    if let Some(id) = &delta.id {
+        tool_call.id.push_str(id);
+    }
+
+    if let Some(tool_type) = &delta._type {
+        tool_call._type = tool_type.clone();
+    }
+
+    if let Some(function) = &delta.function {
+        if let Some(name) = &function.name {
+            tool_call.function.name.push_str(name);
+        }
+
+        if let Some(arguments) = &function.arguments {
+            tool_call.function.arguments.push_str(arguments);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::{json, Value};
+
+    use crate::MessageRole;
+
+    use super::*;
+
+    fn chunk(choices: Value, usage: Value) -> ChatCompletionStreamResponse {
+        serde_json::from_value(json!({
+            "id": "chatcmpl-123",
+            "object": "chat.completion.chunk",
+            "created": 1694268190,
+            "model": "gpt-3.5-turbo-0125",
+            "system_fingerprint": "fp_44709d6fcb",
+            "choices": choices,
+            "usage": usage,
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn accumulates_chunks_correctly() {
+        let chunks = [
+            json!([{ "index": 0, "delta": { "role": "assistant", "content": "" }, "logprobs": null, "finish_reason": null }]),
+            json!([{ "index": 1, "delta": { "role": "assistant", "tool_calls": [
+                { "index": 0, "id": "call_abc", "type": "function", "function": { "name": "get_weather", "arguments": "" } }
+            ] }, "logprobs": null, "finish_reason": null }]),
+            json!([{ "index": 0, "delta": { "content": "Hello" }, "logprobs": null, "finish_reason": null }]),
+            json!([{ "index": 1, "delta": { "tool_calls": [
+                { "index": 0, "function": { "arguments": "{\"city\":" } }
+            ] }, "logprobs": null, "finish_reason": null }]),
+            json!([{ "index": 0, "delta": { "content": " there" }, "logprobs": null, "finish_reason": "stop" }]),
+            json!([{ "index": 1, "delta": { "tool_calls": [
+                { "index": 0, "function": { "arguments": "\"Paris\"}" } }
+            ] }, "logprobs": null, "finish_reason": "tool_calls" }]),
+        ];
+
+        let mut accumulator = ChatCompletionAccumulator::new();
+
+        for (i, choices) in chunks.into_iter().enumerate() {
+            accumulator.push(&chunk(choices, Value::Null));
+
+            if i == 2 {
+                assert_eq!(accumulator.content(0), Some("Hello"));
+                assert!(!accumulator.is_finished());
+            }
+        }
+
+        assert!(accumulator.is_finished());
+        accumulator.push(&chunk(
+            json!([]),
+            json!({ "prompt_tokens": 9, "completion_tokens": 12, "total_tokens": 21 }),
+        ));
+
+        let response = accumulator.into_response().unwrap();
+
+        let expectation = ChatCompletionResponse {
+            id: "chatcmpl-123".to_string(),
+            choices: vec![
+                ChatCompletionChoice {
+                    index: 0,
+                    message: ChatResponseChunk {
+                        role: Some(MessageRole::Assistant),
+                        content: Some("Hello there".to_string()),
+                        tool_calls: vec![],
+                        refusal: None,
+                    },
+                    logprobs: None,
+                    finish_reason: "stop".to_string(),
+                },
+                ChatCompletionChoice {
+                    index: 1,
+                    message: ChatResponseChunk {
+                        role: Some(MessageRole::Assistant),
+                        content: None,
+                        tool_calls: vec![ToolCall {
+                            id: "call_abc".to_string(),
+                            _type: ToolType::Function,
+                            function: FunctionCall {
+                                name: "get_weather".to_string(),
+                                arguments: "{\"city\":\"Paris\"}".to_string(),
+                            },
+                        }],
+                        refusal: None,
+                    },
+                    logprobs: None,
+                    finish_reason: "tool_calls".to_string(),
+                },
+            ],
+            created: 1694268190,
+            model: ChatModel::GPT3_5Turbo0125,
+            system_fingerprint: Some("fp_44709d6fcb".to_string()),
+            object: "chat.completion".to_string(),
+            usage: TokenUsage {
+                prompt_tokens: 9,
+                completion_tokens: 12,
+                total_tokens: 21,
+            },
+        };
+
+        assert_eq!(response, expectation);
+    }
+
+    #[test]
+    fn accumulates_logprobs_correctly() {
+        let logprobs = |token: &str, logprob: f64| json!({ "content": [{ "token": token, "logprob": logprob, "bytes": null, "top_logprobs": [] }] });
+
+        let mut accumulator = ChatCompletionAccumulator::new();
+        accumulator.push(&chunk(
+            json!([{ "index": 0, "delta": { "content": "Yes" }, "logprobs": logprobs("Yes", -0.1), "finish_reason": null }]),
+            Value::Null,
+        ));
+        accumulator.push(&chunk(
+            json!([{ "index": 0, "delta": { "content": "." }, "logprobs": logprobs(".", -0.2), "finish_reason": "stop" }]),
+            Value::Null,
+        ));
+
+        let logprobs = accumulator.choices()[0].logprobs.as_ref().unwrap();
+        let tokens = logprobs
+            .content
+            .iter()
+            .map(|token| token.token.as_str())
+            .collect::<Vec<_>>();
+
+        assert_eq!(tokens, vec!["Yes", "."]);
+        assert!((logprobs.sequence_logprob() + 0.3).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,268 @@
+use std::path::Path;
+
+use base64::{engine::general_purpose, Engine as _};
+use serde::{Deserialize, Serialize};
+
+use crate::common::OpenAIError;
+
+/// The contents of a chat message, either plain text or a list of parts for multimodal messages.
+///
+/// Plain text is serialized as a string, exactly like messages without parts.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum ChatMessageContent {
+    /// Plain text
+    Text(String),
+
+    /// A list of text, image and audio parts
+    Parts(Vec<ChatContentPart>),
+}
+
+impl Default for ChatMessageContent {
+    fn default() -> Self {
+        Self::Text(String::new())
+    }
+}
+
+impl ChatMessageContent {
+    /// Returns the text of the message, joining the text parts of multimodal messages.
+    pub fn text(&self) -> String {
+        match self {
+            Self::Text(text) => text.clone(),
+            Self::Parts(parts) => parts
+                .iter()
+                .filter_map(|part| match part {
+                    ChatContentPart::Text { text } => Some(text.as_str()),
+                    _ => None,
+                })
+                .collect::<Vec<_>>()
+                .join("\n"),
+        }
+    }
+}
+
+impl From<String> for ChatMessageContent {
+    fn from(value: String) -> Self {
+        Self::Text(value)
+    }
+}
+
+impl From<&str> for ChatMessageContent {
+    fn from(value: &str) -> Self {
+        Self::Text(value.to_string())
+    }
+}
+
+impl From<Vec<ChatContentPart>> for ChatMessageContent {
+    fn from(value: Vec<ChatContentPart>) -> Self {
+        Self::Parts(value)
+    }
+}
+
+/// A part of the contents of a multimodal chat message.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ChatContentPart {
+    /// A text part
+    Text {
+        /// The text
+        text: String,
+    },
+
+    /// An image part. Only supported by models with vision capabilities.
+    ImageUrl {
+        /// The image
+        image_url: ImageUrl,
+    },
+
+    /// An audio part. Only supported by models with audio capabilities.
+    InputAudio {
+        /// The audio
+        input_audio: InputAudio,
+    },
+}
+
+/// An image of a chat message, either a URL or a base64 encoded data URL.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ImageUrl {
+    /// The URL of the image, or the image as a base64 encoded data URL
+    pub url: String,
+
+    /// The detail level of the image.
+    /// See the [vision guide](https://platform.openai.com/docs/guides/vision/low-or-high-fidelity-image-understanding).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    pub detail: Option<ImageDetail>,
+}
+
+/// The detail level an image is processed with.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ImageDetail {
+    #[default]
+    /// Lets the model decide
+    Auto,
+
+    /// A low resolution version of the image, using fewer tokens
+    Low,
+
+    /// The image in high resolution
+    High,
+}
+
+/// Audio of a chat message.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct InputAudio {
+    /// The base64 encoded audio data
+    pub data: String,
+
+    /// The format of the audio data
+    pub format: InputAudioFormat,
+}
+
+/// The formats audio of a chat message can be sent in.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum InputAudioFormat {
+    /// WAV audio
+    Wav,
+
+    /// MP3 audio
+    Mp3,
+}
+
+impl ChatContentPart {
+    /// Creates a text part.
+    pub fn text<S>(text: S) -> Self
+    where
+        S: Into<String>,
+    {
+        Self::Text { text: text.into() }
+    }
+
+    /// Creates an image part from the URL of an image.
+    pub fn image_url<S>(url: S) -> Self
+    where
+        S: Into<String>,
+    {
+        Self::ImageUrl {
+            image_url: ImageUrl {
+                url: url.into(),
+                detail: None,
+            },
+        }
+    }
+
+    /// Creates an image part from the raw bytes of a PNG, JPEG, GIF or WEBP image,
+    /// sent as a base64 encoded data URL.
+    pub fn image_bytes(bytes: &[u8]) -> Result<Self, OpenAIError> {
+        let mime_type = detect_image_mime_type(bytes).ok_or_else(|| {
+            OpenAIError::Exception(
+                "Unsupported image format, expected PNG, JPEG, GIF or WEBP".to_string(),
+            )
+        })?;
+
+        let data = general_purpose::STANDARD.encode(bytes);
+
+        Ok(Self::image_url(format!("data:{mime_type};base64,{data}")))
+    }
+
+    /// Creates an image part from a local PNG, JPEG, GIF or WEBP file, sent as a base64 encoded data URL.
+    pub async fn image_path<P>(path: P) -> Result<Self, OpenAIError>
+    where
+        P: AsRef<Path>,
+    {
+        let bytes = tokio::fs::read(path).await?;
+
+        Self::image_bytes(&bytes)
+    }
+
+    /// Creates an audio part from raw audio bytes.
+    pub fn input_audio(bytes: &[u8], format: InputAudioFormat) -> Self {
+        Self::InputAudio {
+            input_audio: InputAudio {
+                data: general_purpose::STANDARD.encode(bytes),
+                format,
+            },
+        }
+    }
+
+    /// Creates an audio part from a local WAV or MP3 file. The format is taken from the file extension.
+    pub async fn input_audio_path<P>(path: P) -> Result<Self, OpenAIError>
+    where
+        P: AsRef<Path>,
+    {
+        let path = path.as_ref();
+        let extension = path
+            .extension()
+            .and_then(|extension| extension.to_str())
+            .map(str::to_ascii_lowercase);
+
+        let format = match extension.as_deref() {
+            Some("wav") => InputAudioFormat::Wav,
+            Some("mp3") => InputAudioFormat::Mp3,
+            _ => {
+                return Err(OpenAIError::Exception(format!(
+                    "Unsupported audio format: {}, expected a WAV or MP3 file",
+                    path.display()
+                )))
+            }
+        };
+
+        let bytes = tokio::fs::read(path).await?;
+
+        Ok(Self::input_audio(&bytes, format))
+    }
+
+    /// Sets the detail level of an image part. Other parts are returned unchanged.
+    pub fn with_detail(mut self, detail: ImageDetail) -> Self {
+        if let Self::ImageUrl { image_url } = &mut self {
+            image_url.detail = Some(detail);
+        }
+
+        self
+    }
+}
+
+/// Detects the MIME type of an image from its magic bytes
+fn detect_image_mime_type(bytes: &[u8]) -> Option<&'static str> {
+    match bytes {
+        [0x89, b'P', b'N', b'G', 0x0D, 0x0A, 0x1A, 0x0A, ..] => Some("image/png"),
+        [0xFF, 0xD8, 0xFF, ..] => Some("image/jpeg"),
+        [b'G', b'I', b'F', b'8', b'7' | b'9', b'a', ..] => Some("image/gif"),
+        [b'R', b'I', b'F', b'F', _, _, _, _, b'W', b'E', b'B', b'P', ..] => Some("image/webp"),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn serializes_content_correctly() {
+        let content = ChatMessageContent::Parts(vec![
+            ChatContentPart::text("What's in this image?"),
+            ChatContentPart::image_url("https://example.com/cat.png").with_detail(ImageDetail::Low),
+            ChatContentPart::image_bytes(b"GIF89a\x01\x00").unwrap(),
+            ChatContentPart::input_audio(b"ID3", InputAudioFormat::Mp3),
+        ]);
+
+        let json = json!([
+            { "type": "text", "text": "What's in this image?" },
+            { "type": "image_url", "image_url": { "url": "https://example.com/cat.png", "detail": "low" } },
+            { "type": "image_url", "image_url": { "url": "data:image/gif;base64,R0lGODlhAQA=" } },
+            { "type": "input_audio", "input_audio": { "data": "SUQz", "format": "mp3" } }
+        ]);
+
+        assert_eq!(serde_json::to_value(&content).unwrap(), json);
+        assert_eq!(
+            serde_json::from_value::<ChatMessageContent>(json).unwrap(),
+            content
+        );
+
+        let text = ChatMessageContent::from("Hello!");
+        assert_eq!(serde_json::to_value(&text).unwrap(), json!("Hello!"));
+    }
+}
@@ -0,0 +1,541 @@
+use std::{future::Future, sync::Arc};
+
+use futures_util::future::BoxFuture;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    common::{ChatModel, MessageRole, ModelId, ModelRegistry, OpenAIError},
+    tokenizer::{count_tokens_with, Tokenizer},
+};
+
+use super::{ChatMessageContent, ChatRequestMessage, CreateChatCompletionRequest, Tool};
+
+type Summarizer = Arc<
+    dyn Fn(Vec<ChatRequestMessage>) -> BoxFuture<'static, Result<ChatRequestMessage, OpenAIError>>
+        + Send
+        + Sync,
+>;
+
+/// How a [`Conversation`] is shortened when it no longer fits into the context window of its model.
+///
+/// System messages and the latest message are always kept. Tool messages are dropped together with
+/// the assistant message whose tool calls they answer.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TruncationPolicy {
+    /// Drops the oldest messages until the conversation fits.
+    #[default]
+    DropOldest,
+
+    /// Keeps the given number of turns, each starting with a user message, and drops the oldest
+    /// of them if they still don't fit.
+    KeepLastTurns(usize),
+
+    /// Replaces the oldest messages with a summary created by the summarizer of the conversation,
+    /// see [`Conversation::with_summarizer`].
+    Summarize,
+}
+
+/// A chat conversation that keeps itself within the context window of its model.
+///
+/// Before every request, the history is shortened following the [`TruncationPolicy`], so that the prompt
+/// and `max_tokens` of the completion fit into the context window. Tokens are counted offline,
+/// see [`count_tokens`](crate::tokenizer::count_tokens).
+///
+/// The summarizer is not serialized, and has to be set again after deserializing.
+#[derive(Clone, Default, Serialize, Deserialize)]
+pub struct Conversation {
+    /// The model the conversation is held with
+    pub model: ChatModel,
+
+    /// The messages of the conversation, oldest first
+    pub messages: Vec<ChatRequestMessage>,
+
+    /// The maximum number of tokens to generate for each completion, reserved in the context window
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    pub max_tokens: Option<i32>,
+
+    /// The tools the model may call
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    #[serde(default)]
+    pub tools: Vec<Tool>,
+
+    /// How the conversation is shortened when it doesn't fit
+    #[serde(default)]
+    pub policy: TruncationPolicy,
+
+    /// Overrides the context window of the model, e.g. with the one of a [`ModelRegistry`](crate::ModelRegistry)
+    /// for models unknown to this crate
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    pub context_window: Option<usize>,
+
+    #[serde(skip)]
+    summarizer: Option<Summarizer>,
+}
+
+impl std::fmt::Debug for Conversation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Conversation")
+            .field("model", &self.model)
+            .field("messages", &self.messages)
+            .field("max_tokens", &self.max_tokens)
+            .field("tools", &self.tools)
+            .field("policy", &self.policy)
+            .field("context_window", &self.context_window)
+            .field("summarizer", &self.summarizer.is_some())
+            .finish()
+    }
+}
+
+impl Conversation {
+    /// Creates an empty conversation with the given model.
+    pub fn new<M>(model: M) -> Self
+    where
+        M: Into<ModelId>,
+    {
+        Self {
+            model: model.into().into(),
+            ..Default::default()
+        }
+    }
+
+    /// Adds a system message to the conversation.
+    pub fn with_system_message<S>(mut self, content: S) -> Self
+    where
+        S: Into<ChatMessageContent>,
+    {
+        self.messages
+            .push(ChatRequestMessage::system_message(content));
+        self
+    }
+
+    /// Sets the maximum number of tokens to generate for each completion.
+    pub fn with_max_tokens(mut self, max_tokens: i32) -> Self {
+        self.max_tokens = Some(max_tokens);
+        self
+    }
+
+    /// Sets the tools the model may call.
+    pub fn with_tools(mut self, tools: Vec<Tool>) -> Self {
+        self.tools = tools;
+        self
+    }
+
+    /// Sets how the conversation is shortened when it doesn't fit.
+    pub fn with_policy(mut self, policy: TruncationPolicy) -> Self {
+        self.policy = policy;
+        self
+    }
+
+    /// Overrides the context window of the model.
+    pub fn with_context_window(mut self, context_window: usize) -> Self {
+        self.context_window = Some(context_window);
+        self
+    }
+
+    /// Sets the summarizer used by [`TruncationPolicy::Summarize`]. It receives the messages
+    /// to drop, and returns the message replacing them.
+    pub fn with_summarizer<F, Fut>(mut self, summarizer: F) -> Self
+    where
+        F: Fn(Vec<ChatRequestMessage>) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<ChatRequestMessage, OpenAIError>> + Send + 'static,
+    {
+        self.summarizer = Some(Arc::new(move |messages| Box::pin(summarizer(messages))));
+        self
+    }
+
+    /// Appends a message to the conversation.
+    pub fn push(&mut self, message: ChatRequestMessage) {
+        self.messages.push(message);
+    }
+
+    /// Appends a user message to the conversation.
+    pub fn push_user_message<S>(&mut self, content: S)
+    where
+        S: Into<ChatMessageContent>,
+    {
+        self.push(ChatRequestMessage::user_message(content));
+    }
+
+    /// The number of tokens available for the prompt, which is the context window of the model
+    /// without the tokens reserved for the completion. `None` if the context window of the model is unknown.
+    pub fn prompt_budget(&self) -> Option<usize> {
+        self.prompt_budget_with(ModelRegistry::builtin())
+    }
+
+    /// The number of tokens available for the prompt, looking up the context window of the model
+    /// in the registry, see [`Conversation::prompt_budget`].
+    pub fn prompt_budget_with(&self, model_registry: &ModelRegistry) -> Option<usize> {
+        let reserved = self.max_tokens.unwrap_or_default().max(0) as usize;
+
+        self.context_window
+            .or_else(|| {
+                self.model
+                    .info_in(model_registry)
+                    .map(|info| info.context_window)
+            })
+            .map(|context_window| context_window.saturating_sub(reserved))
+    }
+
+    /// Counts the tokens of the prompt of the next request.
+    pub fn prompt_tokens(&self) -> Result<usize, OpenAIError> {
+        let tokenizer = Tokenizer::for_model(&self.model.to_string())?;
+
+        Ok(count_tokens_with(&tokenizer, &self.to_request()))
+    }
+
+    /// Builds the request for the next completion of the conversation, without shortening it.
+    pub fn to_request(&self) -> CreateChatCompletionRequest {
+        CreateChatCompletionRequest {
+            model: self.model.to_string(),
+            messages: self.messages.clone(),
+            max_tokens: self.max_tokens,
+            tools: self.tools.clone(),
+            ..Default::default()
+        }
+    }
+
+    /// Shortens the conversation following its policy, until it fits into the context window.
+    ///
+    /// Fails if it doesn't fit even with only the system messages and the latest message left,
+    /// if the context window of the model is unknown, or if the policy is [`TruncationPolicy::Summarize`] and no summarizer is set.
+    pub async fn fit(&mut self) -> Result<(), OpenAIError> {
+        self.fit_with(ModelRegistry::builtin()).await
+    }
+
+    /// Shortens the conversation until it fits, looking up the context window of the model in the registry,
+    /// see [`Conversation::fit`].
+    pub async fn fit_with(&mut self, model_registry: &ModelRegistry) -> Result<(), OpenAIError> {
+        let tokenizer = Tokenizer::for_model(&self.model.to_string())?;
+        let budget = self.prompt_budget_with(model_registry).ok_or_else(|| {
+            OpenAIError::Exception(format!("The context window of {} is unknown", self.model))
+        })?;
+        let mut request = self.to_request();
+        let fits = |request: &CreateChatCompletionRequest| {
+            count_tokens_with(&tokenizer, request) <= budget
+        };
+
+        if fits(&request) {
+            return Ok(());
+        }
+
+        let mut protected = 0;
+
+        match self.policy {
+            TruncationPolicy::DropOldest => {}
+            TruncationPolicy::KeepLastTurns(turns) => keep_last_turns(&mut request.messages, turns),
+            TruncationPolicy::Summarize => {
+                let summarizer = self.summarizer.clone().ok_or_else(|| {
+                    OpenAIError::Exception(
+                        "The summarize truncation policy requires a summarizer".to_string(),
+                    )
+                })?;
+
+                let mut dropped = vec![];
+                while !fits(&request) {
+                    match drop_oldest(&mut request.messages, 0) {
+                        Some(messages) => dropped.extend(messages),
+                        None => break,
+                    }
+                }
+
+                if !dropped.is_empty() {
+                    // The summary takes the place of the first dropped message
+                    let position = first_droppable(&request.messages, 0)
+                        .unwrap_or(request.messages.len().saturating_sub(1));
+                    request
+                        .messages
+                        .insert(position, summarizer(dropped).await?);
+                    protected = position + 1;
+                }
+            }
+        }
+
+        while !fits(&request) {
+            if drop_oldest(&mut request.messages, protected).is_none() {
+                return Err(OpenAIError::Exception(format!(
+                    "The conversation needs {} tokens, but only {} are available for the prompt of {}",
+                    count_tokens_with(&tokenizer, &request),
+                    budget,
+                    self.model
+                )));
+            }
+        }
+
+        self.messages = request.messages;
+
+        Ok(())
+    }
+}
+
+/// Index of the oldest message that may be dropped: not a system message, and not the latest message
+fn first_droppable(messages: &[ChatRequestMessage], from: usize) -> Option<usize> {
+    let last = messages.len().checked_sub(1)?;
+
+    (from..last).find(|&index| messages[index].role != MessageRole::System)
+}
+
+/// Drops the oldest droppable message, with the tool messages answering it.
+///
+/// A message with tool calls is only dropped together with all of its tool messages,
+/// so nothing is dropped if one of them is the latest message.
+fn drop_oldest(
+    messages: &mut Vec<ChatRequestMessage>,
+    from: usize,
+) -> Option<Vec<ChatRequestMessage>> {
+    let start = first_droppable(messages, from)?;
+    let last = messages.len() - 1;
+
+    let mut end = start + 1;
+    while end < messages.len() && messages[end].role == MessageRole::Tool {
+        end += 1;
+    }
+
+    if end > last {
+        return None;
+    }
+
+    Some(messages.drain(start..end).collect())
+}
+
+/// Drops the non-system messages before the last `turns` user messages
+fn keep_last_turns(messages: &mut Vec<ChatRequestMessage>, turns: usize) {
+    let user_messages = messages
+        .iter()
+        .enumerate()
+        .filter(|(_, message)| message.role == MessageRole::User)
+        .map(|(index, _)| index)
+        .collect::<Vec<_>>();
+
+    let cutoff = match turns {
+        0 => messages.len().saturating_sub(1),
+        turns if turns < user_messages.len() => user_messages[user_messages.len() - turns],
+        _ => return,
+    };
+
+    let mut index = 0;
+    messages.retain(|message| {
+        let keep = index >= cutoff || message.role == MessageRole::System;
+        index += 1;
+        keep
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::chat::{FunctionCall, ToolCall, ToolType};
+
+    use super::*;
+
+    fn conversation(turns: usize) -> Conversation {
+        let mut conversation = Conversation::new(ChatModel::GPT3_5Turbo)
+            .with_system_message("You are a helpful assistant.");
+
+        for turn in 0..turns {
+            conversation.push_user_message(format!("Question number {}", turn));
+            conversation.push(ChatRequestMessage::assistant_message(format!(
+                "Answer number {}",
+                turn
+            )));
+        }
+
+        conversation.push_user_message("The last question");
+        conversation
+    }
+
+    /// Reserves all but the given number of prompt tokens for the completion
+    fn with_prompt_budget(conversation: Conversation, budget: usize) -> Conversation {
+        let max_tokens = conversation.model.context_window().unwrap() - budget;
+        conversation.with_max_tokens(max_tokens as i32)
+    }
+
+    fn contents(conversation: &Conversation) -> Vec<String> {
+        conversation
+            .messages
+            .iter()
+            .map(|message| {
+                message
+                    .content
+                    .as_ref()
+                    .map_or_else(String::new, |content| content.text())
+            })
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn keeps_fitting_conversation() {
+        let mut conversation = conversation(3);
+
+        conversation.fit().await.unwrap();
+
+        assert_eq!(conversation.messages.len(), 8);
+    }
+
+    #[tokio::test]
+    async fn drops_oldest_messages() {
+        let full = conversation(3);
+        let budget = full.prompt_tokens().unwrap() - 1;
+        let mut conversation = with_prompt_budget(full, budget);
+
+        conversation.fit().await.unwrap();
+
+        assert!(conversation.prompt_tokens().unwrap() <= budget);
+        assert_eq!(
+            contents(&conversation),
+            vec![
+                "You are a helpful assistant.",
+                "Answer number 0",
+                "Question number 1",
+                "Answer number 1",
+                "Question number 2",
+                "Answer number 2",
+                "The last question",
+            ]
+        );
+    }
+
+    #[test]
+    fn drops_tool_calls_with_their_replies() {
+        let tool_call = ToolCall {
+            id: "call_abc123".to_string(),
+            _type: ToolType::Function,
+            function: FunctionCall {
+                name: "get_weather".to_string(),
+                arguments: "{}".to_string(),
+            },
+        };
+        let mut messages = vec![
+            ChatRequestMessage::system_message("You are a helpful assistant."),
+            ChatRequestMessage::user_message("What's the weather?"),
+            ChatRequestMessage {
+                role: MessageRole::Assistant,
+                tool_calls: vec![tool_call],
+                ..Default::default()
+            },
+            ChatRequestMessage::tool_message("call_abc123", "22 degrees"),
+        ];
+
+        let dropped = drop_oldest(&mut messages, 0).unwrap();
+        assert_eq!(dropped.len(), 1);
+        assert_eq!(dropped[0].role, MessageRole::User);
+
+        // The tool call can't be dropped without its reply, which is the latest message
+        assert!(drop_oldest(&mut messages, 0).is_none());
+        assert_eq!(messages.len(), 3);
+
+        messages.push(ChatRequestMessage::user_message("And tomorrow?"));
+
+        let dropped = drop_oldest(&mut messages, 0).unwrap();
+        assert_eq!(
+            dropped
+                .iter()
+                .map(|message| &message.role)
+                .collect::<Vec<_>>(),
+            vec![&MessageRole::Assistant, &MessageRole::Tool]
+        );
+        assert_eq!(messages.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn keeps_last_turns() {
+        let full = conversation(3);
+        let budget = full.prompt_tokens().unwrap() - 1;
+        let mut conversation =
+            with_prompt_budget(full, budget).with_policy(TruncationPolicy::KeepLastTurns(2));
+
+        conversation.fit().await.unwrap();
+
+        assert_eq!(
+            contents(&conversation),
+            vec![
+                "You are a helpful assistant.",
+                "Question number 2",
+                "Answer number 2",
+                "The last question",
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn summarizes_dropped_messages() {
+        let full = conversation(3);
+        let budget = full.prompt_tokens().unwrap() - 10;
+        let mut conversation = with_prompt_budget(full, budget)
+            .with_policy(TruncationPolicy::Summarize)
+            .with_summarizer(|messages: Vec<ChatRequestMessage>| async move {
+                Ok(ChatRequestMessage::system_message(format!(
+                    "Summary of {} messages",
+                    messages.len()
+                )))
+            });
+
+        conversation.fit().await.unwrap();
+
+        let contents = contents(&conversation);
+        assert_eq!(contents[0], "You are a helpful assistant.");
+        assert!(contents[1].starts_with("Summary of"));
+        assert_eq!(contents.last().unwrap(), "The last question");
+        assert!(conversation.prompt_tokens().unwrap() <= budget);
+    }
+
+    #[tokio::test]
+    async fn fails_without_summarizer() {
+        let full = conversation(3);
+        let budget = full.prompt_tokens().unwrap() - 1;
+        let mut conversation =
+            with_prompt_budget(full, budget).with_policy(TruncationPolicy::Summarize);
+
+        assert!(conversation.fit().await.is_err());
+        assert_eq!(conversation.messages.len(), 8);
+    }
+
+    #[tokio::test]
+    async fn uses_context_window_override() {
+        let mut conversation = conversation(3);
+        conversation.model = ChatModel::from("my-model");
+
+        assert!(conversation.fit().await.is_err());
+
+        let mut conversation = conversation.with_context_window(4_096);
+        conversation.fit().await.unwrap();
+
+        assert_eq!(conversation.messages.len(), 8);
+    }
+
+    #[tokio::test]
+    async fn uses_model_registry() {
+        let mut conversation = conversation(3);
+        conversation.model = ChatModel::from("my-model");
+
+        let model_registry =
+            ModelRegistry::empty().with_model("my-model", crate::ModelInfo::new(4_096));
+        assert_eq!(
+            conversation.prompt_budget_with(&model_registry),
+            Some(4_096)
+        );
+
+        conversation.fit_with(&model_registry).await.unwrap();
+
+        assert_eq!(conversation.messages.len(), 8);
+    }
+
+    #[tokio::test]
+    async fn fails_when_latest_message_does_not_fit() {
+        let mut conversation = with_prompt_budget(conversation(1), 5);
+
+        assert!(conversation.fit().await.is_err());
+    }
+
+    #[test]
+    fn serializes_conversation_correctly() {
+        let conversation = conversation(1).with_policy(TruncationPolicy::KeepLastTurns(4));
+
+        let json = serde_json::to_value(&conversation).unwrap();
+        let deserialized: Conversation = serde_json::from_value(json.clone()).unwrap();
+
+        assert_eq!(json["policy"], serde_json::json!({ "keep_last_turns": 4 }));
+        assert_eq!(serde_json::to_value(&deserialized).unwrap(), json);
+    }
+}
@@ -1,12 +1,20 @@
+use futures_util::{stream::FuturesUnordered, StreamExt};
 use reqwest::Method;
+use serde::de::DeserializeOwned;
+use tokio::time::Instant;
 
 use crate::{
     base_client::BaseClient,
-    common::{OpenAIError, OpenAIStream},
+    common::{OpenAIError, OpenAIStream, StructuredOutputError, ToolSchema, UsageRecord},
     OpenAIRequest,
 };
 
-use super::{ChatCompletionResponse, ChatCompletionStreamResponse, CreateChatCompletionRequest};
+#[cfg(feature = "tokenizer")]
+use super::Conversation;
+use super::{
+    ChatCompletionResponse, ChatCompletionStreamResponse, ChatRequestMessage, ChatResponseFormat,
+    CreateChatCompletionRequest, ToolRegistry, ToolRun, ToolRunOptions, ToolRunStop,
+};
 
 const CHAT_COMPLETION_URL: &str = "/v1/chat/completions";
 
@@ -26,24 +34,247 @@ impl<'a> ChatHandler<'a> {
         &self,
         request: CreateChatCompletionRequest,
     ) -> Result<ChatCompletionResponse, OpenAIError> {
+        let user = request.user.clone();
         let openai_request =
-            OpenAIRequest::with_body(Method::POST, CHAT_COMPLETION_URL.to_string(), request);
+            OpenAIRequest::with_body(Method::POST, CHAT_COMPLETION_URL.to_string(), request)?;
 
         let response = self.client.send(openai_request).await;
+        let response: ChatCompletionResponse = response?.json().await?;
+
+        self.client.track_usage(
+            UsageRecord::new(response.model.clone())
+                .with_user(user)
+                .with_tokens(&response.usage),
+        );
 
-        Ok(response?.json().await?)
+        Ok(response)
     }
 
     /// Creates a model response for the given chat conversation and returns a streaming response.
+    ///
+    /// The usage is recorded from the last chunk, which only carries it when `stream_options.include_usage` is set.
     pub async fn create_chat_completion_streaming(
         &self,
         request: CreateChatCompletionRequest,
     ) -> Result<OpenAIStream<ChatCompletionStreamResponse>, OpenAIError> {
+        let user = request.user.clone();
         let openai_request =
-            OpenAIRequest::with_body(Method::POST, CHAT_COMPLETION_URL.to_string(), request);
+            OpenAIRequest::with_body(Method::POST, CHAT_COMPLETION_URL.to_string(), request)?;
+
+        self.client
+            .create_stream(
+                openai_request,
+                move |chunk: &ChatCompletionStreamResponse| {
+                    let usage = chunk.usage.as_ref()?;
+
+                    Some(
+                        UsageRecord::new(chunk.model.clone())
+                            .with_user(user.clone())
+                            .with_tokens(usage),
+                    )
+                },
+            )
+            .await
+    }
+
+    /// Creates the next model response of a conversation, and appends the message of the model to it.
+    ///
+    /// The conversation is shortened first if it doesn't fit into the context window, see [`Conversation::fit`].
+    /// The context window is looked up in the [model registry](crate::ClientBuilder::model_registry) of the client.
+    #[cfg(feature = "tokenizer")]
+    pub async fn create_conversation_completion(
+        &self,
+        conversation: &mut Conversation,
+    ) -> Result<ChatCompletionResponse, OpenAIError> {
+        conversation.fit_with(self.client.model_registry()).await?;
+
+        let response = self
+            .create_chat_completion(conversation.to_request())
+            .await?;
+
+        if let Some(choice) = response.choices.first() {
+            conversation.push(choice.to_request_message());
+        }
+
+        Ok(response)
+    }
+
+    /// Creates a model response following the JSON Schema of `T`, using
+    /// [Structured Outputs](https://platform.openai.com/docs/guides/structured-outputs), and parses it into `T`.
+    ///
+    /// Fails with [`OpenAIError::StructuredOutput`] if the model refuses to answer,
+    /// the output is truncated or it doesn't match `T`.
+    pub async fn create_parsed<T>(
+        &self,
+        mut request: CreateChatCompletionRequest,
+    ) -> Result<T, OpenAIError>
+    where
+        T: DeserializeOwned + ToolSchema,
+    {
+        request.response_format = Some(ChatResponseFormat::json_schema::<T, _>(schema_name::<T>()));
+
+        let response = self.create_chat_completion(request).await?;
+        let choice = response.choices.into_iter().next().ok_or_else(|| {
+            OpenAIError::from(StructuredOutputError::MissingContent(
+                "no choices".to_string(),
+            ))
+        })?;
+
+        if let Some(refusal) = choice.message.refusal {
+            return Err(StructuredOutputError::Refusal(refusal).into());
+        }
+
+        if choice.finish_reason == "length" {
+            let partial = choice.message.content.unwrap_or_default();
+            return Err(StructuredOutputError::Truncated(partial).into());
+        }
+
+        let Some(content) = choice.message.content else {
+            return Err(StructuredOutputError::MissingContent(choice.finish_reason).into());
+        };
+
+        serde_json::from_str(&content)
+            .map_err(|error| StructuredOutputError::SchemaMismatch { content, error }.into())
+    }
 
-        let response = self.client.create_stream(openai_request).await?;
+    /// Runs the conversation until the model produces a message without tool calls.
+    ///
+    /// Every tool call of the model is executed with the registry, and its output is sent back in the
+    /// next request. Failing calls are reported to the model as an error output, so it can correct them.
+    /// The registered tools are added to the request, unless it already lists tools.
+    /// Only the first choice of each response is followed.
+    ///
+    /// If the timeout passes while tools are running, the calls that didn't finish are answered with an
+    /// error output, so the transcript of the run can still be sent to the model.
+    pub async fn run_with_tools(
+        &self,
+        mut request: CreateChatCompletionRequest,
+        registry: &ToolRegistry,
+        options: ToolRunOptions,
+    ) -> Result<ToolRun, OpenAIError> {
+        if request.tools.is_empty() {
+            request.tools = registry.tools();
+        }
+
+        let deadline = options.timeout.map(|timeout| Instant::now() + timeout);
+        let mut responses = vec![];
+
+        for _ in 0..options.max_iterations {
+            let Some(response) =
+                with_deadline(deadline, self.create_chat_completion(request.clone())).await
+            else {
+                return Ok(tool_run(request.messages, responses, ToolRunStop::Timeout));
+            };
+            let response = response?;
+
+            let Some(choice) = response.choices.first().cloned() else {
+                responses.push(response);
+                return Ok(tool_run(
+                    request.messages,
+                    responses,
+                    ToolRunStop::Completed,
+                ));
+            };
+
+            responses.push(response);
+            request.messages.push(choice.to_request_message());
+
+            if choice.tool_calls().is_empty() {
+                return Ok(tool_run(
+                    request.messages,
+                    responses,
+                    ToolRunStop::Completed,
+                ));
+            }
+
+            // Outputs of calls that finish before the deadline are kept, even if others don't
+            let mut outputs = vec![None; choice.tool_calls().len()];
+            let calls =
+                choice
+                    .tool_calls()
+                    .iter()
+                    .enumerate()
+                    .map(|(index, tool_call)| async move {
+                        (index, registry.call_for_model(tool_call).await)
+                    });
+
+            let finished = if options.parallel {
+                let mut calls = calls.collect::<FuturesUnordered<_>>();
+                with_deadline(deadline, async {
+                    while let Some((index, output)) = calls.next().await {
+                        outputs[index] = Some(output);
+                    }
+                })
+                .await
+            } else {
+                with_deadline(deadline, async {
+                    for call in calls {
+                        let (index, output) = call.await;
+                        outputs[index] = Some(output);
+                    }
+                })
+                .await
+            };
+
+            // Every tool call needs a reply, or the transcript can't be sent again
+            let tool_messages =
+                choice
+                    .tool_calls()
+                    .iter()
+                    .zip(outputs)
+                    .map(|(tool_call, output)| {
+                        let output = output.unwrap_or_else(|| {
+                            serde_json::json!({ "error": "The tool call timed out" }).to_string()
+                        });
+                        ChatRequestMessage::tool_message(tool_call.id.clone(), output)
+                    });
+            request.messages.extend(tool_messages);
+
+            if finished.is_none() {
+                return Ok(tool_run(request.messages, responses, ToolRunStop::Timeout));
+            }
+        }
+
+        Ok(tool_run(
+            request.messages,
+            responses,
+            ToolRunStop::MaxIterations,
+        ))
+    }
+}
+
+/// Names the schema of a type after the type, without its path and generics
+fn schema_name<T>() -> String {
+    let type_name = std::any::type_name::<T>();
+    let type_name = type_name.split('<').next().unwrap_or(type_name);
+    let type_name = type_name.rsplit("::").next().unwrap_or(type_name);
+
+    type_name
+        .chars()
+        .filter(|c| c.is_ascii_alphanumeric() || *c == '_' || *c == '-')
+        .take(64)
+        .collect()
+}
+
+/// Awaits the future, unless the deadline passes first
+async fn with_deadline<F: std::future::Future>(
+    deadline: Option<Instant>,
+    future: F,
+) -> Option<F::Output> {
+    match deadline {
+        Some(deadline) => tokio::time::timeout_at(deadline, future).await.ok(),
+        None => Some(future.await),
+    }
+}
 
-        Ok(OpenAIStream::new(response).await)
+fn tool_run(
+    messages: Vec<ChatRequestMessage>,
+    responses: Vec<ChatCompletionResponse>,
+    stop: ToolRunStop,
+) -> ToolRun {
+    ToolRun {
+        messages,
+        responses,
+        stop,
     }
 }
@@ -0,0 +1,169 @@
+use serde::{Deserialize, Deserializer, Serialize};
+
+/// Log probability information of a choice, returned when `logprobs` is set on the request.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ChoiceLogprobs {
+    /// The log probabilities of the tokens of the message content
+    #[serde(default, deserialize_with = "deserialize_null_default")]
+    pub content: Vec<TokenLogprob>,
+
+    /// The log probabilities of the tokens of the refusal message
+    #[serde(default, deserialize_with = "deserialize_null_default")]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub refusal: Vec<TokenLogprob>,
+}
+
+impl ChoiceLogprobs {
+    /// The log probability of the whole content, which is the sum of the log probabilities of its tokens.
+    pub fn sequence_logprob(&self) -> f64 {
+        self.content.iter().map(|token| token.logprob).sum()
+    }
+
+    /// The probability of the model generating the whole content.
+    pub fn sequence_probability(&self) -> f64 {
+        self.sequence_logprob().exp()
+    }
+
+    /// The perplexity per token of the content, the exponential of the negative mean log probability.
+    /// 1.0 means the model was certain of every token. `None` without tokens.
+    pub fn perplexity(&self) -> Option<f64> {
+        if self.content.is_empty() {
+            return None;
+        }
+
+        Some((-self.sequence_logprob() / self.content.len() as f64).exp())
+    }
+
+    /// The probability of the first token, the confidence of the model in its answer for
+    /// classification-style prompts, whose answer is a single label token. `None` without tokens.
+    pub fn confidence(&self) -> Option<f64> {
+        self.content.first().map(TokenLogprob::probability)
+    }
+
+    /// The probabilities of the labels as first token, from the top log probabilities of the first token,
+    /// for classification-style prompts. Labels are compared after trimming whitespace, and labels not
+    /// among the top log probabilities have a probability of 0.0.
+    pub fn label_probabilities<'a, I>(&self, labels: I) -> Vec<(&'a str, f64)>
+    where
+        I: IntoIterator<Item = &'a str>,
+    {
+        labels
+            .into_iter()
+            .map(|label| {
+                let probability = self
+                    .content
+                    .first()
+                    .map(|token| {
+                        token
+                            .top_logprobs
+                            .iter()
+                            .filter(|top| top.token.trim() == label.trim())
+                            .map(TopLogprob::probability)
+                            .sum()
+                    })
+                    .unwrap_or_default();
+
+                (label, probability)
+            })
+            .collect()
+    }
+}
+
+/// The log probability of a generated token.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TokenLogprob {
+    /// The token
+    pub token: String,
+
+    /// The log probability of the token, -9999.0 if it's very unlikely
+    pub logprob: f64,
+
+    /// The UTF-8 bytes of the token. Useful when characters are represented by multiple tokens,
+    /// and their bytes must be combined to decode them.
+    #[serde(default)]
+    pub bytes: Option<Vec<u8>>,
+
+    /// The most likely tokens at this position with their log probabilities, as many as `top_logprobs`
+    /// of the request. May be fewer in rare cases.
+    #[serde(default, deserialize_with = "deserialize_null_default")]
+    pub top_logprobs: Vec<TopLogprob>,
+}
+
+impl TokenLogprob {
+    /// The probability of the token.
+    pub fn probability(&self) -> f64 {
+        self.logprob.exp()
+    }
+}
+
+/// One of the most likely tokens at a position.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TopLogprob {
+    /// The token
+    pub token: String,
+
+    /// The log probability of the token
+    pub logprob: f64,
+
+    /// The UTF-8 bytes of the token
+    #[serde(default)]
+    pub bytes: Option<Vec<u8>>,
+}
+
+impl TopLogprob {
+    /// The probability of the token.
+    pub fn probability(&self) -> f64 {
+        self.logprob.exp()
+    }
+}
+
+/// Lists are `null` when there are no log probabilities for them
+fn deserialize_null_default<'de, D, T>(deserializer: D) -> Result<T, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Default + Deserialize<'de>,
+{
+    Ok(Option::<T>::deserialize(deserializer)?.unwrap_or_default())
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn computes_probabilities_correctly() {
+        let json = json!({
+          "content": [
+            {
+              "token": "Positive",
+              "logprob": -0.1,
+              "bytes": [80, 111, 115, 105, 116, 105, 118, 101],
+              "top_logprobs": [
+                { "token": "Positive", "logprob": -0.1, "bytes": null },
+                { "token": " Negative", "logprob": -2.5, "bytes": null }
+              ]
+            },
+            { "token": ".", "logprob": -0.3, "bytes": [46], "top_logprobs": [] }
+          ],
+          "refusal": null
+        });
+
+        let logprobs: ChoiceLogprobs = serde_json::from_value(json).unwrap();
+
+        assert_eq!(logprobs.content.len(), 2);
+        assert!(logprobs.refusal.is_empty());
+        assert!((logprobs.sequence_logprob() + 0.4).abs() < 1e-9);
+        assert!((logprobs.sequence_probability() - (-0.4f64).exp()).abs() < 1e-9);
+        assert!((logprobs.perplexity().unwrap() - 0.2f64.exp()).abs() < 1e-9);
+        assert!((logprobs.confidence().unwrap() - (-0.1f64).exp()).abs() < 1e-9);
+
+        let labels = logprobs.label_probabilities(["Positive", "Negative", "Neutral"]);
+        assert!((labels[0].1 - (-0.1f64).exp()).abs() < 1e-9);
+        assert!((labels[1].1 - (-2.5f64).exp()).abs() < 1e-9);
+        assert_eq!(labels[2].1, 0.0);
+
+        assert_eq!(ChoiceLogprobs::default().perplexity(), None);
+    }
+}
@@ -1,7 +1,13 @@
 //! Given a list of messages comprising a conversation, the model will return a response.
 //! Related guide: [Chat completions](https://platform.openai.com/docs/api-reference/chat)
 
+mod accumulator;
+mod content;
+#[cfg(feature = "tokenizer")]
+mod conversation;
 mod handler;
+mod logprobs;
+mod partial_json;
 mod request;
 mod response;
 mod response_chunk;
@@ -9,10 +15,17 @@ mod response_format;
 mod streaming_response;
 mod tooling;
 
+pub use self::accumulator::*;
+pub use self::content::*;
+#[cfg(feature = "tokenizer")]
+pub use self::conversation::*;
 pub use self::handler::*;
+pub use self::logprobs::*;
+pub use self::partial_json::*;
 pub use self::request::*;
 pub use self::response::*;
 pub use self::response_chunk::*;
+pub use self::response_format::JsonSchemaFormat;
 pub use self::response_format::ResponseFormat as ChatResponseFormat;
 pub use self::response_format::ResponseFormatType as ChatResponseFormatType;
 pub use self::streaming_response::*;
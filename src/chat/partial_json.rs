@@ -0,0 +1,613 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use serde_json::{Map, Number, Value};
+
+use super::ChatCompletionStreamResponse;
+
+/// Parses a JSON document that may be cut off, closing open strings, arrays and objects.
+///
+/// Members whose value hasn't started yet are left out, and so are numbers that aren't valid yet, such as `-`.
+/// Partial literals are completed, e.g. `tr` is parsed as `true`. Returns `None` if no value has started,
+/// or the document is invalid before any value could be parsed.
+pub fn parse_partial_json(input: &str) -> Option<Value> {
+    PartialParser::new(input)
+        .parse_value(String::new())
+        .map(|parsed| parsed.value)
+}
+
+/// An event of a [`PartialJsonParser`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum PartialJsonEvent {
+    /// The best-effort value of the document changed
+    Updated(Value),
+
+    /// The value at the path, a [JSON Pointer](https://datatracker.ietf.org/doc/html/rfc6901) such as `/address/city`,
+    /// is complete and won't change anymore. Sent for object members and array elements.
+    FieldCompleted {
+        /// The JSON Pointer of the value
+        path: String,
+
+        /// The complete value
+        value: Value,
+    },
+
+    /// The whole document is complete
+    Completed(Value),
+}
+
+/// Parses a JSON document incrementally, as it's streamed in fragments.
+///
+/// After each fragment, the best-effort value of the document so far is available, see [`parse_partial_json`],
+/// along with events for the values completed by the fragment.
+#[derive(Debug, Default, Clone)]
+pub struct PartialJsonParser {
+    buffer: String,
+    value: Option<Value>,
+    completed: BTreeSet<String>,
+    is_complete: bool,
+}
+
+impl PartialJsonParser {
+    /// Creates a parser for an empty document.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Appends a fragment to the document, and returns the events it caused: first [`PartialJsonEvent::Updated`]
+    /// if the value changed, then the completed fields, innermost first, and [`PartialJsonEvent::Completed`] last.
+    pub fn push(&mut self, fragment: &str) -> Vec<PartialJsonEvent> {
+        if fragment.is_empty() {
+            return vec![];
+        }
+
+        self.buffer.push_str(fragment);
+
+        let mut parser = PartialParser::new(&self.buffer);
+        let Some(parsed) = parser.parse_value(String::new()) else {
+            return vec![];
+        };
+
+        let mut events = vec![];
+
+        if self.value.as_ref() != Some(&parsed.value) {
+            events.push(PartialJsonEvent::Updated(parsed.value.clone()));
+        }
+
+        for path in parser.completed {
+            if path.is_empty() || self.completed.contains(&path) {
+                continue;
+            }
+
+            if let Some(value) = parsed.value.pointer(&path) {
+                events.push(PartialJsonEvent::FieldCompleted {
+                    path: path.clone(),
+                    value: value.clone(),
+                });
+            }
+
+            let _ = self.completed.insert(path);
+        }
+
+        if parsed.complete && !self.is_complete {
+            self.is_complete = true;
+            events.push(PartialJsonEvent::Completed(parsed.value.clone()));
+        }
+
+        self.value = Some(parsed.value);
+
+        events
+    }
+
+    /// The best-effort value of the document so far.
+    pub fn value(&self) -> Option<&Value> {
+        self.value.as_ref()
+    }
+
+    /// The fragments received so far.
+    pub fn buffer(&self) -> &str {
+        &self.buffer
+    }
+
+    /// Whether the document is complete.
+    pub fn is_complete(&self) -> bool {
+        self.is_complete
+    }
+}
+
+/// Where the JSON of a [`PartialOutputEvent`] comes from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PartialOutputSource {
+    /// The content of the message, e.g. in JSON mode or with Structured Outputs
+    Content,
+
+    /// The arguments of the tool call at the index
+    ToolCall(usize),
+}
+
+/// An event of a [`PartialChatOutput`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct PartialOutputEvent {
+    /// The JSON document the event belongs to
+    pub source: PartialOutputSource,
+
+    /// What happened to the document
+    pub event: PartialJsonEvent,
+}
+
+/// A tool call whose arguments are being streamed.
+#[derive(Debug, Default, Clone)]
+pub struct PartialToolCall {
+    /// The ID of the tool call
+    pub id: Option<String>,
+
+    /// The name of the function
+    pub name: Option<String>,
+
+    /// The arguments parsed so far
+    pub arguments: PartialJsonParser,
+}
+
+/// Parses the JSON content and tool call arguments of a streamed chat completion as they arrive.
+///
+/// Feed it every chunk of an [`OpenAIStream<ChatCompletionStreamResponse>`](crate::OpenAIStream).
+/// Only the first choice is parsed.
+#[derive(Debug, Default, Clone)]
+pub struct PartialChatOutput {
+    content: PartialJsonParser,
+    tool_calls: BTreeMap<usize, PartialToolCall>,
+}
+
+impl PartialChatOutput {
+    /// Creates a parser for a new stream.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Parses the deltas of the chunk, and returns the events they caused.
+    pub fn push(&mut self, chunk: &ChatCompletionStreamResponse) -> Vec<PartialOutputEvent> {
+        let mut events = vec![];
+
+        let Some(choice) = chunk.choices.iter().find(|choice| choice.index == 0) else {
+            return events;
+        };
+
+        if let Some(content) = &choice.delta.content {
+            events.extend(
+                self.content
+                    .push(content)
+                    .into_iter()
+                    .map(|event| PartialOutputEvent {
+                        source: PartialOutputSource::Content,
+                        event,
+                    }),
+            );
+        }
+
+        for delta in &choice.delta.tool_calls {
+            let tool_call = self.tool_calls.entry(delta.index).or_default();
+
+            if delta.id.is_some() {
+                tool_call.id.clone_from(&delta.id);
+            }
+
+            let Some(function) = &delta.function else {
+                continue;
+            };
+
+            if function.name.is_some() {
+                tool_call.name.clone_from(&function.name);
+            }
+
+            if let Some(arguments) = &function.arguments {
+                events.extend(
+                    tool_call
+                        .arguments
+                        .push(arguments)
+                        .into_iter()
+                        .map(|event| PartialOutputEvent {
+                            source: PartialOutputSource::ToolCall(delta.index),
+                            event,
+                        }),
+                );
+            }
+        }
+
+        events
+    }
+
+    /// The best-effort value of the content so far.
+    pub fn content(&self) -> Option<&Value> {
+        self.content.value()
+    }
+
+    /// The tool call at the index.
+    pub fn tool_call(&self, index: usize) -> Option<&PartialToolCall> {
+        self.tool_calls.get(&index)
+    }
+
+    /// The tool calls so far, ordered by their index.
+    pub fn tool_calls(&self) -> impl Iterator<Item = &PartialToolCall> {
+        self.tool_calls.values()
+    }
+}
+
+/// A parsed value, and whether it was closed
+struct Parsed {
+    value: Value,
+    complete: bool,
+}
+
+impl Parsed {
+    fn new(value: Value, complete: bool) -> Self {
+        Self { value, complete }
+    }
+}
+
+/// A recursive descent parser, which stops at the end of the input or at the first error
+struct PartialParser<'a> {
+    input: &'a str,
+    position: usize,
+
+    /// JSON Pointers of the values that were closed, innermost first
+    completed: Vec<String>,
+}
+
+impl<'a> PartialParser<'a> {
+    fn new(input: &'a str) -> Self {
+        Self {
+            input,
+            position: 0,
+            completed: vec![],
+        }
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.input[self.position..].chars().next()
+    }
+
+    fn advance(&mut self, c: char) {
+        self.position += c.len_utf8();
+    }
+
+    fn skip_whitespace(&mut self) {
+        while let Some(c) = self.peek().filter(|c| c.is_whitespace()) {
+            self.advance(c);
+        }
+    }
+
+    fn parse_value(&mut self, path: String) -> Option<Parsed> {
+        self.skip_whitespace();
+
+        let parsed = match self.peek()? {
+            '{' => self.parse_object(&path),
+            '[' => self.parse_array(&path),
+            '"' => {
+                let (string, complete) = self.parse_string();
+                Parsed::new(Value::String(string), complete)
+            }
+            '-' | '0'..='9' => self.parse_number()?,
+            _ => self.parse_literal()?,
+        };
+
+        if parsed.complete {
+            self.completed.push(path);
+        }
+
+        Some(parsed)
+    }
+
+    fn parse_object(&mut self, path: &str) -> Parsed {
+        self.advance('{');
+        let mut object = Map::new();
+
+        loop {
+            self.skip_whitespace();
+
+            match self.peek() {
+                Some('}') => {
+                    self.advance('}');
+                    return Parsed::new(Value::Object(object), true);
+                }
+                Some(',') => self.advance(','),
+                Some('"') => {
+                    let (key, complete) = self.parse_string();
+                    self.skip_whitespace();
+
+                    if !complete || self.peek() != Some(':') {
+                        return Parsed::new(Value::Object(object), false);
+                    }
+                    self.advance(':');
+
+                    let child = format!("{}/{}", path, escape_pointer(&key));
+                    let Some(parsed) = self.parse_value(child) else {
+                        return Parsed::new(Value::Object(object), false);
+                    };
+
+                    let _ = object.insert(key, parsed.value);
+                    if !parsed.complete {
+                        return Parsed::new(Value::Object(object), false);
+                    }
+                }
+                _ => return Parsed::new(Value::Object(object), false),
+            }
+        }
+    }
+
+    fn parse_array(&mut self, path: &str) -> Parsed {
+        self.advance('[');
+        let mut array = vec![];
+
+        loop {
+            self.skip_whitespace();
+
+            match self.peek() {
+                Some(']') => {
+                    self.advance(']');
+                    return Parsed::new(Value::Array(array), true);
+                }
+                Some(',') => self.advance(','),
+                Some(_) => {
+                    let child = format!("{}/{}", path, array.len());
+                    let Some(parsed) = self.parse_value(child) else {
+                        return Parsed::new(Value::Array(array), false);
+                    };
+
+                    array.push(parsed.value);
+                    if !parsed.complete {
+                        return Parsed::new(Value::Array(array), false);
+                    }
+                }
+                None => return Parsed::new(Value::Array(array), false),
+            }
+        }
+    }
+
+    /// Parses a string, and returns it with whether it was closed. Incomplete escapes are left out.
+    fn parse_string(&mut self) -> (String, bool) {
+        self.advance('"');
+        let mut string = String::new();
+
+        while let Some(c) = self.peek() {
+            self.advance(c);
+
+            match c {
+                '"' => return (string, true),
+                '\\' => match self.parse_escape() {
+                    Some(c) => string.push(c),
+                    None => return (string, false),
+                },
+                c => string.push(c),
+            }
+        }
+
+        (string, false)
+    }
+
+    fn parse_escape(&mut self) -> Option<char> {
+        let c = self.peek()?;
+        self.advance(c);
+
+        Some(match c {
+            'b' => '\u{8}',
+            'f' => '\u{c}',
+            'n' => '\n',
+            'r' => '\r',
+            't' => '\t',
+            'u' => {
+                let code = self.parse_hex()?;
+
+                if (0xD800..0xDC00).contains(&code) {
+                    // A high surrogate, which is followed by an escaped low surrogate
+                    if !self.input[self.position..].starts_with("\\u") {
+                        return None;
+                    }
+                    self.position += 2;
+
+                    let low = self.parse_hex()?;
+                    let code = 0x10000 + ((code - 0xD800) << 10) + low.wrapping_sub(0xDC00);
+                    char::from_u32(code).unwrap_or(char::REPLACEMENT_CHARACTER)
+                } else {
+                    char::from_u32(code).unwrap_or(char::REPLACEMENT_CHARACTER)
+                }
+            }
+            c => c,
+        })
+    }
+
+    fn parse_hex(&mut self) -> Option<u32> {
+        let hex = self.input.get(self.position..self.position + 4)?;
+        let code = u32::from_str_radix(hex, 16).ok()?;
+        self.position += 4;
+
+        Some(code)
+    }
+
+    fn parse_number(&mut self) -> Option<Parsed> {
+        let start = self.position;
+        while let Some(c) = self
+            .peek()
+            .filter(|c| c.is_ascii_digit() || matches!(c, '-' | '+' | '.' | 'e' | 'E'))
+        {
+            self.advance(c);
+        }
+
+        // The number may continue in the next fragment, unless something follows it
+        let complete = self.peek().is_some();
+        let text = &self.input[start..self.position];
+        let text = if complete {
+            text
+        } else {
+            text.trim_end_matches(['-', '+', '.', 'e', 'E'])
+        };
+
+        let number = serde_json::from_str::<Number>(text).ok()?;
+
+        Some(Parsed::new(Value::Number(number), complete))
+    }
+
+    fn parse_literal(&mut self) -> Option<Parsed> {
+        let rest = &self.input[self.position..];
+
+        for (literal, value) in [
+            ("true", Value::Bool(true)),
+            ("false", Value::Bool(false)),
+            ("null", Value::Null),
+        ] {
+            if rest.starts_with(literal) {
+                self.position += literal.len();
+                return Some(Parsed::new(value, true));
+            }
+
+            if !rest.is_empty() && literal.starts_with(rest) {
+                self.position += rest.len();
+                return Some(Parsed::new(value, false));
+            }
+        }
+
+        None
+    }
+}
+
+/// Escapes a key for a JSON Pointer
+fn escape_pointer(key: &str) -> String {
+    key.replace('~', "~0").replace('/', "~1")
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn parses_partial_json_correctly() {
+        let cases = [
+            ("", None),
+            ("  ", None),
+            ("{", Some(json!({}))),
+            (r#"{"na"#, Some(json!({}))),
+            (r#"{"name""#, Some(json!({}))),
+            (r#"{"name": "Jo"#, Some(json!({ "name": "Jo" }))),
+            (r#"{"name": "Jo\"#, Some(json!({ "name": "Jo" }))),
+            (r#"{"name": "Jo\n"#, Some(json!({ "name": "Jo\n" }))),
+            (r#"{"name": "é"#, Some(json!({ "name": "é" }))),
+            (r#"{"name": "\u00"#, Some(json!({ "name": "" }))),
+            (r#"{"name": "😀"#, Some(json!({ "name": "😀" }))),
+            (r#"{"age": -"#, Some(json!({}))),
+            (r#"{"age": 4"#, Some(json!({ "age": 4 }))),
+            (r#"{"age": 4.5e"#, Some(json!({ "age": 4.5 }))),
+            (r#"{"ok": tr"#, Some(json!({ "ok": true }))),
+            (r#"{"tags": ["a", "b"#, Some(json!({ "tags": ["a", "b"] }))),
+            (r#"{"tags": ["a",],"#, Some(json!({ "tags": ["a"] }))),
+            (
+                r#"[{"a": {"b": [1, 2"#,
+                Some(json!([{ "a": { "b": [1, 2] } }])),
+            ),
+            (r#"{"a": 1} trailing"#, Some(json!({ "a": 1 }))),
+        ];
+
+        for (input, expectation) in cases {
+            assert_eq!(parse_partial_json(input), expectation, "input: {}", input);
+        }
+    }
+
+    #[test]
+    fn parses_every_prefix() {
+        let document = json!({
+            "name": "Zoë \"the\" 😀",
+            "age": -12.5e3,
+            "tags": ["a", { "b": [true, false, null] }],
+            "nested": { "empty": {}, "list": [] }
+        })
+        .to_string();
+
+        for (index, _) in document.char_indices() {
+            let _ = parse_partial_json(&document[..index]);
+        }
+
+        assert_eq!(
+            parse_partial_json(&document),
+            serde_json::from_str(&document).ok()
+        );
+    }
+
+    #[test]
+    fn emits_events_correctly() {
+        let mut parser = PartialJsonParser::new();
+
+        assert_eq!(
+            parser.push(r#"{"name": "Jo"#),
+            vec![PartialJsonEvent::Updated(json!({ "name": "Jo" }))]
+        );
+
+        assert_eq!(
+            parser.push(r#"hn", "address": {"city": "Paris""#),
+            vec![
+                PartialJsonEvent::Updated(
+                    json!({ "name": "John", "address": { "city": "Paris" } })
+                ),
+                PartialJsonEvent::FieldCompleted {
+                    path: "/name".to_string(),
+                    value: json!("John"),
+                },
+                PartialJsonEvent::FieldCompleted {
+                    path: "/address/city".to_string(),
+                    value: json!("Paris"),
+                },
+            ]
+        );
+
+        let events = parser.push("}}");
+        assert_eq!(
+            events,
+            vec![
+                PartialJsonEvent::FieldCompleted {
+                    path: "/address".to_string(),
+                    value: json!({ "city": "Paris" }),
+                },
+                PartialJsonEvent::Completed(
+                    json!({ "name": "John", "address": { "city": "Paris" } })
+                ),
+            ]
+        );
+        assert!(parser.is_complete());
+        assert!(parser.push(" ").is_empty());
+    }
+
+    #[test]
+    fn parses_streamed_tool_calls() {
+        let chunk = |arguments: &str| -> ChatCompletionStreamResponse {
+            serde_json::from_value(json!({
+                "id": "chatcmpl-123",
+                "object": "chat.completion.chunk",
+                "created": 1694268190,
+                "model": "gpt-4o",
+                "choices": [{
+                    "index": 0,
+                    "delta": { "tool_calls": [
+                        { "index": 0, "id": "call_abc", "function": { "name": "get_weather", "arguments": arguments } }
+                    ] },
+                    "finish_reason": null
+                }]
+            }))
+            .unwrap()
+        };
+
+        let mut output = PartialChatOutput::new();
+        let _ = output.push(&chunk(r#"{"city": "Par"#));
+
+        let tool_call = output.tool_call(0).unwrap();
+        assert_eq!(tool_call.name.as_deref(), Some("get_weather"));
+        assert_eq!(tool_call.arguments.value(), Some(&json!({ "city": "Par" })));
+
+        let events = output.push(&chunk(r#"is"}"#));
+        assert!(events
+            .iter()
+            .all(|event| event.source == PartialOutputSource::ToolCall(0)));
+        assert_eq!(
+            events.last().unwrap().event,
+            PartialJsonEvent::Completed(json!({ "city": "Paris" }))
+        );
+        assert_eq!(output.content(), None);
+    }
+}
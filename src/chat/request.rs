@@ -2,9 +2,9 @@ use std::collections::HashMap;
 
 use serde::{Deserialize, Serialize};
 
-use crate::common::{ChatModel, MessageRole};
+use crate::common::{MessageRole, ModelId};
 
-use super::{ChatResponseFormat, Tool, ToolChoice};
+use super::{ChatMessageContent, ChatResponseFormat, Tool, ToolCall, ToolChoice};
 
 /// Request to the Chat API
 #[derive(Debug, Default, Clone, Serialize, Deserialize)]
@@ -35,6 +35,15 @@ pub struct CreateChatCompletionRequest {
     #[serde(skip_serializing_if = "HashMap::is_empty")]
     pub logit_bias: HashMap<String, i32>,
 
+    /// Whether to return the log probabilities of the generated tokens, in the `logprobs` of each choice.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub logprobs: Option<bool>,
+
+    /// The number of most likely tokens to return at each position, between 0 and 20, each with its log probability.
+    /// Requires `logprobs` to be set.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub top_logprobs: Option<u8>,
+
     /// The maximum number of [tokens](https://platform.openai.com/tokenizer) to generate in the chat completion.
     /// The total length of input tokens and generated tokens is limited by the model's context length.
     /// [Example Python code](https://github.com/openai/openai-cookbook/blob/main/examples/How_to_count_tokens_with_tiktoken.ipynb)
@@ -82,6 +91,10 @@ pub struct CreateChatCompletionRequest {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub stream: Option<bool>,
 
+    /// Options for streaming responses. Only set this when `stream` is `true`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stream_options: Option<ChatStreamOptions>,
+
     /// What sampling temperature to use, between 0 and 2.
     /// Higher values like 0.8 will make the output more random,
     /// while lower values like 0.2 will make it more focused and deterministic.
@@ -123,10 +136,10 @@ impl CreateChatCompletionRequest {
     /// Creates a new [`ChatCompletionRequest`] with a single message from a given user query.
     pub fn from_user_query<S>(query: S) -> Self
     where
-        S: Into<String>,
+        S: Into<ChatMessageContent>,
     {
         let messages = vec![ChatRequestMessage {
-            content: query.into(),
+            content: Some(query.into()),
             ..Default::default()
         }];
 
@@ -145,8 +158,11 @@ impl CreateChatCompletionRequest {
     }
 
     /// Sets the model to use for the request.
-    pub fn set_model(mut self, model: ChatModel) -> Self {
-        self.model = model.to_string();
+    pub fn set_model<M>(mut self, model: M) -> Self
+    where
+        M: Into<ModelId>,
+    {
+        self.model = model.into().into();
         self
     }
 
@@ -162,6 +178,13 @@ impl CreateChatCompletionRequest {
         self
     }
 
+    /// Requests the log probabilities of the generated tokens, with the given number of most likely tokens at each position.
+    pub fn set_logprobs(mut self, top_logprobs: u8) -> Self {
+        self.logprobs = Some(true);
+        self.top_logprobs = Some(top_logprobs);
+        self
+    }
+
     /// Sets max tokens for chat gpt model.
     pub fn set_max_tokens(mut self, max_tokens: i32) -> Self {
         self.max_tokens = Some(max_tokens);
@@ -176,8 +199,16 @@ pub struct ChatRequestMessage {
     pub role: MessageRole,
 
     /// The contents of the message. `content` is required for all messages,
-    /// and may be null for assistant messages with function calls.
-    pub content: String,
+    /// and may be omitted for assistant messages with tool calls or a refusal.
+    /// User messages may contain images and audio, see [`ChatContentPart`](super::ChatContentPart).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    pub content: Option<ChatMessageContent>,
+
+    /// The refusal message of the model, for assistant messages.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    pub refusal: Option<String>,
 
     /// The name of the author of this message. `name` is required if role is `function`,
     /// and it should be the name of the function whose response is in the `content`.
@@ -190,6 +221,16 @@ pub struct ChatRequestMessage {
     #[serde(skip_serializing_if = "Option::is_none")]
     #[serde(default)]
     pub function_call: Option<String>,
+
+    /// The tool calls generated by the model, for assistant messages.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    #[serde(default)]
+    pub tool_calls: Vec<ToolCall>,
+
+    /// The ID of the tool call this message is the output of, for tool messages.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    pub tool_call_id: Option<String>,
 }
 
 impl ChatRequestMessage {
@@ -197,7 +238,7 @@ impl ChatRequestMessage {
     pub fn new(role: MessageRole, content: &str) -> Self {
         Self {
             role,
-            content: content.to_string(),
+            content: Some(content.into()),
             ..Default::default()
         }
     }
@@ -205,11 +246,11 @@ impl ChatRequestMessage {
     /// Creates a new [`ChatRequestMessage`] with the given content and system role.
     pub fn system_message<S>(content: S) -> Self
     where
-        S: Into<String>,
+        S: Into<ChatMessageContent>,
     {
         Self {
             role: MessageRole::System,
-            content: content.into(),
+            content: Some(content.into()),
             ..Default::default()
         }
     }
@@ -217,11 +258,11 @@ impl ChatRequestMessage {
     /// Creates a new [`ChatRequestMessage`] with the given content and user role.
     pub fn user_message<S>(content: S) -> Self
     where
-        S: Into<String>,
+        S: Into<ChatMessageContent>,
     {
         Self {
             role: MessageRole::User,
-            content: content.into(),
+            content: Some(content.into()),
             ..Default::default()
         }
     }
@@ -229,11 +270,34 @@ impl ChatRequestMessage {
     /// Creates a new [`ChatRequestMessage`] with the given content and assistant role.
     pub fn assistant_message<S>(content: S) -> Self
     where
-        S: Into<String>,
+        S: Into<ChatMessageContent>,
     {
         Self {
             role: MessageRole::Assistant,
-            content: content.into(),
+            content: Some(content.into()),
+            ..Default::default()
+        }
+    }
+
+    /// Creates a new [`ChatRequestMessage`] with the tool calls of the model and assistant role.
+    pub fn assistant_tool_calls_message(tool_calls: Vec<ToolCall>) -> Self {
+        Self {
+            role: MessageRole::Assistant,
+            tool_calls,
+            ..Default::default()
+        }
+    }
+
+    /// Creates a new [`ChatRequestMessage`] with the output of the tool call with the given ID and tool role.
+    pub fn tool_message<I, S>(tool_call_id: I, content: S) -> Self
+    where
+        I: Into<String>,
+        S: Into<ChatMessageContent>,
+    {
+        Self {
+            role: MessageRole::Tool,
+            content: Some(content.into()),
+            tool_call_id: Some(tool_call_id.into()),
             ..Default::default()
         }
     }
@@ -249,9 +313,20 @@ impl ChatRequestMessage {
     }
 }
 
+/// Options for streaming responses of the Chat API
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ChatStreamOptions {
+    /// If set, an additional chunk is streamed before the `data: [DONE]` message, with the token usage
+    /// of the entire request in its `usage` field and an empty `choices` array.
+    pub include_usage: bool,
+}
+
 #[cfg(test)]
 mod tests {
-    use crate::{FunctionChoice, ToolChoiceObject};
+    use crate::{
+        chat::{FunctionChoice, ToolChoiceObject},
+        ChatModel,
+    };
 
     use super::*;
     use serde_json::json;
@@ -263,12 +338,12 @@ mod tests {
             messages: vec![
                 ChatRequestMessage {
                     role: MessageRole::System,
-                    content: "You are a helpful assistant.".to_string(),
+                    content: Some("You are a helpful assistant.".into()),
                     ..Default::default()
                 },
                 ChatRequestMessage {
                     role: MessageRole::User,
-                    content: "Hello!".to_string(),
+                    content: Some("Hello!".into()),
                     ..Default::default()
                 },
             ],
@@ -296,7 +371,6 @@ mod tests {
               "content": "Hello!"
             }
           ],
-          "tools": [],
           "tool_choice": {
             "type": "function",
             "function": {
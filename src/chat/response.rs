@@ -1,8 +1,8 @@
 use serde::{Deserialize, Serialize};
 
-use crate::common::{ChatModel, TokenUsage};
+use crate::common::{ChatModel, MessageRole, TokenUsage};
 
-use super::ChatResponseChunk;
+use super::{ChatRequestMessage, ChatResponseChunk, ChoiceLogprobs, ToolCall};
 
 /// Represents a chat completion response returned by model, based on the provided input
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -42,6 +42,14 @@ impl ChatCompletionResponse {
             .as_ref()
     }
 
+    /// Returns the tool calls of the first [`ChatCompletionChoice`]
+    pub fn tool_calls(&self) -> &[ToolCall] {
+        self.choices
+            .first()
+            .map(ChatCompletionChoice::tool_calls)
+            .unwrap_or_default()
+    }
+
     /// Returns the messages content from [`ChatCompletionChoice`] at the given index
     pub fn message_at_index(&self, index: usize) -> Option<&String> {
         self.choices
@@ -61,6 +69,11 @@ pub struct ChatCompletionChoice {
     /// A chat completion message generated by the model
     pub message: ChatResponseChunk,
 
+    /// Log probability information for the choice, if `logprobs` was set on the request
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub logprobs: Option<ChoiceLogprobs>,
+
     /// The reason the model stopped generating tokens. This will be `stop` if the model hit a natural stop point or a provided stop sequence,
     /// `length` if the maximum number of tokens specified in the request was reached,
     /// `content_filter` if content was omitted due to a flag from our content filters,
@@ -69,9 +82,47 @@ pub struct ChatCompletionChoice {
     pub finish_reason: String,
 }
 
+impl ChatCompletionChoice {
+    /// Returns the tool calls the model made in this choice.
+    pub fn tool_calls(&self) -> &[ToolCall] {
+        &self.message.tool_calls
+    }
+
+    /// Turns the message of the model into a request message, so it can be sent back in the next request.
+    pub fn to_request_message(&self) -> ChatRequestMessage {
+        ChatRequestMessage {
+            role: self.message.role.clone().unwrap_or(MessageRole::Assistant),
+            content: self.message.content.clone().map(Into::into),
+            refusal: self.message.refusal.clone(),
+            tool_calls: self.message.tool_calls.clone(),
+            ..Default::default()
+        }
+    }
+
+    /// Builds the messages to append to the conversation for the next request: the message of the model
+    /// with its tool calls, followed by a tool message for each pair of tool call ID and output.
+    pub fn next_messages<I, K, V>(&self, tool_outputs: I) -> Vec<ChatRequestMessage>
+    where
+        I: IntoIterator<Item = (K, V)>,
+        K: Into<String>,
+        V: Into<String>,
+    {
+        std::iter::once(self.to_request_message())
+            .chain(
+                tool_outputs
+                    .into_iter()
+                    .map(|(id, output)| ChatRequestMessage::tool_message(id, output.into())),
+            )
+            .collect()
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use crate::{FunctionCall, MessageRole, ToolCall, ToolType};
+    use crate::{
+        chat::{FunctionCall, ToolCall, ToolType},
+        MessageRole,
+    };
 
     use super::*;
     use serde_json::json;
@@ -91,7 +142,7 @@ mod tests {
               "content": "\n\nHello there, how may I assist you today?",
               "tool_calls": [
                 {
-                  "name": "tool",
+                  "id": "call_abc123",
                   "type": "function",
                   "function": {
                     "name": "set_response",
@@ -122,7 +173,7 @@ mod tests {
                 message: ChatResponseChunk {
                     role: Some(MessageRole::Assistant),
                     tool_calls: vec![ToolCall {
-                        name: "tool".to_string(),
+                        id: "call_abc123".to_string(),
                         _type: ToolType::Function,
                         function: FunctionCall {
                             name: "set_response".to_string(),
@@ -130,7 +181,9 @@ mod tests {
                         },
                     }],
                     content: Some("\n\nHello there, how may I assist you today?".to_string()),
+                    refusal: None,
                 },
+                logprobs: None,
                 finish_reason: "stop".to_string(),
             }],
             usage: TokenUsage {
@@ -142,4 +195,62 @@ mod tests {
 
         assert_eq!(response, expectation);
     }
+
+    #[test]
+    fn builds_next_messages_correctly() {
+        let choice = ChatCompletionChoice {
+            index: 0,
+            message: ChatResponseChunk {
+                role: Some(MessageRole::Assistant),
+                content: None,
+                tool_calls: vec![ToolCall {
+                    id: "call_abc123".to_string(),
+                    _type: ToolType::Function,
+                    function: FunctionCall {
+                        name: "get_weather".to_string(),
+                        arguments: "{\"city\":\"Paris\"}".to_string(),
+                    },
+                }],
+                refusal: None,
+            },
+            logprobs: None,
+            finish_reason: "tool_calls".to_string(),
+        };
+
+        let messages = choice.next_messages([("call_abc123", "22 degrees")]);
+
+        let json = json!([
+          {
+            "role": "assistant",
+            "tool_calls": [{
+              "id": "call_abc123",
+              "type": "function",
+              "function": { "name": "get_weather", "arguments": "{\"city\":\"Paris\"}" }
+            }]
+          },
+          {
+            "role": "tool",
+            "content": "22 degrees",
+            "tool_call_id": "call_abc123"
+          }
+        ]);
+
+        assert_eq!(serde_json::to_value(messages).unwrap(), json);
+
+        let refusal = ChatCompletionChoice {
+            message: ChatResponseChunk {
+                role: Some(MessageRole::Assistant),
+                content: None,
+                tool_calls: vec![],
+                refusal: Some("I can't help with that.".to_string()),
+            },
+            finish_reason: "stop".to_string(),
+            ..choice
+        };
+
+        assert_eq!(
+            serde_json::to_value(refusal.to_request_message()).unwrap(),
+            json!({ "role": "assistant", "refusal": "I can't help with that." })
+        );
+    }
 }
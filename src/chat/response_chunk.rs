@@ -18,4 +18,9 @@ pub struct ChatResponseChunk {
     /// List of tool calls
     #[serde(default)]
     pub tool_calls: Vec<ToolCall>,
+
+    /// The refusal message of the model, if it refused to answer in the requested format
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    pub refusal: Option<String>,
 }
@@ -1,11 +1,37 @@
 use serde::{Deserialize, Serialize};
 
+use crate::common::ToolSchema;
+
 /// An object specifying the format that the model must output
 #[derive(Debug, Default, Clone, Serialize, Deserialize)]
 pub struct ResponseFormat {
     #[serde(rename = "type")]
     /// Model output format
     pub _type: ResponseFormatType,
+
+    /// The schema the output must follow, for the `json_schema` format
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    pub json_schema: Option<JsonSchemaFormat>,
+}
+
+impl ResponseFormat {
+    /// Creates a `json_schema` format with the strict JSON Schema of `T`, see [Structured Outputs](https://platform.openai.com/docs/guides/structured-outputs).
+    pub fn json_schema<T, S>(name: S) -> Self
+    where
+        T: ToolSchema,
+        S: Into<String>,
+    {
+        Self {
+            _type: ResponseFormatType::JsonSchema,
+            json_schema: Some(JsonSchemaFormat {
+                name: name.into(),
+                description: None,
+                schema: T::strict_json_schema(),
+                strict: Some(true),
+            }),
+        }
+    }
 }
 
 /// Model output formats.
@@ -18,4 +44,28 @@ pub enum ResponseFormatType {
     /// JSON format
     #[default]
     JsonObject,
+
+    /// JSON following a schema
+    JsonSchema,
+}
+
+/// The schema of the `json_schema` response format.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct JsonSchemaFormat {
+    /// The name of the format. Must be a-z, A-Z, 0-9, or contain underscores and dashes, with a maximum length of 64.
+    pub name: String,
+
+    /// A description of what the format is for, used by the model to determine how to respond in the format.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    pub description: Option<String>,
+
+    /// The schema of the format, described as a JSON Schema object.
+    pub schema: serde_json::Value,
+
+    /// Whether the model must follow the schema exactly. Requires a schema meeting the constraints
+    /// of the strict mode, see [`strict_schema`](crate::strict_schema).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    pub strict: Option<bool>,
 }
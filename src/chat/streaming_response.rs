@@ -1,6 +1,8 @@
 use serde::{Deserialize, Serialize};
 
-use super::ChatResponseChunk;
+use crate::common::{MessageRole, TokenUsage};
+
+use super::{ChoiceLogprobs, ToolType};
 
 /// Represents a chat completion streaming response returned by model, based on the provided input
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -25,6 +27,11 @@ pub struct ChatCompletionStreamResponse {
 
     /// The object type, which is always `chat.completion.chunk `
     pub object: String,
+
+    /// Usage statistics for the completion request. Only sent in the last chunk, which has no choices,
+    /// when `stream_options.include_usage` is set on the request.
+    #[serde(default)]
+    pub usage: Option<TokenUsage>,
 }
 
 impl ChatCompletionStreamResponse {
@@ -54,10 +61,11 @@ pub struct ChatCompletionStreamChoice {
     pub index: i32,
 
     /// A chat completion delta generated by streamed model responses.
-    pub delta: ChatResponseChunk,
+    pub delta: ChatCompletionDelta,
 
-    /// Log probability information for the choice.
-    pub logprobs: Option<f32>,
+    /// Log probability information for the tokens of this chunk, if `logprobs` was set on the request.
+    #[serde(default)]
+    pub logprobs: Option<ChoiceLogprobs>,
 
     /// The reason the model stopped generating tokens. This will be `stop` if the model hit a natural stop point or a provided stop sequence,
     /// `length` if the maximum number of tokens specified in the request was reached,
@@ -68,10 +76,60 @@ pub struct ChatCompletionStreamChoice {
     pub finish_reason: Option<String>,
 }
 
+/// A part of the message generated by the model, sent in a chunk of a streamed response.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ChatCompletionDelta {
+    /// The role of the author of the message, only sent in the first chunk
+    #[serde(default)]
+    pub role: Option<MessageRole>,
+
+    /// The next part of the contents of the message
+    #[serde(default)]
+    pub content: Option<String>,
+
+    /// Parts of the tool calls of the message
+    #[serde(default)]
+    pub tool_calls: Vec<ToolCallDelta>,
+
+    /// The next part of the refusal message
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    pub refusal: Option<String>,
+}
+
+/// A part of a tool call generated by the model, sent in a chunk of a streamed response.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ToolCallDelta {
+    /// The index of the tool call in the message, identifying the tool call the part belongs to
+    pub index: usize,
+
+    /// The ID of the tool call, only sent in the first part
+    #[serde(default)]
+    pub id: Option<String>,
+
+    /// The type of the tool, only sent in the first part
+    #[serde(default, rename = "type")]
+    pub _type: Option<ToolType>,
+
+    /// A part of the function call
+    #[serde(default)]
+    pub function: Option<FunctionCallDelta>,
+}
+
+/// A part of a function call generated by the model, sent in a chunk of a streamed response.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FunctionCallDelta {
+    /// The name of the function, only sent in the first part
+    #[serde(default)]
+    pub name: Option<String>,
+
+    /// The next fragment of the arguments, which are only valid JSON once all fragments are joined
+    #[serde(default)]
+    pub arguments: Option<String>,
+}
+
 #[cfg(test)]
 mod tests {
-    use crate::{FunctionCall, MessageRole, ToolCall, ToolType};
-
     use super::*;
     use serde_json::json;
 
@@ -91,11 +149,12 @@ mod tests {
                 "content": "\n\nHello there, how may I assist you today?",
                 "tool_calls": [
                   {
-                    "name": "tool",
+                    "index": 0,
+                    "id": "call_abc123",
                     "type": "function",
                     "function": {
                       "name": "set_response",
-                      "arguments": "Hello there, how may I assist you today?"
+                      "arguments": "{\"message\":"
                     }
                   }
                 ],
@@ -114,22 +173,25 @@ mod tests {
             choices: vec![ChatCompletionStreamChoice {
                 index: 0,
                 logprobs: None,
-                delta: ChatResponseChunk {
+                delta: ChatCompletionDelta {
                     role: Some(MessageRole::Assistant),
-                    tool_calls: vec![ToolCall {
-                        name: "tool".to_string(),
-                        _type: ToolType::Function,
-                        function: FunctionCall {
-                            name: "set_response".to_string(),
-                            arguments: "Hello there, how may I assist you today?".to_string(),
-                        },
+                    tool_calls: vec![ToolCallDelta {
+                        index: 0,
+                        id: Some("call_abc123".to_string()),
+                        _type: Some(ToolType::Function),
+                        function: Some(FunctionCallDelta {
+                            name: Some("set_response".to_string()),
+                            arguments: Some("{\"message\":".to_string()),
+                        }),
                     }],
                     content: Some("\n\nHello there, how may I assist you today?".to_string()),
+                    refusal: None,
                 },
                 finish_reason: Some("stop".to_string()),
             }],
             system_fingerprint: None,
             object: "chat.completion.chunk".to_string(),
+            usage: None,
         };
 
         assert_eq!(response, expectation);
@@ -1,9 +1,11 @@
 //! A modules for chat completion tooling
 
+mod registry;
 mod request_tools;
 mod response_tools;
 mod tool_type;
 
+pub use self::registry::*;
 pub use self::request_tools::*;
 pub use self::response_tools::*;
 pub use self::tool_type::*;
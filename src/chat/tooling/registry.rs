@@ -0,0 +1,191 @@
+use std::{collections::BTreeMap, future::Future, sync::Arc, time::Duration};
+
+use futures_util::future::BoxFuture;
+use serde::{de::DeserializeOwned, Serialize};
+use serde_json::Value;
+
+use crate::{
+    chat::{ChatCompletionResponse, ChatRequestMessage},
+    common::OpenAIError,
+};
+
+use super::{Function, Tool, ToolCall, ToolType};
+
+type ToolHandler =
+    Arc<dyn Fn(String) -> BoxFuture<'static, Result<String, OpenAIError>> + Send + Sync>;
+
+/// A set of tools the model may call, each with the async Rust function that executes it.
+///
+/// Handlers receive the arguments of the call deserialized into their argument type,
+/// and their result is serialized into the output sent back to the model.
+#[derive(Clone, Default)]
+pub struct ToolRegistry {
+    tools: BTreeMap<String, (Tool, ToolHandler)>,
+}
+
+impl std::fmt::Debug for ToolRegistry {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ToolRegistry")
+            .field("tools", &self.tools.keys().collect::<Vec<_>>())
+            .finish()
+    }
+}
+
+impl ToolRegistry {
+    /// Creates an empty registry.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers a function and the handler executing it. A function with the same name is replaced.
+    pub fn register<A, R, F, Fut>(mut self, function: Function, handler: F) -> Self
+    where
+        A: DeserializeOwned + Send + 'static,
+        R: Serialize,
+        F: Fn(A) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<R, OpenAIError>> + Send + 'static,
+    {
+        let handler = Arc::new(handler);
+        let tool_handler: ToolHandler = Arc::new(move |arguments: String| {
+            let handler = handler.clone();
+
+            Box::pin(async move {
+                let arguments = serde_json::from_str::<A>(&arguments)?;
+                let output = serde_json::to_value(handler(arguments).await?)?;
+
+                // Strings are sent as they are, everything else as JSON
+                Ok(match output {
+                    Value::String(output) => output,
+                    output => output.to_string(),
+                })
+            })
+        });
+
+        let tool = Tool {
+            _type: ToolType::Function,
+            function,
+        };
+
+        let _ = self
+            .tools
+            .insert(tool.function.name.clone(), (tool, tool_handler));
+
+        self
+    }
+
+    /// Returns the registered tools, to be sent with a request.
+    pub fn tools(&self) -> Vec<Tool> {
+        self.tools.values().map(|(tool, _)| tool.clone()).collect()
+    }
+
+    /// Returns whether a function with the name is registered.
+    pub fn contains(&self, name: &str) -> bool {
+        self.tools.contains_key(name)
+    }
+
+    /// Executes a tool call, returning the output for the model.
+    pub async fn call(&self, tool_call: &ToolCall) -> Result<String, OpenAIError> {
+        let (_, handler) = self.tools.get(&tool_call.function.name).ok_or_else(|| {
+            OpenAIError::Exception(format!("Unknown tool: {}", tool_call.function.name))
+        })?;
+
+        handler(tool_call.function.arguments.clone()).await
+    }
+
+    /// Executes a tool call, turning failures into an error message for the model,
+    /// so it can correct the call.
+    pub(crate) async fn call_for_model(&self, tool_call: &ToolCall) -> String {
+        match self.call(tool_call).await {
+            Ok(output) => output,
+            Err(error) => {
+                warn!(tool = %tool_call.function.name, error = %error, "Tool call failed");
+                serde_json::json!({ "error": error.to_string() }).to_string()
+            }
+        }
+    }
+}
+
+/// Limits and behavior of [`ChatHandler::run_with_tools`](crate::chat::ChatHandler::run_with_tools).
+#[derive(Debug, Clone)]
+pub struct ToolRunOptions {
+    /// The maximum number of requests sent to the model
+    pub max_iterations: usize,
+
+    /// The maximum time the whole run may take
+    pub timeout: Option<Duration>,
+
+    /// Whether the tool calls of a response are executed concurrently
+    pub parallel: bool,
+}
+
+impl Default for ToolRunOptions {
+    fn default() -> Self {
+        Self {
+            max_iterations: 10,
+            timeout: None,
+            parallel: true,
+        }
+    }
+}
+
+impl ToolRunOptions {
+    /// Sets the maximum number of requests sent to the model.
+    pub fn max_iterations(mut self, max_iterations: usize) -> Self {
+        self.max_iterations = max_iterations;
+        self
+    }
+
+    /// Sets the maximum time the whole run may take.
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Sets whether the tool calls of a response are executed concurrently.
+    pub fn parallel(mut self, parallel: bool) -> Self {
+        self.parallel = parallel;
+        self
+    }
+}
+
+/// Why [`ChatHandler::run_with_tools`](crate::chat::ChatHandler::run_with_tools) stopped.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ToolRunStop {
+    /// The model produced a message without tool calls
+    Completed,
+
+    /// The maximum number of iterations was reached while the model still called tools
+    MaxIterations,
+
+    /// The run took longer than its timeout
+    Timeout,
+}
+
+/// The result of [`ChatHandler::run_with_tools`](crate::chat::ChatHandler::run_with_tools).
+#[derive(Debug, Clone)]
+pub struct ToolRun {
+    /// The full transcript: the messages of the request, followed by every message of the model
+    /// and the outputs of the tools it called
+    pub messages: Vec<ChatRequestMessage>,
+
+    /// Every response of the model, in order
+    pub responses: Vec<ChatCompletionResponse>,
+
+    /// Why the run stopped
+    pub stop: ToolRunStop,
+}
+
+impl ToolRun {
+    /// Returns the final response of the model.
+    pub fn last_response(&self) -> Option<&ChatCompletionResponse> {
+        self.responses.last()
+    }
+
+    /// Returns the content of the final message of the model, if the run completed.
+    pub fn final_message(&self) -> Option<&String> {
+        match self.stop {
+            ToolRunStop::Completed => self.last_response()?.first_message(),
+            _ => None,
+        }
+    }
+}
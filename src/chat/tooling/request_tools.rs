@@ -1,5 +1,7 @@
 use serde::{Deserialize, Serialize};
 
+use crate::ToolSchema;
+
 use super::ToolType;
 
 /// A tool which OpenAI models can call
@@ -28,6 +30,12 @@ pub struct Function {
     /// See the [guide](https://platform.openai.com/docs/guides/text-generation/function-calling) for examples,
     /// and the [JSON Schema](https://json-schema.org/understanding-json-schema/) reference for documentation about the format.
     pub parameters: serde_json::Value,
+
+    /// Whether the model must follow the schema of the parameters exactly. Requires a schema
+    /// meeting the constraints of the strict mode, see [`strict_schema`](crate::strict_schema).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    pub strict: Option<bool>,
 }
 
 impl Default for Function {
@@ -39,6 +47,23 @@ impl Default for Function {
                 "type": "object",
                 "properties": {}
             }),
+            strict: None,
+        }
+    }
+}
+
+impl Function {
+    /// Creates a strict function whose parameters are described by the JSON Schema of `T`.
+    pub fn from_schema<T, S>(name: S, description: Option<String>) -> Self
+    where
+        T: ToolSchema,
+        S: Into<String>,
+    {
+        Self {
+            name: name.into(),
+            description,
+            parameters: T::strict_json_schema(),
+            strict: Some(true),
         }
     }
 }
@@ -70,7 +95,7 @@ impl Serialize for ToolChoice {
     }
 }
 
-///
+/// Specifies a tool the model should use.
 #[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
 pub struct ToolChoiceObject {
     /// The type of the tool. Currently, only `function` is supported.
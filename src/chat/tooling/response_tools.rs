@@ -5,8 +5,8 @@ use super::ToolType;
 /// The tool call generated by the model, such as function call.
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct ToolCall {
-    ///The ID of the tool call.
-    pub name: String,
+    /// The ID of the tool call, which the message with the output of the call refers to.
+    pub id: String,
 
     /// The type of the tool. Currently, only `function` is supported.    
     #[serde(rename = "type")]
@@ -1,17 +1,22 @@
+use std::future::Future;
+
 use crate::{
     audio::AudioHandler,
     base_client::BaseClient,
+    batches::BatchHandler,
     beta::{
         assistants::AssistantsHandler, messages::MessagesHandler, runs::RunsHandler,
         threads::ThreadsHandler,
     },
     chat::ChatHandler,
+    completions::CompletionsHandler,
     embeddings::EmbeddingsHandler,
     files::FileHandler,
     fine_tunning::FineTuningHandler,
     images::ImagesHandler,
     models::ModelsHandler,
     moderations::ModerationsHandler,
+    with_response_meta, ModelRegistry, RateLimitInfo, ResponseMeta, UsageTracker,
 };
 
 /// OpenAI client
@@ -26,75 +31,127 @@ impl OpenAIClient {
         Self { client }
     }
 
+    /// Returns the rate limit information OpenAI reported with the latest response for the model.
+    ///
+    /// This is a convenience snapshot, only kept for requests that name a model.
+    /// Use [`with_response_meta`](Self::with_response_meta) to get the rate limits of a specific response.
+    pub fn rate_limit_info<S: AsRef<str>>(&self, model: S) -> Option<RateLimitInfo> {
+        self.client.rate_limit_info(model.as_ref())
+    }
+
+    /// Runs a call of this client and returns its output together with the metadata of the response,
+    /// such as its request ID and rate limits.
+    ///
+    /// ```no_run
+    /// # async fn example(client: open_ai_client::OpenAIClient) {
+    /// let (files, meta) = client
+    ///     .with_response_meta(client.files().list_files(Default::default()))
+    ///     .await;
+    ///
+    /// let rate_limits = meta.and_then(|meta| meta.rate_limit_info());
+    /// # }
+    /// ```
+    ///
+    /// Calls sending several requests, such as [`ChatHandler::run_with_tools`](crate::chat::ChatHandler::run_with_tools),
+    /// return the metadata of the last response. It's `None` if no response was received.
+    pub async fn with_response_meta<F>(&self, call: F) -> (F::Output, Option<ResponseMeta>)
+    where
+        F: Future,
+    {
+        with_response_meta(call).await
+    }
+
+    /// Returns the capabilities and prices of the models, see [`ClientBuilder::model_registry`](crate::ClientBuilder::model_registry).
+    pub fn model_registry(&self) -> &ModelRegistry {
+        self.client.model_registry()
+    }
+
+    /// Returns the usage tracker, if one was installed with [`ClientBuilder::usage_tracker`](crate::ClientBuilder::usage_tracker).
+    pub fn usage_tracker(&self) -> Option<&UsageTracker> {
+        self.client.usage_tracker()
+    }
+
     /// Handles audio related operations
     #[cfg(feature = "audio")]
-    pub fn audio(&self) -> AudioHandler {
+    pub fn audio(&self) -> AudioHandler<'_> {
         AudioHandler::new(&self.client)
     }
 
+    /// Handles batch related operations
+    #[cfg(feature = "batches")]
+    pub fn batches(&self) -> BatchHandler<'_> {
+        BatchHandler::new(&self.client)
+    }
+
     /// Handles chat related operations
     #[cfg(feature = "chat")]
-    pub fn chat(&self) -> ChatHandler {
+    pub fn chat(&self) -> ChatHandler<'_> {
         ChatHandler::new(&self.client)
     }
 
+    /// Handles legacy completions related operations
+    #[cfg(feature = "completions")]
+    pub fn completions(&self) -> CompletionsHandler<'_> {
+        CompletionsHandler::new(&self.client)
+    }
+
     /// Handles moderation related operations
     #[cfg(feature = "embeddings")]
-    pub fn embeddings(&self) -> EmbeddingsHandler {
+    pub fn embeddings(&self) -> EmbeddingsHandler<'_> {
         EmbeddingsHandler::new(&self.client)
     }
 
     /// Handles file related operations
     #[cfg(feature = "files")]
-    pub fn files(&self) -> FileHandler {
+    pub fn files(&self) -> FileHandler<'_> {
         FileHandler::new(&self.client)
     }
 
     /// Handles fine-tuning related operations
     #[cfg(feature = "fine_tunning")]
-    pub fn fine_tunning(&self) -> FineTuningHandler {
+    pub fn fine_tunning(&self) -> FineTuningHandler<'_> {
         FineTuningHandler::new(&self.client)
     }
 
     /// Handles images related operations
     #[cfg(feature = "images")]
-    pub fn images(&self) -> ImagesHandler {
+    pub fn images(&self) -> ImagesHandler<'_> {
         ImagesHandler::new(&self.client)
     }
 
     /// Handles models related operations
     #[cfg(feature = "models")]
-    pub fn models(&self) -> ModelsHandler {
+    pub fn models(&self) -> ModelsHandler<'_> {
         ModelsHandler::new(&self.client)
     }
 
     /// Handles moderation related operations
     #[cfg(feature = "moderations")]
-    pub fn moderation(&self) -> ModerationsHandler {
+    pub fn moderation(&self) -> ModerationsHandler<'_> {
         ModerationsHandler::new(&self.client)
     }
 
     /// Handles assistants related operations
     #[cfg(feature = "assistants")]
-    pub fn assistants(&self) -> AssistantsHandler {
+    pub fn assistants(&self) -> AssistantsHandler<'_> {
         AssistantsHandler::new(&self.client)
     }
 
     /// Handles threads related operations
     #[cfg(feature = "messages")]
-    pub fn threads(&self) -> ThreadsHandler {
+    pub fn threads(&self) -> ThreadsHandler<'_> {
         ThreadsHandler::new(&self.client)
     }
 
     /// Handles messages related operations
     #[cfg(feature = "runs")]
-    pub fn messages(&self) -> MessagesHandler {
+    pub fn messages(&self) -> MessagesHandler<'_> {
         MessagesHandler::new(&self.client)
     }
 
     /// Handles runs related operations
     #[cfg(feature = "threads")]
-    pub fn runs(&self) -> RunsHandler {
+    pub fn runs(&self) -> RunsHandler<'_> {
         RunsHandler::new(&self.client)
     }
 }
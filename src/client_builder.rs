@@ -1,8 +1,14 @@
-use std::{collections::BTreeMap, time::Duration};
+use std::{collections::BTreeMap, sync::Arc, time::Duration};
 
 use reqwest::header::HeaderName;
 
-use crate::{base_client::BaseClient, OpenAIClient};
+use crate::{
+    base_client::BaseClient, Middleware, ModelRegistry, OpenAIClient, RateLimiter,
+    ReqwestTransport, RetryPolicy, Transport, UsageTracker,
+};
+
+#[cfg(feature = "cassette")]
+use crate::{CassetteMatcher, CassetteMode, CassetteTransport};
 
 /// A builder for [`Client`].
 #[derive(Debug)]
@@ -12,6 +18,23 @@ pub struct ClientBuilder {
     timeout: Duration,
 
     headers: BTreeMap<String, String>,
+
+    retry_policy: RetryPolicy,
+
+    rate_limiter: Option<RateLimiter>,
+
+    middlewares: Vec<Arc<dyn Middleware>>,
+
+    stream_idle_timeout: Option<Duration>,
+
+    transport: Option<Arc<dyn Transport>>,
+
+    model_registry: ModelRegistry,
+
+    usage_tracker: Option<UsageTracker>,
+
+    #[cfg(feature = "cassette")]
+    cassette: Option<(CassetteMode, std::path::PathBuf, CassetteMatcher)>,
 }
 
 impl Default for ClientBuilder {
@@ -20,6 +43,15 @@ impl Default for ClientBuilder {
             headers: BTreeMap::new(),
             timeout: Duration::from_secs(10),
             host: "https://api.openai.com".into(),
+            retry_policy: RetryPolicy::never(),
+            rate_limiter: None,
+            middlewares: vec![],
+            stream_idle_timeout: Some(Duration::from_secs(60)),
+            transport: None,
+            model_registry: ModelRegistry::default(),
+            usage_tracker: None,
+            #[cfg(feature = "cassette")]
+            cassette: None,
         }
     }
 }
@@ -80,6 +112,80 @@ impl ClientBuilder {
         self
     }
 
+    /// Sets the policy for retrying failed requests. By default requests are not retried.
+    pub fn retry_policy(mut self, retry_policy: RetryPolicy) -> Self {
+        self.retry_policy = retry_policy;
+
+        self
+    }
+
+    /// Installs a client-side rate limiter that delays requests to stay within the given budgets.
+    pub fn rate_limiter(mut self, rate_limiter: RateLimiter) -> Self {
+        self.rate_limiter = Some(rate_limiter);
+
+        self
+    }
+
+    /// Sets how long a stream may go without receiving data before it fails. Defaults to 60 seconds,
+    /// `None` disables the timeout.
+    pub fn stream_idle_timeout(mut self, timeout: Option<Duration>) -> Self {
+        self.stream_idle_timeout = timeout;
+
+        self
+    }
+
+    /// Adds a middleware that every request passes through. Middlewares run in the order they are added.
+    pub fn with_middleware<M>(mut self, middleware: M) -> Self
+    where
+        M: Middleware + 'static,
+    {
+        self.middlewares.push(Arc::new(middleware));
+
+        self
+    }
+
+    /// Replaces the transport sending the requests, e.g. with a [`MockTransport`](crate::MockTransport) in tests.
+    /// The host, timeout and headers configured on the builder only apply to the default transport.
+    pub fn transport<T>(mut self, transport: T) -> Self
+    where
+        T: Transport + 'static,
+    {
+        self.transport = Some(Arc::new(transport));
+
+        self
+    }
+
+    /// Replaces the registry of model capabilities and prices, e.g. with one extended from a JSON file.
+    /// Defaults to the models known to this crate.
+    ///
+    /// The registry prices the usage recorded by the [usage tracker](ClientBuilder::usage_tracker), unless it
+    /// has prices of its own, and provides the context window of conversations completed by the client.
+    pub fn model_registry(mut self, model_registry: ModelRegistry) -> Self {
+        self.model_registry = model_registry;
+
+        self
+    }
+
+    /// Records the usage and costs of the client with the tracker. Keep a clone of the tracker
+    /// to read the usage, or use [`OpenAIClient::usage_tracker`].
+    pub fn usage_tracker(mut self, usage_tracker: UsageTracker) -> Self {
+        self.usage_tracker = Some(usage_tracker);
+
+        self
+    }
+
+    /// Records the requests of the client to a cassette file, or replays them from it, depending on the mode.
+    /// See [`CassetteTransport`](crate::CassetteTransport).
+    #[cfg(feature = "cassette")]
+    pub fn cassette<P>(mut self, mode: CassetteMode, path: P, matcher: CassetteMatcher) -> Self
+    where
+        P: Into<std::path::PathBuf>,
+    {
+        self.cassette = Some((mode, path.into(), matcher));
+
+        self
+    }
+
     /// Builds OpenAI [`Client`].
     pub fn build(self) -> Result<OpenAIClient, anyhow::Error> {
         let mut headers = reqwest::header::HeaderMap::new();
@@ -95,12 +201,33 @@ impl ClientBuilder {
             .default_headers(headers)
             .timeout(self.timeout)
             .tcp_nodelay(true)
-            .trust_dns(true)
+            .hickory_dns(true)
             .no_proxy()
             .build()?;
 
         let host = reqwest::Url::parse(&self.host)?;
-        let base_client = BaseClient::new(reqwest_client, host);
+        let transport = match self.transport {
+            Some(transport) => transport,
+            None => Arc::new(ReqwestTransport::new(reqwest_client, host)),
+        };
+
+        #[cfg(feature = "cassette")]
+        let transport: Arc<dyn Transport> = match self.cassette {
+            Some((mode, path, matcher)) => {
+                Arc::new(CassetteTransport::new(mode, path, transport)?.with_matcher(matcher))
+            }
+            None => transport,
+        };
+
+        let base_client = BaseClient::new(
+            transport,
+            self.retry_policy,
+            self.rate_limiter,
+            self.middlewares,
+            self.stream_idle_timeout,
+            self.model_registry,
+            self.usage_tracker,
+        );
 
         Ok(OpenAIClient::new(base_client))
     }
@@ -0,0 +1,254 @@
+use reqwest::{header::HeaderMap, Response, StatusCode};
+use serde::{Deserialize, Deserializer, Serialize};
+
+use super::RateLimitInfo;
+
+pub(crate) const REQUEST_ID_HEADER: &str = "x-request-id";
+
+/// An error returned by the OpenAI API, together with the context of the response it came from.
+#[derive(Debug, Clone)]
+pub struct ApiError {
+    /// HTTP status code of the response.
+    pub status: StatusCode,
+
+    /// The error object returned by OpenAI.
+    pub error: ApiErrorObject,
+
+    /// The value of the `x-request-id` header, useful when contacting OpenAI support.
+    pub request_id: Option<String>,
+
+    /// The headers of the response.
+    pub headers: HeaderMap,
+}
+
+impl ApiError {
+    /// Creates an API error from a status, headers and the raw response body.
+    ///
+    /// If the body is not a valid OpenAI error object, the whole body is used as the error message.
+    pub fn from_parts(status: StatusCode, headers: HeaderMap, body: &str) -> Self {
+        let error = serde_json::from_str::<ApiErrorBody>(body)
+            .map(|body| body.error)
+            .unwrap_or_else(|_| ApiErrorObject {
+                message: body.to_string(),
+                ..Default::default()
+            });
+
+        let request_id = headers
+            .get(REQUEST_ID_HEADER)
+            .and_then(|value| value.to_str().ok())
+            .map(ToString::to_string);
+
+        Self {
+            status,
+            error,
+            request_id,
+            headers,
+        }
+    }
+
+    /// Reads an unsuccessful response into an API error.
+    pub(crate) async fn from_response(response: Response) -> Self {
+        let status = response.status();
+        let headers = response.headers().clone();
+        let body = response.text().await.unwrap_or_default();
+
+        Self::from_parts(status, headers, &body)
+    }
+
+    /// The human readable error message.
+    pub fn message(&self) -> &str {
+        &self.error.message
+    }
+
+    /// The error code, such as `context_length_exceeded` or `rate_limit_exceeded`.
+    pub fn code(&self) -> Option<&str> {
+        self.error.code.as_deref()
+    }
+
+    /// The error type, such as `invalid_request_error` or `server_error`.
+    pub fn error_type(&self) -> Option<&str> {
+        self.error._type.as_deref()
+    }
+
+    /// The rate limit information sent along with the error, if any.
+    pub fn rate_limit_info(&self) -> Option<RateLimitInfo> {
+        RateLimitInfo::from_headers(&self.headers)
+    }
+
+    /// Whether the request was rejected because of rate limiting.
+    pub fn is_rate_limited(&self) -> bool {
+        self.status == StatusCode::TOO_MANY_REQUESTS && !self.is_insufficient_quota()
+    }
+
+    /// Whether the request was rejected because the account ran out of quota.
+    /// Retrying such requests will not help.
+    pub fn is_insufficient_quota(&self) -> bool {
+        self.code() == Some("insufficient_quota") || self.error_type() == Some("insufficient_quota")
+    }
+
+    /// Whether the request exceeded the context window of the model.
+    pub fn is_context_length_exceeded(&self) -> bool {
+        self.code() == Some("context_length_exceeded")
+    }
+
+    /// Whether the request failed with a server side error.
+    pub fn is_server_error(&self) -> bool {
+        self.status.is_server_error()
+    }
+
+    /// Whether retrying the same request may succeed.
+    ///
+    /// Timeouts, conflicts, rate limits and server errors are considered retryable.
+    pub fn is_retryable(&self) -> bool {
+        matches!(
+            self.status,
+            StatusCode::REQUEST_TIMEOUT | StatusCode::CONFLICT
+        ) || self.is_rate_limited()
+            || self.is_server_error()
+    }
+}
+
+impl std::fmt::Display for ApiError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "OpenAI API error ({}): {}",
+            self.status, self.error.message
+        )?;
+
+        if let Some(request_id) = &self.request_id {
+            write!(f, " [request id: {}]", request_id)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// The error object OpenAI returns in the body of unsuccessful responses.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ApiErrorObject {
+    /// A human readable error message.
+    #[serde(default)]
+    pub message: String,
+
+    /// The type of the error, such as `invalid_request_error`.
+    #[serde(rename = "type")]
+    #[serde(default)]
+    pub _type: Option<String>,
+
+    /// The request parameter that caused the error, if any.
+    #[serde(default)]
+    pub param: Option<String>,
+
+    /// A machine readable error code, such as `context_length_exceeded`.
+    #[serde(default, deserialize_with = "deserialize_code")]
+    pub code: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ApiErrorBody {
+    error: ApiErrorObject,
+}
+
+/// Error codes are usually strings, but some endpoints return them as numbers.
+fn deserialize_code<'de, D>(deserializer: D) -> Result<Option<String>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let code = Option::<serde_json::Value>::deserialize(deserializer)?;
+
+    Ok(code.and_then(|code| match code {
+        serde_json::Value::String(code) => Some(code),
+        serde_json::Value::Null => None,
+        code => Some(code.to_string()),
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use reqwest::header::HeaderValue;
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn parses_error_body_correctly() {
+        let body = json!({
+          "error": {
+            "message": "This model's maximum context length is 4097 tokens.",
+            "type": "invalid_request_error",
+            "param": "messages",
+            "code": "context_length_exceeded"
+          }
+        });
+
+        let mut headers = HeaderMap::new();
+        let _ = headers.insert(REQUEST_ID_HEADER, HeaderValue::from_static("req_123"));
+
+        let error = ApiError::from_parts(StatusCode::BAD_REQUEST, headers, &body.to_string());
+
+        let expectation = ApiErrorObject {
+            message: "This model's maximum context length is 4097 tokens.".to_string(),
+            _type: Some("invalid_request_error".to_string()),
+            param: Some("messages".to_string()),
+            code: Some("context_length_exceeded".to_string()),
+        };
+
+        assert_eq!(error.error, expectation);
+        assert_eq!(error.request_id.as_deref(), Some("req_123"));
+        assert!(error.is_context_length_exceeded());
+        assert!(!error.is_retryable());
+    }
+
+    #[test]
+    fn classifies_rate_limits_correctly() {
+        let rate_limited = json!({
+          "error": {
+            "message": "Rate limit reached for requests",
+            "type": "requests",
+            "param": null,
+            "code": "rate_limit_exceeded"
+          }
+        });
+
+        let error = ApiError::from_parts(
+            StatusCode::TOO_MANY_REQUESTS,
+            HeaderMap::new(),
+            &rate_limited.to_string(),
+        );
+
+        assert!(error.is_rate_limited());
+        assert!(error.is_retryable());
+
+        let out_of_quota = json!({
+          "error": {
+            "message": "You exceeded your current quota",
+            "type": "insufficient_quota",
+            "param": null,
+            "code": "insufficient_quota"
+          }
+        });
+
+        let error = ApiError::from_parts(
+            StatusCode::TOO_MANY_REQUESTS,
+            HeaderMap::new(),
+            &out_of_quota.to_string(),
+        );
+
+        assert!(!error.is_rate_limited());
+        assert!(!error.is_retryable());
+    }
+
+    #[test]
+    fn falls_back_to_raw_body() {
+        let error = ApiError::from_parts(
+            StatusCode::BAD_GATEWAY,
+            HeaderMap::new(),
+            "<html>Bad gateway</html>",
+        );
+
+        assert_eq!(error.message(), "<html>Bad gateway</html>");
+        assert!(error.is_server_error());
+        assert!(error.is_retryable());
+    }
+}
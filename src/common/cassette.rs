@@ -0,0 +1,708 @@
+use std::{
+    collections::HashSet,
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex, MutexGuard},
+};
+
+use async_trait::async_trait;
+use base64::{engine::general_purpose, Engine as _};
+use futures_util::StreamExt;
+use reqwest::{header::CONTENT_TYPE, Response, StatusCode};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use tokio_util::codec::BytesCodec;
+
+use super::{MockResponse, MultipartPart, OpenAIError, OpenAIRequest, RequestBody, Transport};
+
+/// Whether a cassette records interactions or replays them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CassetteMode {
+    /// Sends requests with the wrapped transport and writes every interaction to the cassette file
+    Record,
+
+    /// Answers requests from the cassette file, without sending anything
+    Replay,
+
+    /// Replays the cassette file if it exists, records it otherwise
+    Auto,
+}
+
+/// Decides which recorded interaction answers a request during replay.
+///
+/// By default the method, path, query parameters and JSON body have to match,
+/// except for the `user` and `seed` fields of the body. Whether the request opens an event stream
+/// always has to match.
+#[derive(Debug, Clone)]
+pub struct CassetteMatcher {
+    method: bool,
+    path: bool,
+    query: bool,
+    body: bool,
+    ignored_body_fields: HashSet<String>,
+}
+
+impl Default for CassetteMatcher {
+    fn default() -> Self {
+        Self {
+            method: true,
+            path: true,
+            query: true,
+            body: true,
+            ignored_body_fields: ["user", "seed"].into_iter().map(String::from).collect(),
+        }
+    }
+}
+
+impl CassetteMatcher {
+    /// Creates the default matcher.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets whether the HTTP method has to match.
+    pub fn match_method(mut self, enabled: bool) -> Self {
+        self.method = enabled;
+        self
+    }
+
+    /// Sets whether the path has to match.
+    pub fn match_path(mut self, enabled: bool) -> Self {
+        self.path = enabled;
+        self
+    }
+
+    /// Sets whether the query parameters have to match.
+    pub fn match_query(mut self, enabled: bool) -> Self {
+        self.query = enabled;
+        self
+    }
+
+    /// Sets whether the body, JSON or multipart, has to match.
+    pub fn match_body(mut self, enabled: bool) -> Self {
+        self.body = enabled;
+        self
+    }
+
+    /// Ignores a top-level field of JSON bodies when matching.
+    pub fn ignore_body_field<S>(mut self, field: S) -> Self
+    where
+        S: Into<String>,
+    {
+        let _ = self.ignored_body_fields.insert(field.into());
+        self
+    }
+
+    /// Stops ignoring every body field, including the default `user` and `seed`.
+    pub fn clear_ignored_body_fields(mut self) -> Self {
+        self.ignored_body_fields.clear();
+        self
+    }
+
+    fn matches(&self, recorded: &CassetteRequest, request: &CassetteRequest) -> bool {
+        recorded.stream == request.stream
+            && (!self.method || recorded.method == request.method)
+            && (!self.path
+                || recorded.url.trim_start_matches('/') == request.url.trim_start_matches('/'))
+            && (!self.query || recorded.query == request.query)
+            && (!self.body
+                || (self.strip_body(&recorded.body) == self.strip_body(&request.body)
+                    && recorded.form == request.form))
+    }
+
+    fn strip_body(&self, body: &Option<Value>) -> Option<Value> {
+        match body {
+            Some(Value::Object(fields)) => Some(Value::Object(
+                fields
+                    .iter()
+                    .filter(|(key, _)| !self.ignored_body_fields.contains(key.as_str()))
+                    .map(|(key, value)| (key.clone(), value.clone()))
+                    .collect(),
+            )),
+            body => body.clone(),
+        }
+    }
+}
+
+/// The recorded interactions, as stored in a cassette file.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Cassette {
+    /// The interactions, in the order they were recorded
+    pub interactions: Vec<CassetteInteraction>,
+}
+
+/// A request and the response it received.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CassetteInteraction {
+    /// The request
+    pub request: CassetteRequest,
+
+    /// The response
+    pub response: CassetteResponse,
+}
+
+/// A recorded request.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CassetteRequest {
+    /// The HTTP method
+    pub method: String,
+
+    /// The path of the endpoint
+    pub url: String,
+
+    /// Query parameters
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub query: Vec<(String, String)>,
+
+    /// The JSON body
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub body: Option<Value>,
+
+    /// The fields of a multipart body
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub form: Vec<CassetteFormField>,
+
+    /// Whether the response was requested as an event stream
+    #[serde(default)]
+    pub stream: bool,
+}
+
+/// A recorded field of a multipart body.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum CassetteFormField {
+    /// A text field
+    Text {
+        /// The name of the field
+        name: String,
+
+        /// The value of the field
+        value: String,
+    },
+
+    /// A file field. The content is not stored, only its digest.
+    File {
+        /// The name of the field
+        name: String,
+
+        /// The name of the file
+        file_name: String,
+
+        /// The hex encoded SHA-256 digest of the file content
+        sha256: String,
+    },
+}
+
+/// A recorded response.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CassetteResponse {
+    /// The HTTP status code
+    pub status: u16,
+
+    /// Response headers, in the order they were received. Repeated headers have an entry per value.
+    #[serde(default)]
+    pub headers: Vec<(String, String)>,
+
+    /// The body. Event streams are stored whole and replayed one event at a time.
+    pub body: String,
+
+    /// How the body is encoded. Binary bodies, such as speech audio, are stored as base64.
+    #[serde(default, skip_serializing_if = "CassetteBodyEncoding::is_text")]
+    pub encoding: CassetteBodyEncoding,
+}
+
+/// How the body of a [`CassetteResponse`] is stored.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CassetteBodyEncoding {
+    /// The body is stored as is
+    #[default]
+    Text,
+
+    /// The body is binary and stored base64 encoded
+    Base64,
+}
+
+impl CassetteBodyEncoding {
+    fn is_text(&self) -> bool {
+        *self == Self::Text
+    }
+}
+
+impl Cassette {
+    /// Loads a cassette from a JSON file, or a YAML file if the extension is `yaml` or `yml`.
+    pub fn load<P>(path: P) -> Result<Self, OpenAIError>
+    where
+        P: AsRef<Path>,
+    {
+        let path = path.as_ref();
+        let content = std::fs::read_to_string(path)?;
+
+        if is_yaml(path) {
+            serde_yaml::from_str(&content).map_err(|e| OpenAIError::Exception(e.to_string()))
+        } else {
+            Ok(serde_json::from_str(&content)?)
+        }
+    }
+
+    /// Saves the cassette to a JSON file, or a YAML file if the extension is `yaml` or `yml`.
+    pub async fn save<P>(&self, path: P) -> Result<(), OpenAIError>
+    where
+        P: AsRef<Path>,
+    {
+        let path = path.as_ref();
+        let content = if is_yaml(path) {
+            serde_yaml::to_string(self).map_err(|e| OpenAIError::Exception(e.to_string()))?
+        } else {
+            serde_json::to_string_pretty(self)?
+        };
+
+        if let Some(parent) = path
+            .parent()
+            .filter(|parent| !parent.as_os_str().is_empty())
+        {
+            tokio::fs::create_dir_all(parent).await?;
+        }
+
+        Ok(tokio::fs::write(path, content).await?)
+    }
+}
+
+/// A [`Transport`] that records interactions to a cassette file, or replays them from it.
+///
+/// Recording wraps another transport, usually the default one. Event streams are read to the end
+/// before they're handed back, so they arrive all at once while recording. Replay fails with
+/// [`OpenAIError::Exception`] when no unused recorded interaction matches the request.
+#[derive(Debug, Clone)]
+pub struct CassetteTransport {
+    path: PathBuf,
+    inner: Option<Arc<dyn Transport>>,
+    matcher: CassetteMatcher,
+    state: Arc<Mutex<CassetteState>>,
+}
+
+#[derive(Debug, Default)]
+struct CassetteState {
+    cassette: Cassette,
+    used: Vec<bool>,
+}
+
+impl CassetteTransport {
+    /// Creates a transport recording the interactions of `inner` to the file.
+    pub fn record<P>(path: P, inner: Arc<dyn Transport>) -> Self
+    where
+        P: Into<PathBuf>,
+    {
+        Self {
+            path: path.into(),
+            inner: Some(inner),
+            matcher: CassetteMatcher::default(),
+            state: Default::default(),
+        }
+    }
+
+    /// Creates a transport replaying the interactions of the file.
+    pub fn replay<P>(path: P) -> Result<Self, OpenAIError>
+    where
+        P: Into<PathBuf>,
+    {
+        let path = path.into();
+        let cassette = Cassette::load(&path)?;
+        let used = vec![false; cassette.interactions.len()];
+
+        Ok(Self {
+            path,
+            inner: None,
+            matcher: CassetteMatcher::default(),
+            state: Arc::new(Mutex::new(CassetteState { cassette, used })),
+        })
+    }
+
+    /// Creates a transport in the given mode. `inner` is only used when recording.
+    pub fn new<P>(
+        mode: CassetteMode,
+        path: P,
+        inner: Arc<dyn Transport>,
+    ) -> Result<Self, OpenAIError>
+    where
+        P: Into<PathBuf>,
+    {
+        let path = path.into();
+
+        match mode {
+            CassetteMode::Record => Ok(Self::record(path, inner)),
+            CassetteMode::Replay => Self::replay(path),
+            CassetteMode::Auto if path.exists() => Self::replay(path),
+            CassetteMode::Auto => Ok(Self::record(path, inner)),
+        }
+    }
+
+    /// Sets how requests are matched to recorded interactions.
+    pub fn with_matcher(mut self, matcher: CassetteMatcher) -> Self {
+        self.matcher = matcher;
+        self
+    }
+
+    /// Returns whether the transport is recording.
+    pub fn is_recording(&self) -> bool {
+        self.inner.is_some()
+    }
+
+    /// Returns a copy of the interactions recorded or loaded so far.
+    pub fn cassette(&self) -> Cassette {
+        self.state().cassette.clone()
+    }
+
+    fn state(&self) -> MutexGuard<'_, CassetteState> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    async fn handle(&self, request: &OpenAIRequest, stream: bool) -> Result<Response, OpenAIError> {
+        let cassette_request = CassetteRequest::new(request, stream).await?;
+
+        match &self.inner {
+            Some(inner) => {
+                let response = match (&request.body, stream) {
+                    (RequestBody::Multipart(_), _) => inner.send_multipart(request).await?,
+                    (_, true) => inner.open_stream(request).await?,
+                    (_, false) => inner.send_json(request).await?,
+                };
+
+                self.record_interaction(cassette_request, response).await
+            }
+            None => self.replay_interaction(&cassette_request),
+        }
+    }
+
+    async fn record_interaction(
+        &self,
+        request: CassetteRequest,
+        response: Response,
+    ) -> Result<Response, OpenAIError> {
+        let headers = response
+            .headers()
+            .iter()
+            .filter_map(|(name, value)| Some((name.to_string(), value.to_str().ok()?.to_string())))
+            .collect();
+
+        let status = response.status().as_u16();
+        let is_text = response
+            .headers()
+            .get(CONTENT_TYPE)
+            .is_none_or(|content_type| content_type.to_str().is_ok_and(is_text_content_type));
+
+        let bytes = response.bytes().await?;
+        let (body, encoding) = match std::str::from_utf8(&bytes) {
+            Ok(text) if is_text => (text.to_string(), CassetteBodyEncoding::Text),
+            _ => (
+                general_purpose::STANDARD.encode(&bytes),
+                CassetteBodyEncoding::Base64,
+            ),
+        };
+
+        let cassette_response = CassetteResponse {
+            status,
+            headers,
+            body,
+            encoding,
+        };
+
+        let replayed = cassette_response.to_response(request.stream)?;
+        let cassette = {
+            let mut state = self.state();
+            state.cassette.interactions.push(CassetteInteraction {
+                request,
+                response: cassette_response,
+            });
+            state.used.push(true);
+            state.cassette.clone()
+        };
+
+        cassette.save(&self.path).await?;
+
+        Ok(replayed)
+    }
+
+    fn replay_interaction(&self, request: &CassetteRequest) -> Result<Response, OpenAIError> {
+        let mut state = self.state();
+        let CassetteState { cassette, used } = &mut *state;
+
+        let index = cassette
+            .interactions
+            .iter()
+            .zip(used.iter())
+            .position(|(interaction, used)| {
+                !used && self.matcher.matches(&interaction.request, request)
+            })
+            .ok_or_else(|| {
+                OpenAIError::Exception(format!(
+                    "Cassette {}: no recorded interaction matches {} {}",
+                    self.path.display(),
+                    request.method,
+                    request.url
+                ))
+            })?;
+
+        used[index] = true;
+
+        cassette.interactions[index]
+            .response
+            .to_response(request.stream)
+    }
+}
+
+impl CassetteRequest {
+    async fn new(request: &OpenAIRequest, stream: bool) -> Result<Self, OpenAIError> {
+        let mut body = None;
+        let mut form = vec![];
+
+        match &request.body {
+            RequestBody::Empty => {}
+            RequestBody::Json(json) => body = Some(json.clone()),
+            RequestBody::Multipart(multipart) => {
+                for (name, part) in multipart.parts() {
+                    form.push(match part {
+                        MultipartPart::Text(value) => CassetteFormField::Text {
+                            name: name.clone(),
+                            value: value.clone(),
+                        },
+                        MultipartPart::File(file) => {
+                            let mut hasher = Sha256::new();
+                            let mut content =
+                                file.try_clone().await?.into_stream(BytesCodec::new());
+
+                            while let Some(chunk) = content.next().await {
+                                hasher.update(&chunk?);
+                            }
+
+                            CassetteFormField::File {
+                                name: name.clone(),
+                                file_name: file.name.clone(),
+                                sha256: format!("{:x}", hasher.finalize()),
+                            }
+                        }
+                    });
+                }
+            }
+        }
+
+        Ok(Self {
+            method: request.method.to_string(),
+            url: request.url.clone(),
+            query: request.query.clone(),
+            body,
+            form,
+            stream,
+        })
+    }
+}
+
+impl CassetteResponse {
+    fn to_response(&self, stream: bool) -> Result<Response, OpenAIError> {
+        let status =
+            StatusCode::from_u16(self.status).map_err(|e| OpenAIError::Exception(e.to_string()))?;
+
+        let response = if self.encoding == CassetteBodyEncoding::Base64 {
+            MockResponse::new(status).body(general_purpose::STANDARD.decode(&self.body)?)
+        } else if stream && status.is_success() {
+            // Hand the events out one at a time, like the API does
+            let chunks = self
+                .body
+                .split_inclusive("\n\n")
+                .map(String::from)
+                .collect();
+
+            MockResponse::sse_chunks(chunks)
+        } else {
+            MockResponse::new(status).body(self.body.clone())
+        };
+
+        // Recorded headers replace the defaults of the mock response, repeated ones are added
+        let response = self.headers.iter().enumerate().fold(
+            response.status(status),
+            |response, (index, (name, value))| {
+                let repeated = self.headers[..index]
+                    .iter()
+                    .any(|(previous, _)| previous.eq_ignore_ascii_case(name));
+
+                if repeated {
+                    response.append_header(name, value)
+                } else {
+                    response.header(name, value)
+                }
+            },
+        );
+
+        response.into_response()
+    }
+}
+
+#[async_trait]
+impl Transport for CassetteTransport {
+    async fn send_json(&self, request: &OpenAIRequest) -> Result<Response, OpenAIError> {
+        self.handle(request, false).await
+    }
+
+    async fn send_multipart(&self, request: &OpenAIRequest) -> Result<Response, OpenAIError> {
+        self.handle(request, false).await
+    }
+
+    async fn open_stream(&self, request: &OpenAIRequest) -> Result<Response, OpenAIError> {
+        self.handle(request, true).await
+    }
+}
+
+fn is_text_content_type(content_type: &str) -> bool {
+    let content_type = content_type.to_ascii_lowercase();
+
+    content_type.starts_with("text/")
+        || content_type.contains("json")
+        || content_type.contains("xml")
+}
+
+fn is_yaml(path: &Path) -> bool {
+    matches!(
+        path.extension().and_then(|extension| extension.to_str()),
+        Some("yaml" | "yml")
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use reqwest::Method;
+    use serde_json::json;
+
+    use crate::{MockExpectation, MockTransport};
+
+    use super::*;
+
+    fn chat_request(user: &str) -> OpenAIRequest {
+        OpenAIRequest::with_body(
+            Method::POST,
+            "/v1/chat/completions".to_string(),
+            json!({ "model": "gpt-4", "messages": [], "user": user }),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn cassette_matcher_test() {
+        let recorded = CassetteRequest {
+            method: "POST".to_string(),
+            url: "/v1/chat/completions".to_string(),
+            query: vec![],
+            body: Some(json!({ "model": "gpt-4", "user": "a", "seed": 1 })),
+            form: vec![],
+            stream: false,
+        };
+
+        let request = CassetteRequest {
+            body: Some(json!({ "model": "gpt-4", "user": "b" })),
+            ..recorded.clone()
+        };
+
+        assert!(CassetteMatcher::new().matches(&recorded, &request));
+        assert!(!CassetteMatcher::new()
+            .clear_ignored_body_fields()
+            .matches(&recorded, &request));
+        assert!(CassetteMatcher::new()
+            .clear_ignored_body_fields()
+            .match_body(false)
+            .matches(&recorded, &request));
+
+        let stream_request = CassetteRequest {
+            stream: true,
+            ..recorded.clone()
+        };
+
+        assert!(!CassetteMatcher::new().matches(&recorded, &stream_request));
+    }
+
+    #[tokio::test]
+    async fn cassette_record_replay_test() {
+        let path = std::env::temp_dir().join(format!(
+            "open-ai-client-cassette-{}.yaml",
+            std::process::id()
+        ));
+
+        let mock = MockTransport::new();
+        let _ = mock.expect(
+            MockExpectation::new(Method::POST, "/v1/chat/completions")
+                .respond_with(MockResponse::sse([json!({ "id": 1 }), json!({ "id": 2 })])),
+        );
+
+        let recorder = CassetteTransport::record(&path, Arc::new(mock));
+        let recorded = recorder
+            .open_stream(&chat_request("a"))
+            .await
+            .unwrap()
+            .text()
+            .await
+            .unwrap();
+
+        let player = CassetteTransport::replay(&path).unwrap();
+        assert_eq!(player.cassette(), recorder.cassette());
+
+        let replayed = player
+            .open_stream(&chat_request("b"))
+            .await
+            .unwrap()
+            .text()
+            .await
+            .unwrap();
+
+        assert_eq!(replayed, recorded);
+        assert!(player.open_stream(&chat_request("c")).await.is_err());
+
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[tokio::test]
+    async fn cassette_binary_body_test() {
+        let path = std::env::temp_dir().join(format!(
+            "open-ai-client-cassette-binary-{}.json",
+            std::process::id()
+        ));
+        let audio = vec![0xff, 0xfb, 0x90, 0x00, 0x80, 0x7f, 0xfe];
+
+        let mock = MockTransport::new();
+        let _ = mock.expect(
+            MockExpectation::new(Method::POST, "/v1/audio/speech").respond_with(
+                MockResponse::new(StatusCode::OK)
+                    .header("content-type", "audio/mpeg")
+                    .header("set-cookie", "a=1")
+                    .append_header("set-cookie", "b=2")
+                    .body(audio.clone()),
+            ),
+        );
+
+        let request = OpenAIRequest::with_body(
+            Method::POST,
+            "/v1/audio/speech".to_string(),
+            json!({ "model": "tts-1", "input": "Hello", "voice": "alloy" }),
+        )
+        .unwrap();
+
+        let recorder = CassetteTransport::record(&path, Arc::new(mock));
+        let recorded = recorder.send_json(&request).await.unwrap();
+        assert_eq!(recorded.bytes().await.unwrap(), audio);
+
+        let player = CassetteTransport::replay(&path).unwrap();
+        let interaction = &player.cassette().interactions[0];
+        assert_eq!(interaction.response.encoding, CassetteBodyEncoding::Base64);
+
+        let replayed = player.send_json(&request).await.unwrap();
+        assert_eq!(replayed.headers()["content-type"], "audio/mpeg");
+        assert_eq!(
+            replayed
+                .headers()
+                .get_all("set-cookie")
+                .iter()
+                .collect::<Vec<_>>(),
+            ["a=1", "b=2"]
+        );
+        assert_eq!(replayed.bytes().await.unwrap(), audio);
+
+        std::fs::remove_file(path).unwrap();
+    }
+}
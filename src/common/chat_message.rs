@@ -38,6 +38,9 @@ pub enum MessageRole {
 
     /// Assistant
     Assistant,
+
+    /// Tool, for messages with the output of a tool call
+    Tool,
 }
 
 impl Display for MessageRole {
@@ -1,8 +1,11 @@
-use reqwest_eventsource::CannotCloneRequestError;
+use super::ApiError;
 
 /// OpenAI errors
 #[derive(Debug)]
 pub enum OpenAIError {
+    /// Error response returned by the OpenAI API
+    Api(Box<ApiError>),
+
     /// Client error
     Client(reqwest::Error),
 
@@ -18,31 +21,132 @@ pub enum OpenAIError {
     /// Serde error
     Serde(serde_json::Error),
 
-    /// Stream request error
-    StreamRequest(CannotCloneRequestError),
-
     /// Stream error
     StreamError(String),
 
     /// Stream receive error
     StreamReceiveError(tokio::sync::mpsc::error::TryRecvError),
+
+    /// No data was received from a stream for the given time
+    StreamIdleTimeout(std::time::Duration),
+
+    /// The stream ended before the `[DONE]` marker, e.g. because the connection dropped,
+    /// so the received output is incomplete
+    StreamTruncated,
+
+    /// The model did not produce the requested structured output
+    StructuredOutput(StructuredOutputError),
+}
+
+/// Why a structured output could not be parsed from the response of the model.
+#[derive(Debug)]
+pub enum StructuredOutputError {
+    /// The model refused to answer, with the given refusal message
+    Refusal(String),
+
+    /// The output was cut off, because the maximum number of tokens was reached.
+    /// Contains the partial output.
+    Truncated(String),
+
+    /// The output does not match the expected type
+    SchemaMismatch {
+        /// The output of the model
+        content: String,
+
+        /// The deserialization error
+        error: serde_json::Error,
+    },
+
+    /// The response has no output, e.g. because it was omitted by the content filter.
+    /// Contains the finish reason.
+    MissingContent(String),
+}
+
+impl std::fmt::Display for StructuredOutputError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Refusal(refusal) => write!(f, "The model refused to answer: {}", refusal),
+            Self::Truncated(_) => write!(
+                f,
+                "The output was truncated at the maximum number of tokens"
+            ),
+            Self::SchemaMismatch { error, .. } => {
+                write!(f, "The output does not match the schema: {}", error)
+            }
+            Self::MissingContent(finish_reason) => {
+                write!(
+                    f,
+                    "The response has no output, finish reason: {}",
+                    finish_reason
+                )
+            }
+        }
+    }
 }
 
 impl std::fmt::Display for OpenAIError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
+            Self::Api(error) => error.fmt(f),
             Self::Serde(error) => error.fmt(f),
             Self::Client(error) => error.fmt(f),
             Self::Decoder(error) => error.fmt(f),
             Self::Exception(message) => message.fmt(f),
             Self::IO(error) => error.fmt(f),
-            Self::StreamRequest(error) => error.fmt(f),
             Self::StreamError(message) => message.fmt(f),
             Self::StreamReceiveError(error) => error.fmt(f),
+            Self::StructuredOutput(error) => error.fmt(f),
+            Self::StreamIdleTimeout(timeout) => {
+                write!(f, "No data received from the stream for {:?}", timeout)
+            }
+            Self::StreamTruncated => write!(f, "The stream ended before [DONE]"),
+        }
+    }
+}
+
+impl OpenAIError {
+    /// Returns the API error, if the error was returned by the OpenAI API.
+    pub fn api_error(&self) -> Option<&ApiError> {
+        match self {
+            Self::Api(error) => Some(error.as_ref()),
+            _ => None,
+        }
+    }
+
+    /// Whether the request was rejected because of rate limiting.
+    pub fn is_rate_limited(&self) -> bool {
+        self.api_error().is_some_and(ApiError::is_rate_limited)
+    }
+
+    /// Whether the request exceeded the context window of the model.
+    pub fn is_context_length_exceeded(&self) -> bool {
+        self.api_error()
+            .is_some_and(ApiError::is_context_length_exceeded)
+    }
+
+    /// Whether retrying the same request may succeed. Besides retryable API errors,
+    /// this includes timeouts and connection failures of the underlying client.
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            Self::Api(error) => error.is_retryable(),
+            Self::Client(error) => error.is_timeout() || error.is_connect(),
+            _ => false,
         }
     }
 }
 
+impl From<ApiError> for OpenAIError {
+    fn from(value: ApiError) -> Self {
+        Self::Api(Box::new(value))
+    }
+}
+
+impl From<StructuredOutputError> for OpenAIError {
+    fn from(value: StructuredOutputError) -> Self {
+        Self::StructuredOutput(value)
+    }
+}
+
 impl From<reqwest::Error> for OpenAIError {
     fn from(value: reqwest::Error) -> Self {
         Self::Client(value)
@@ -67,12 +171,6 @@ impl From<std::io::Error> for OpenAIError {
     }
 }
 
-impl From<CannotCloneRequestError> for OpenAIError {
-    fn from(value: CannotCloneRequestError) -> Self {
-        Self::StreamRequest(value)
-    }
-}
-
 impl From<serde_json::Error> for OpenAIError {
     fn from(value: serde_json::Error) -> Self {
         Self::Serde(value)
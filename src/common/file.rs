@@ -1,18 +1,34 @@
-use std::{fs, path::Path};
+use std::{fs, io::Cursor, path::Path, pin::Pin};
 
-use tokio::fs::File;
-use tokio_util::codec::{Decoder, FramedRead};
+use tokio::{
+    fs::File,
+    io::{AsyncRead, AsyncSeekExt},
+};
+use tokio_util::{
+    bytes::Bytes,
+    codec::{Decoder, FramedRead},
+};
 
 use super::OpenAIError;
 
-/// A structure representing a file that can be uploaded to OpenAI
+/// A structure representing a file that can be uploaded to OpenAI, read from disk or held in memory
 #[derive(Debug)]
 pub struct OpenAIFile {
     /// The name of the file
     pub name: String,
 
+    /// The content of the file
+    pub content: OpenAIFileContent,
+}
+
+/// The content of an [`OpenAIFile`]
+#[derive(Debug)]
+pub enum OpenAIFileContent {
     /// File handle
-    pub file: File,
+    File(File),
+
+    /// Content held in memory
+    Bytes(Bytes),
 }
 
 impl OpenAIFile {
@@ -32,29 +48,64 @@ impl OpenAIFile {
                 ))
             })?
             .to_str()
-            .ok_or_else(|| {
-                OpenAIError::IO(std::io::Error::new(
-                    std::io::ErrorKind::Other,
-                    "Could not stringify filename",
-                ))
-            })?
+            .ok_or_else(|| OpenAIError::IO(std::io::Error::other("Could not stringify filename")))?
             .to_string();
 
         let file = File::open(path).await?;
-        Ok(Self { name, file })
+        Ok(Self {
+            name,
+            content: OpenAIFileContent::File(file),
+        })
+    }
+
+    /// Creates an OpenAI file from content held in memory
+    pub fn from_bytes<N, B>(name: N, bytes: B) -> Self
+    where
+        N: Into<String>,
+        B: Into<Bytes>,
+    {
+        Self {
+            name: name.into(),
+            content: OpenAIFileContent::Bytes(bytes.into()),
+        }
+    }
+
+    /// Creates a new handle to the same file, rewound to its start.
+    ///
+    /// The handles share the underlying file cursor, so only one of them should be read at a time.
+    pub async fn try_clone(&self) -> Result<Self, OpenAIError> {
+        let content = match &self.content {
+            OpenAIFileContent::File(file) => {
+                let mut file = file.try_clone().await?;
+                let _ = file.rewind().await?;
+
+                OpenAIFileContent::File(file)
+            }
+            OpenAIFileContent::Bytes(bytes) => OpenAIFileContent::Bytes(bytes.clone()),
+        };
+
+        Ok(Self {
+            name: self.name.clone(),
+            content,
+        })
     }
 
     /// Converts the file into a stream
-    pub fn into_stream<D>(self, decoder: D) -> FramedRead<File, D>
+    pub fn into_stream<D>(self, decoder: D) -> FramedRead<Pin<Box<dyn AsyncRead + Send + Sync>>, D>
     where
         D: Decoder,
     {
-        FramedRead::new(self.file, decoder)
+        let reader: Pin<Box<dyn AsyncRead + Send + Sync>> = match self.content {
+            OpenAIFileContent::File(file) => Box::pin(file),
+            OpenAIFileContent::Bytes(bytes) => Box::pin(Cursor::new(bytes)),
+        };
+
+        FramedRead::new(reader, decoder)
     }
 }
 
 /// Dowloads a file from the web and saves it
-pub async fn download_file(url: &str) -> Result<tokio_util::bytes::Bytes, OpenAIError> {
+pub async fn download_file(url: &str) -> Result<Bytes, OpenAIError> {
     let mut response = reqwest::get(url).await?;
 
     if !response.status().is_success() {
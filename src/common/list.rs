@@ -0,0 +1,201 @@
+use std::{collections::VecDeque, future::Future};
+
+use futures_util::{stream, Stream};
+use serde::{Deserialize, Serialize};
+
+use super::OpenAIError;
+
+/// A page of objects returned by a list endpoint.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ListResponse<T> {
+    /// The object type, which is always `list`.
+    pub object: String,
+
+    /// The objects of the page.
+    pub data: Vec<T>,
+
+    /// The ID of the first object of the page, if the endpoint returns it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub first_id: Option<String>,
+
+    /// The ID of the last object of the page, if the endpoint returns it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_id: Option<String>,
+
+    /// Whether there are more objects after this page.
+    #[serde(default)]
+    pub has_more: bool,
+}
+
+impl<T> Default for ListResponse<T> {
+    fn default() -> Self {
+        Self {
+            object: "list".to_string(),
+            data: vec![],
+            first_id: None,
+            last_id: None,
+            has_more: false,
+        }
+    }
+}
+
+impl<T> ListResponse<T>
+where
+    T: ListItem,
+{
+    /// The `after` cursor of the next page, `None` if this is the last page.
+    /// Endpoints without `last_id` are continued after the last object of the page.
+    pub fn next_cursor(&self) -> Option<&str> {
+        if !self.has_more {
+            return None;
+        }
+
+        self.last_id
+            .as_deref()
+            .or_else(|| self.data.last().map(ListItem::id))
+    }
+}
+
+/// An object of a list endpoint, whose ID can be used as a pagination cursor.
+pub trait ListItem {
+    /// The ID of the object
+    fn id(&self) -> &str;
+}
+
+struct Pages<T, F> {
+    fetch: F,
+    cursor: Option<String>,
+    items: VecDeque<T>,
+    has_more: bool,
+    remaining: Option<usize>,
+}
+
+/// Streams the objects of a list endpoint, fetching the pages lazily. `fetch` is called with the `after` cursor
+/// of the next page, `None` for the first one. Stops after `max_items` objects, if set, or after the first error.
+pub(crate) fn paginate<'a, T, F, Fut>(
+    max_items: Option<usize>,
+    fetch: F,
+) -> impl Stream<Item = Result<T, OpenAIError>> + 'a
+where
+    T: ListItem + 'a,
+    F: FnMut(Option<String>) -> Fut + 'a,
+    Fut: Future<Output = Result<ListResponse<T>, OpenAIError>> + 'a,
+{
+    let pages = Pages {
+        fetch,
+        cursor: None,
+        items: VecDeque::new(),
+        has_more: true,
+        remaining: max_items,
+    };
+
+    stream::unfold(pages, |mut pages| async move {
+        loop {
+            if pages.remaining == Some(0) {
+                return None;
+            }
+
+            if let Some(item) = pages.items.pop_front() {
+                if let Some(remaining) = &mut pages.remaining {
+                    *remaining -= 1;
+                }
+
+                return Some((Ok(item), pages));
+            }
+
+            if !pages.has_more {
+                return None;
+            }
+
+            match (pages.fetch)(pages.cursor.take()).await {
+                Ok(page) => {
+                    pages.cursor = page.next_cursor().map(ToString::to_string);
+                    pages.has_more = pages.cursor.is_some() && !page.data.is_empty();
+                    pages.items.extend(page.data);
+                }
+                Err(error) => {
+                    pages.has_more = false;
+                    return Some((Err(error), pages));
+                }
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use futures_util::StreamExt;
+    use serde_json::json;
+
+    use super::*;
+
+    #[derive(Debug, Deserialize)]
+    struct Item {
+        id: String,
+    }
+
+    impl ListItem for Item {
+        fn id(&self) -> &str {
+            &self.id
+        }
+    }
+
+    fn page(ids: &[&str], has_more: bool) -> ListResponse<Item> {
+        serde_json::from_value(json!({
+            "object": "list",
+            "data": ids.iter().map(|id| json!({ "id": id })).collect::<Vec<_>>(),
+            "has_more": has_more,
+        }))
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn paginates_lazily() {
+        let cursors = Arc::new(Mutex::new(vec![]));
+        let fetched = cursors.clone();
+
+        let items = paginate(Some(3), move |after: Option<String>| {
+            fetched.lock().unwrap().push(after.clone());
+            let page = match after.as_deref() {
+                None => page(&["a", "b"], true),
+                Some("b") => page(&["c", "d"], true),
+                _ => page(&["e"], false),
+            };
+
+            async move { Ok(page) }
+        })
+        .map(|item| item.unwrap().id)
+        .collect::<Vec<_>>()
+        .await;
+
+        assert_eq!(items, ["a", "b", "c"]);
+        assert_eq!(*cursors.lock().unwrap(), [None, Some("b".to_string())]);
+
+        let items = paginate(None, |after: Option<String>| {
+            let page = match after.as_deref() {
+                None => page(&["a", "b"], true),
+                _ => page(&["c"], false),
+            };
+
+            async move { Ok(page) }
+        })
+        .collect::<Vec<_>>()
+        .await;
+
+        assert_eq!(items.len(), 3);
+    }
+
+    #[tokio::test]
+    async fn stops_after_error() {
+        let items = paginate(None, |_| async {
+            Err::<ListResponse<Item>, _>(OpenAIError::Exception("failed".to_string()))
+        })
+        .collect::<Vec<_>>()
+        .await;
+
+        assert_eq!(items.len(), 1);
+        assert!(items[0].is_err());
+    }
+}
@@ -0,0 +1,195 @@
+use async_trait::async_trait;
+use reqwest::Response;
+
+use super::{OpenAIError, OpenAIRequest};
+
+/// A hook around every request the client sends, installed with
+/// [`ClientBuilder::with_middleware`](crate::ClientBuilder::with_middleware).
+///
+/// Middlewares run in the order they were installed. Each attempt of a retried request passes
+/// through the whole chain again, starting from the original, unmodified request.
+///
+/// ```ignore
+/// #[derive(Debug)]
+/// struct TenantHeader(String);
+///
+/// #[async_trait::async_trait]
+/// impl Middleware for TenantHeader {
+///     async fn on_request(&self, request: &mut OpenAIRequest) -> Result<Option<Response>, OpenAIError> {
+///         request.set_header("x-tenant-id", &self.0)?;
+///         Ok(None)
+///     }
+/// }
+/// ```
+#[async_trait]
+pub trait Middleware: std::fmt::Debug + Send + Sync {
+    /// Inspects or modifies the outgoing request.
+    ///
+    /// Returning a response short-circuits the request: it is not sent, and the response is passed
+    /// back through the middlewares that already ran, as if it came from OpenAI.
+    async fn on_request(
+        &self,
+        request: &mut OpenAIRequest,
+    ) -> Result<Option<Response>, OpenAIError> {
+        let _ = request;
+        Ok(None)
+    }
+
+    /// Inspects or replaces the response, or the error, received for the request.
+    ///
+    /// This is called before unsuccessful statuses are turned into [`OpenAIError::Api`],
+    /// so error responses can be inspected here too.
+    async fn on_response(
+        &self,
+        request: &OpenAIRequest,
+        result: Result<Response, OpenAIError>,
+    ) -> Result<Response, OpenAIError> {
+        let _ = request;
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use serde_json::{json, Value};
+
+    use super::*;
+    use crate::{embeddings::CreateEmbeddingsRequest, ClientBuilder, RequestBody};
+
+    #[derive(Debug)]
+    struct Recorder {
+        name: &'static str,
+        log: Arc<Mutex<Vec<String>>>,
+        sent: Arc<Mutex<Vec<String>>>,
+    }
+
+    impl Recorder {
+        fn new(name: &'static str, log: &Arc<Mutex<Vec<String>>>) -> Self {
+            Self {
+                name,
+                log: log.clone(),
+                sent: Default::default(),
+            }
+        }
+    }
+
+    fn json_response(body: Value) -> Response {
+        http::Response::builder()
+            .header("content-type", "application/json")
+            .body(body.to_string())
+            .unwrap()
+            .into()
+    }
+
+    #[async_trait]
+    impl Middleware for Recorder {
+        async fn on_request(
+            &self,
+            request: &mut OpenAIRequest,
+        ) -> Result<Option<Response>, OpenAIError> {
+            self.log
+                .lock()
+                .unwrap()
+                .push(format!("request {}", self.name));
+
+            if self.name == "tenant" {
+                request.set_header("x-tenant-id", "tenant-1")?;
+
+                if let RequestBody::Json(body) = &mut request.body {
+                    body["user"] = json!("tenant-1");
+                }
+            }
+
+            if self.name == "cache" {
+                let tenant = request.headers.get("x-tenant-id");
+                let user = match &request.body {
+                    RequestBody::Json(body) => body.get("user").cloned(),
+                    _ => None,
+                };
+                self.sent
+                    .lock()
+                    .unwrap()
+                    .push(format!("{tenant:?} {user:?}"));
+
+                let response = if request.url.ends_with("/models") {
+                    json!({ "object": "list", "data": [] })
+                } else {
+                    json!({
+                        "object": "list",
+                        "data": [{ "object": "embedding", "index": 0, "embedding": [0.1] }],
+                        "model": "text-embedding-ada-002",
+                        "usage": { "prompt_tokens": 1, "total_tokens": 1 }
+                    })
+                };
+
+                return Ok(Some(json_response(response)));
+            }
+
+            Ok(None)
+        }
+
+        async fn on_response(
+            &self,
+            _request: &OpenAIRequest,
+            result: Result<Response, OpenAIError>,
+        ) -> Result<Response, OpenAIError> {
+            self.log
+                .lock()
+                .unwrap()
+                .push(format!("response {}", self.name));
+            result
+        }
+    }
+
+    #[tokio::test]
+    async fn runs_middlewares_in_order() {
+        let log = Arc::new(Mutex::new(vec![]));
+        let cache = Recorder::new("cache", &log);
+        let sent = cache.sent.clone();
+
+        let client = ClientBuilder::new("test-token")
+            .with_middleware(Recorder::new("tenant", &log))
+            .with_middleware(cache)
+            .with_middleware(Recorder::new("network", &log))
+            .build()
+            .unwrap();
+
+        let _ = client
+            .embeddings()
+            .create_embeddings(CreateEmbeddingsRequest::from_single_input("Hello"))
+            .await
+            .unwrap();
+
+        assert_eq!(
+            sent.lock().unwrap()[0],
+            r#"Some("tenant-1") Some(String("tenant-1"))"#,
+            "later middlewares see the changes of earlier ones"
+        );
+        assert_eq!(
+            *log.lock().unwrap(),
+            [
+                "request tenant",
+                "request cache",
+                "response cache",
+                "response tenant"
+            ],
+            "short-circuited requests skip the following middlewares"
+        );
+
+        log.lock().unwrap().clear();
+        let models = client.models().list_models().await.unwrap();
+
+        assert!(models.data.is_empty());
+        assert_eq!(
+            *log.lock().unwrap(),
+            [
+                "request tenant",
+                "request cache",
+                "response cache",
+                "response tenant"
+            ]
+        );
+    }
+}
@@ -0,0 +1,460 @@
+use std::{
+    collections::VecDeque,
+    sync::{Arc, Mutex, MutexGuard},
+};
+
+use async_trait::async_trait;
+use reqwest::{
+    header::{HeaderMap, HeaderName, HeaderValue, CONTENT_TYPE},
+    Body, Method, Response, StatusCode,
+};
+use serde_json::Value;
+
+use super::{MultipartPart, OpenAIError, OpenAIRequest, RequestBody, Transport};
+
+/// An in-memory [`Transport`] that answers requests with scripted responses, so code built on
+/// the client can be tested without a network connection or an API token.
+///
+/// Expectations are answered in the order they were added. The transport is cheap to clone and
+/// clones share their state, so a test can keep a copy to inspect the requests after handing
+/// the other to [`ClientBuilder::transport`](crate::ClientBuilder::transport).
+///
+/// ```ignore
+/// let mock = MockTransport::new();
+/// mock.expect(
+///     MockExpectation::new(Method::GET, "/v1/models/gpt-4")
+///         .respond_with(MockResponse::json(json!({ "id": "gpt-4", ... }))),
+/// );
+///
+/// let client = ClientBuilder::new("token").transport(mock.clone()).build()?;
+/// let model = client.models().retrieve_model("gpt-4").await?;
+///
+/// mock.assert_finished();
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct MockTransport {
+    state: Arc<Mutex<MockState>>,
+}
+
+#[derive(Debug, Default)]
+struct MockState {
+    expectations: VecDeque<MockExpectation>,
+    requests: Vec<RecordedRequest>,
+}
+
+/// How a request reached a [`MockTransport`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MockRequestKind {
+    /// Sent with [`Transport::send_json`]
+    Json,
+
+    /// Sent with [`Transport::send_multipart`]
+    Multipart,
+
+    /// Sent with [`Transport::open_stream`]
+    Stream,
+}
+
+/// A request received by a [`MockTransport`].
+#[derive(Debug, Clone)]
+pub struct RecordedRequest {
+    /// How the request was sent
+    pub kind: MockRequestKind,
+
+    /// The HTTP method
+    pub method: Method,
+
+    /// The path of the endpoint
+    pub url: String,
+
+    /// Query parameters
+    pub query: Vec<(String, String)>,
+
+    /// Headers added to the request
+    pub headers: HeaderMap,
+
+    /// The JSON body, if the request had one
+    pub body: Option<Value>,
+
+    /// The fields of a multipart body. Files are recorded with their file name.
+    pub form: Vec<(String, String)>,
+}
+
+/// A request a [`MockTransport`] expects, and the response it answers with.
+#[derive(Debug)]
+pub struct MockExpectation {
+    method: Method,
+    url: String,
+    body: Option<Value>,
+    response: MockResponse,
+}
+
+impl MockExpectation {
+    /// Expects a request to the endpoint. Unless set with [`Self::respond_with`],
+    /// it's answered with an empty JSON object.
+    pub fn new<S>(method: Method, url: S) -> Self
+    where
+        S: Into<String>,
+    {
+        Self {
+            method,
+            url: url.into(),
+            body: None,
+            response: MockResponse::json(Value::Object(Default::default())),
+        }
+    }
+
+    /// Expects the JSON body of the request to contain the given value. Objects are compared
+    /// field by field, so only the fields of interest have to be listed.
+    pub fn with_body(mut self, body: Value) -> Self {
+        self.body = Some(body);
+        self
+    }
+
+    /// Sets the response to the request.
+    pub fn respond_with(mut self, response: MockResponse) -> Self {
+        self.response = response;
+        self
+    }
+
+    /// Explains why the request doesn't match, if it doesn't
+    fn mismatch(&self, request: &RecordedRequest) -> Option<String> {
+        if self.method != request.method || trim_path(&self.url) != trim_path(&request.url) {
+            return Some(format!(
+                "expected {} {}, got {} {}",
+                self.method, self.url, request.method, request.url
+            ));
+        }
+
+        match (&self.body, &request.body) {
+            (Some(expected), Some(actual)) if !json_contains(actual, expected) => Some(format!(
+                "body of {} {} doesn't match, expected {expected}, got {actual}",
+                self.method, self.url
+            )),
+            (Some(_), None) => Some(format!(
+                "expected a JSON body for {} {}",
+                self.method, self.url
+            )),
+            _ => None,
+        }
+    }
+}
+
+/// A canned response of a [`MockTransport`].
+#[derive(Debug, Clone)]
+pub struct MockResponse {
+    status: StatusCode,
+    headers: HeaderMap,
+    chunks: Vec<Vec<u8>>,
+}
+
+impl MockResponse {
+    /// Creates an empty response with the status.
+    pub fn new(status: StatusCode) -> Self {
+        Self {
+            status,
+            headers: HeaderMap::new(),
+            chunks: vec![],
+        }
+    }
+
+    /// Creates a successful response with a JSON body.
+    pub fn json(body: Value) -> Self {
+        Self::new(StatusCode::OK)
+            .header(CONTENT_TYPE.as_str(), "application/json")
+            .body(body.to_string())
+    }
+
+    /// Creates an error response, shaped like the errors of the OpenAI API.
+    pub fn error<S>(status: StatusCode, message: S) -> Self
+    where
+        S: Into<String>,
+    {
+        let body = serde_json::json!({
+            "error": {
+                "message": message.into(),
+                "type": "invalid_request_error",
+                "param": null,
+                "code": null,
+            }
+        });
+
+        Self {
+            status,
+            ..Self::json(body)
+        }
+    }
+
+    /// Creates a successful event stream, sending every value as a separate `data` event,
+    /// followed by the `[DONE]` marker.
+    pub fn sse<I>(events: I) -> Self
+    where
+        I: IntoIterator<Item = Value>,
+    {
+        let chunks = events
+            .into_iter()
+            .map(|event| format!("data: {event}\n\n"))
+            .chain(std::iter::once("data: [DONE]\n\n".to_string()))
+            .collect();
+
+        Self::sse_chunks(chunks)
+    }
+
+    /// Creates a successful event stream out of raw chunks of the body. Each chunk is
+    /// delivered separately, so events split over several chunks can be simulated.
+    pub fn sse_chunks(chunks: Vec<String>) -> Self {
+        Self {
+            chunks: chunks.into_iter().map(String::into_bytes).collect(),
+            ..Self::new(StatusCode::OK).header(CONTENT_TYPE.as_str(), "text/event-stream")
+        }
+    }
+
+    /// Sets the status of the response.
+    pub fn status(mut self, status: StatusCode) -> Self {
+        self.status = status;
+        self
+    }
+
+    /// Sets a header of the response. Invalid names or values are ignored.
+    pub fn header(mut self, name: &str, value: &str) -> Self {
+        if let (Ok(name), Ok(value)) = (
+            HeaderName::from_bytes(name.as_bytes()),
+            HeaderValue::from_str(value),
+        ) {
+            let _ = self.headers.insert(name, value);
+        }
+
+        self
+    }
+
+    /// Adds a header to the response, keeping the values it already has. Invalid names or values are ignored.
+    pub fn append_header(mut self, name: &str, value: &str) -> Self {
+        if let (Ok(name), Ok(value)) = (
+            HeaderName::from_bytes(name.as_bytes()),
+            HeaderValue::from_str(value),
+        ) {
+            let _ = self.headers.append(name, value);
+        }
+
+        self
+    }
+
+    /// Sets the body of the response, text or binary.
+    pub fn body<B>(mut self, body: B) -> Self
+    where
+        B: Into<Vec<u8>>,
+    {
+        self.chunks = vec![body.into()];
+        self
+    }
+
+    pub(crate) fn into_response(self) -> Result<Response, OpenAIError> {
+        let chunks = self
+            .chunks
+            .into_iter()
+            .map(Ok::<_, std::io::Error>)
+            .collect::<Vec<_>>();
+
+        let mut response = http::Response::builder()
+            .status(self.status)
+            .body(Body::wrap_stream(futures_util::stream::iter(chunks)))
+            .map_err(|e| OpenAIError::Exception(e.to_string()))?;
+
+        *response.headers_mut() = self.headers;
+
+        Ok(Response::from(response))
+    }
+}
+
+impl MockTransport {
+    /// Creates a transport without any expectations.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds an expected request.
+    pub fn expect(&self, expectation: MockExpectation) -> &Self {
+        self.state().expectations.push_back(expectation);
+        self
+    }
+
+    /// Returns the requests received so far.
+    pub fn requests(&self) -> Vec<RecordedRequest> {
+        self.state().requests.clone()
+    }
+
+    /// Returns the number of expected requests that have not been received yet.
+    pub fn pending(&self) -> usize {
+        self.state().expectations.len()
+    }
+
+    /// Panics if any expected request has not been received.
+    pub fn assert_finished(&self) {
+        let state = self.state();
+
+        if let Some(expectation) = state.expectations.front() {
+            panic!(
+                "MockTransport: {} expected request(s) not received, next is {} {}",
+                state.expectations.len(),
+                expectation.method,
+                expectation.url
+            );
+        }
+    }
+
+    fn state(&self) -> MutexGuard<'_, MockState> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn respond(
+        &self,
+        kind: MockRequestKind,
+        request: &OpenAIRequest,
+    ) -> Result<Response, OpenAIError> {
+        let recorded = RecordedRequest::new(kind, request);
+        let mut state = self.state();
+
+        let Some(expectation) = state.expectations.pop_front() else {
+            return Err(OpenAIError::Exception(format!(
+                "MockTransport: unexpected request {} {}",
+                recorded.method, recorded.url
+            )));
+        };
+
+        if let Some(mismatch) = expectation.mismatch(&recorded) {
+            // The expectation stays pending, so `assert_finished` reports it
+            state.expectations.push_front(expectation);
+            return Err(OpenAIError::Exception(format!("MockTransport: {mismatch}")));
+        }
+
+        state.requests.push(recorded);
+        drop(state);
+
+        expectation.response.into_response()
+    }
+}
+
+impl RecordedRequest {
+    fn new(kind: MockRequestKind, request: &OpenAIRequest) -> Self {
+        let (body, form) = match &request.body {
+            RequestBody::Empty => (None, vec![]),
+            RequestBody::Json(body) => (Some(body.clone()), vec![]),
+            RequestBody::Multipart(form) => {
+                let fields = form
+                    .parts()
+                    .iter()
+                    .map(|(name, part)| match part {
+                        MultipartPart::Text(value) => (name.clone(), value.clone()),
+                        MultipartPart::File(file) => (name.clone(), file.name.clone()),
+                    })
+                    .collect();
+
+                (None, fields)
+            }
+        };
+
+        Self {
+            kind,
+            method: request.method.clone(),
+            url: request.url.clone(),
+            query: request.query.clone(),
+            headers: request.headers.clone(),
+            body,
+            form,
+        }
+    }
+}
+
+#[async_trait]
+impl Transport for MockTransport {
+    async fn send_json(&self, request: &OpenAIRequest) -> Result<Response, OpenAIError> {
+        self.respond(MockRequestKind::Json, request)
+    }
+
+    async fn send_multipart(&self, request: &OpenAIRequest) -> Result<Response, OpenAIError> {
+        self.respond(MockRequestKind::Multipart, request)
+    }
+
+    async fn open_stream(&self, request: &OpenAIRequest) -> Result<Response, OpenAIError> {
+        self.respond(MockRequestKind::Stream, request)
+    }
+}
+
+fn trim_path(url: &str) -> &str {
+    url.trim_start_matches('/')
+}
+
+/// Checks that every field of the expected value is present in the actual one.
+/// Arrays have to be of the same length, with their elements compared the same way.
+fn json_contains(actual: &Value, expected: &Value) -> bool {
+    match (actual, expected) {
+        (Value::Array(actual), Value::Array(expected)) => {
+            actual.len() == expected.len()
+                && actual
+                    .iter()
+                    .zip(expected)
+                    .all(|(actual, expected)| json_contains(actual, expected))
+        }
+        (Value::Object(actual), Value::Object(expected)) => expected.iter().all(|(key, value)| {
+            actual
+                .get(key)
+                .is_some_and(|actual| json_contains(actual, value))
+        }),
+        _ => actual == expected,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn json_contains_test() {
+        let actual = json!({ "model": "gpt-4", "n": 1, "stream": { "include_usage": true } });
+
+        assert!(json_contains(&actual, &json!({ "model": "gpt-4" })));
+        assert!(json_contains(&actual, &json!({ "stream": {} })));
+        assert!(!json_contains(
+            &actual,
+            &json!({ "model": "gpt-3.5-turbo" })
+        ));
+        assert!(!json_contains(&actual, &json!({ "user": "me" })));
+
+        let actual = json!({ "tools": [{ "type": "function", "function": { "name": "a" } }] });
+        assert!(json_contains(
+            &actual,
+            &json!({ "tools": [{ "function": { "name": "a" } }] })
+        ));
+        assert!(!json_contains(&actual, &json!({ "tools": [] })));
+    }
+
+    #[tokio::test]
+    async fn mock_transport_test() {
+        let mock = MockTransport::new();
+        let _ = mock
+            .expect(
+                MockExpectation::new(Method::POST, "v1/embeddings")
+                    .with_body(json!({ "model": "text-embedding-ada-002" }))
+                    .respond_with(MockResponse::json(json!({ "ok": true }))),
+            )
+            .expect(MockExpectation::new(Method::GET, "/v1/models"));
+
+        let request = OpenAIRequest::with_body(
+            Method::POST,
+            "/v1/embeddings".to_string(),
+            json!({ "model": "text-embedding-ada-002", "input": "hi" }),
+        )
+        .unwrap();
+
+        let response = mock.send_json(&request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.text().await.unwrap(), r#"{"ok":true}"#);
+
+        let request = OpenAIRequest::new(Method::GET, "/v1/files".to_string());
+        assert!(mock.send_json(&request).await.is_err());
+        assert_eq!(mock.pending(), 1);
+        assert_eq!(mock.requests().len(), 1);
+    }
+}
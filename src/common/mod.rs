@@ -1,18 +1,47 @@
+mod api_error;
+#[cfg(feature = "cassette")]
+mod cassette;
 mod chat_message;
 mod error;
 mod file;
+mod list;
+mod middleware;
+mod mock;
+mod model_info;
 mod models;
+mod multipart;
 mod query_parameters;
+mod rate_limit;
 mod request;
+mod response_meta;
+mod retry;
+mod schema;
+mod sse;
 mod stream;
 mod token_usage;
+mod transport;
+mod usage;
 
-pub(crate) use self::request::*;
-
+pub use self::api_error::*;
+#[cfg(feature = "cassette")]
+pub use self::cassette::*;
 pub use self::chat_message::*;
 pub use self::error::*;
 pub use self::file::*;
+pub use self::list::*;
+pub use self::middleware::*;
+pub use self::mock::*;
+pub use self::model_info::*;
 pub use self::models::*;
+pub use self::multipart::*;
 pub use self::query_parameters::*;
+pub use self::rate_limit::*;
+pub use self::request::*;
+pub use self::response_meta::*;
+pub use self::retry::*;
+pub use self::schema::*;
+pub use self::sse::*;
 pub use self::stream::*;
 pub use self::token_usage::*;
+pub use self::transport::*;
+pub use self::usage::*;
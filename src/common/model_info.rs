@@ -0,0 +1,568 @@
+use std::{collections::HashMap, path::Path, sync::OnceLock};
+
+use serde::{Deserialize, Serialize};
+
+use super::{
+    AudioModel, ChatModel, CompletionModel, EmbeddingModel, ImageGenerationModel, ModelId,
+    OpenAIError, TokenUsage, TtsModel,
+};
+
+/// Capabilities and prices of a model.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ModelInfo {
+    /// The maximum number of tokens of the prompt and the completion together.
+    /// Zero for models that don't take tokens, such as image or audio models.
+    #[serde(default)]
+    pub context_window: usize,
+
+    /// The maximum number of tokens the model generates in a completion
+    #[serde(default)]
+    pub max_output_tokens: Option<usize>,
+
+    /// Whether the model can call tools
+    #[serde(default)]
+    pub supports_tools: bool,
+
+    /// Whether the model accepts images as input
+    #[serde(default)]
+    pub supports_vision: bool,
+
+    /// Whether the model supports [Structured Outputs](https://platform.openai.com/docs/guides/structured-outputs)
+    /// with the `json_schema` response format
+    #[serde(default)]
+    pub supports_json_schema: bool,
+
+    /// The price of 1M input tokens, in USD
+    #[serde(default)]
+    pub input_price_per_million: Option<f64>,
+
+    /// The price of 1M output tokens, in USD
+    #[serde(default)]
+    pub output_price_per_million: Option<f64>,
+
+    /// The price of a generated image in the default `1024x1024` size and `standard` quality, in USD
+    #[serde(default)]
+    pub image_price: Option<f64>,
+
+    /// The price of a minute of transcribed or translated audio, in USD
+    #[serde(default)]
+    pub audio_price_per_minute: Option<f64>,
+
+    /// The price of 1M characters converted to speech, in USD
+    #[serde(default)]
+    pub character_price_per_million: Option<f64>,
+}
+
+impl ModelInfo {
+    /// Creates the info of a model with the given context window, without capabilities or prices.
+    pub fn new(context_window: usize) -> Self {
+        Self {
+            context_window,
+            max_output_tokens: None,
+            supports_tools: false,
+            supports_vision: false,
+            supports_json_schema: false,
+            input_price_per_million: None,
+            output_price_per_million: None,
+            image_price: None,
+            audio_price_per_minute: None,
+            character_price_per_million: None,
+        }
+    }
+
+    /// Sets the maximum number of output tokens.
+    pub fn with_max_output_tokens(mut self, max_output_tokens: usize) -> Self {
+        self.max_output_tokens = Some(max_output_tokens);
+        self
+    }
+
+    /// Sets whether the model supports tools, vision and Structured Outputs.
+    pub fn with_capabilities(mut self, tools: bool, vision: bool, json_schema: bool) -> Self {
+        self.supports_tools = tools;
+        self.supports_vision = vision;
+        self.supports_json_schema = json_schema;
+        self
+    }
+
+    /// Sets the prices of 1M input and output tokens, in USD.
+    pub fn with_prices(mut self, input_per_million: f64, output_per_million: f64) -> Self {
+        self.input_price_per_million = Some(input_per_million);
+        self.output_price_per_million = Some(output_per_million);
+        self
+    }
+
+    /// Sets the price of a generated image in the default size and quality, in USD.
+    pub fn with_image_price(mut self, image_price: f64) -> Self {
+        self.image_price = Some(image_price);
+        self
+    }
+
+    /// Sets the price of a minute of transcribed or translated audio, in USD.
+    pub fn with_audio_price_per_minute(mut self, audio_price_per_minute: f64) -> Self {
+        self.audio_price_per_minute = Some(audio_price_per_minute);
+        self
+    }
+
+    /// Sets the price of 1M characters converted to speech, in USD.
+    pub fn with_character_price_per_million(mut self, character_price_per_million: f64) -> Self {
+        self.character_price_per_million = Some(character_price_per_million);
+        self
+    }
+
+    /// Returns the capabilities of the model, without any prices.
+    fn without_prices(&self) -> Self {
+        Self {
+            input_price_per_million: None,
+            output_price_per_million: None,
+            image_price: None,
+            audio_price_per_minute: None,
+            character_price_per_million: None,
+            ..self.clone()
+        }
+    }
+
+    /// The cost of the usage in USD, if the prices of the model are known.
+    pub fn cost(&self, usage: &TokenUsage) -> Option<f64> {
+        let input = self.input_price_per_million? * usage.prompt_tokens.max(0) as f64;
+        let output = self.output_price_per_million.unwrap_or_default()
+            * usage.completion_tokens.max(0) as f64;
+
+        Some((input + output) / 1_000_000.0)
+    }
+}
+
+/// Looks up the [`ModelInfo`] of models by their ID.
+///
+/// The default registry knows the models of this crate, and can be extended or overridden from JSON,
+/// as an object of model IDs to their info:
+///
+/// ```json
+/// { "my-model": { "context_window": 8192, "supports_tools": true } }
+/// ```
+///
+/// Fine-tuned models (`ft:<base model>:...`) that aren't registered themselves take the capabilities and context window
+/// of their base model, but no prices, since fine-tuned models are billed at higher rates. New snapshots of a model (e.g. `gpt-4o-2099-01-01` or `gpt-4-0999`) resolve to the model without the date.
+/// Other unknown models, such as `gpt-4.5-preview`, are not resolved.
+#[derive(Debug, Clone)]
+pub struct ModelRegistry {
+    models: HashMap<String, ModelInfo>,
+
+    /// The models without their prices, for fine-tuned models based on them
+    unpriced: HashMap<String, ModelInfo>,
+}
+
+impl Default for ModelRegistry {
+    fn default() -> Self {
+        Self::builtin().clone()
+    }
+}
+
+impl ModelRegistry {
+    /// Creates a registry without any models.
+    pub fn empty() -> Self {
+        Self {
+            models: HashMap::new(),
+            unpriced: HashMap::new(),
+        }
+    }
+
+    /// The registry of the models known to this crate.
+    pub fn builtin() -> &'static Self {
+        static BUILTIN: OnceLock<ModelRegistry> = OnceLock::new();
+
+        BUILTIN.get_or_init(builtin_models)
+    }
+
+    /// Parses a registry from a JSON object of model IDs to their info.
+    pub fn from_json(json: &str) -> Result<Self, OpenAIError> {
+        let mut registry = Self::empty();
+        registry.extend_from_json(json)?;
+
+        Ok(registry)
+    }
+
+    /// Adds or replaces the models of a JSON object of model IDs to their info.
+    pub fn extend_from_json(&mut self, json: &str) -> Result<(), OpenAIError> {
+        let models: HashMap<String, ModelInfo> = serde_json::from_str(json)?;
+
+        for (model, info) in models {
+            self.insert(model, info);
+        }
+
+        Ok(())
+    }
+
+    /// Adds or replaces the models of a JSON file, see [`ModelRegistry::extend_from_json`].
+    pub async fn extend_from_file<P>(&mut self, path: P) -> Result<(), OpenAIError>
+    where
+        P: AsRef<Path>,
+    {
+        let json = tokio::fs::read_to_string(path).await?;
+
+        self.extend_from_json(&json)
+    }
+
+    /// Adds or replaces a model.
+    pub fn with_model<M>(mut self, model: M, info: ModelInfo) -> Self
+    where
+        M: Into<ModelId>,
+    {
+        self.insert(model, info);
+        self
+    }
+
+    /// Adds or replaces a model.
+    pub fn insert<M>(&mut self, model: M, info: ModelInfo)
+    where
+        M: Into<ModelId>,
+    {
+        let model: String = model.into().into();
+
+        let _ = self.unpriced.insert(model.clone(), info.without_prices());
+        let _ = self.models.insert(model, info);
+    }
+
+    /// Returns the info of the model, see [`ModelRegistry`] for how model IDs are resolved.
+    pub fn get<M>(&self, model: M) -> Option<&ModelInfo>
+    where
+        M: Into<ModelId>,
+    {
+        self.resolve(model.into().as_str())
+    }
+
+    fn resolve(&self, id: &str) -> Option<&ModelInfo> {
+        if let Some(info) = self.models.get(id) {
+            return Some(info);
+        }
+
+        match base_model(id) {
+            Some(base) => resolve_snapshot(&self.unpriced, base),
+            None => resolve_snapshot(&self.models, id),
+        }
+    }
+}
+
+/// Looks up a model, or the model without its snapshot suffix
+fn resolve_snapshot<'a>(models: &'a HashMap<String, ModelInfo>, id: &str) -> Option<&'a ModelInfo> {
+    models.get(id).or_else(|| models.get(strip_snapshot(id)?))
+}
+
+/// The ID of a model without its snapshot suffix, which is a date as `-YYYY-MM-DD` or `-MMDD`
+fn strip_snapshot(id: &str) -> Option<&str> {
+    let is_date = |suffix: &str, pattern: &str| {
+        suffix.len() == pattern.len()
+            && suffix
+                .bytes()
+                .zip(pattern.bytes())
+                .all(|(byte, kind)| match kind {
+                    b'0' => byte.is_ascii_digit(),
+                    kind => byte == kind,
+                })
+    };
+
+    ["-0000-00-00", "-0000"].into_iter().find_map(|pattern| {
+        let split = id.len().checked_sub(pattern.len())?;
+        let (model, suffix) = (id.get(..split)?, id.get(split..)?);
+
+        (!model.is_empty() && is_date(suffix, pattern)).then_some(model)
+    })
+}
+
+/// The base model of a fine-tuned model, whose ID is `ft:<base model>:<organization>:<suffix>:<id>`
+fn base_model(id: &str) -> Option<&str> {
+    id.strip_prefix("ft:")?
+        .split(':')
+        .next()
+        .filter(|base| !base.is_empty())
+}
+
+fn builtin_models() -> ModelRegistry {
+    let chat_models = [
+        (
+            ChatModel::GPT4_1,
+            model(1_047_576, 32_768, (true, true, true), (2.0, 8.0)),
+        ),
+        (
+            ChatModel::GPT4_1Mini,
+            model(1_047_576, 32_768, (true, true, true), (0.4, 1.6)),
+        ),
+        (
+            ChatModel::GPT4_1Nano,
+            model(1_047_576, 32_768, (true, true, true), (0.1, 0.4)),
+        ),
+        (
+            ChatModel::GPT4o,
+            model(128_000, 16_384, (true, true, true), (2.5, 10.0)),
+        ),
+        (
+            ChatModel::GPT4o2024_05_13,
+            model(128_000, 4_096, (true, true, false), (5.0, 15.0)),
+        ),
+        (
+            ChatModel::GPT4o2024_08_06,
+            model(128_000, 16_384, (true, true, true), (2.5, 10.0)),
+        ),
+        (
+            ChatModel::GPT4o2024_11_20,
+            model(128_000, 16_384, (true, true, true), (2.5, 10.0)),
+        ),
+        (
+            ChatModel::ChatGPT4oLatest,
+            model(128_000, 16_384, (false, true, false), (5.0, 15.0)),
+        ),
+        (
+            ChatModel::GPT4oMini,
+            model(128_000, 16_384, (true, true, true), (0.15, 0.6)),
+        ),
+        (
+            ChatModel::GPT4oMini2024_07_18,
+            model(128_000, 16_384, (true, true, true), (0.15, 0.6)),
+        ),
+        (
+            ChatModel::O1,
+            model(200_000, 100_000, (true, true, true), (15.0, 60.0)),
+        ),
+        (
+            ChatModel::O1Mini,
+            model(128_000, 65_536, (false, false, false), (1.1, 4.4)),
+        ),
+        (
+            ChatModel::O3,
+            model(200_000, 100_000, (true, true, true), (2.0, 8.0)),
+        ),
+        (
+            ChatModel::O3Mini,
+            model(200_000, 100_000, (true, false, true), (1.1, 4.4)),
+        ),
+        (
+            ChatModel::O4Mini,
+            model(200_000, 100_000, (true, true, true), (1.1, 4.4)),
+        ),
+        (
+            ChatModel::GPT4Turbo,
+            model(128_000, 4_096, (true, true, false), (10.0, 30.0)),
+        ),
+        (
+            ChatModel::GPT4Turbo2024_04_09,
+            model(128_000, 4_096, (true, true, false), (10.0, 30.0)),
+        ),
+        (
+            ChatModel::GPT4TurboPreview,
+            model(128_000, 4_096, (true, false, false), (10.0, 30.0)),
+        ),
+        (
+            ChatModel::GPT4_0125Preview,
+            model(128_000, 4_096, (true, false, false), (10.0, 30.0)),
+        ),
+        (
+            ChatModel::GPT3_5Turbo0125,
+            model(16_385, 4_096, (true, false, false), (0.5, 1.5)),
+        ),
+        (
+            ChatModel::GPT3_5Turbo,
+            model(4_096, 4_096, (true, false, false), (0.5, 1.5)),
+        ),
+        (
+            ChatModel::GPT3_5Turbo1106,
+            model(16_385, 4_096, (true, false, false), (1.0, 2.0)),
+        ),
+        (
+            ChatModel::GPT3_5Turbo16k,
+            model(16_385, 4_096, (true, false, false), (3.0, 4.0)),
+        ),
+        (
+            ChatModel::GPT3_5TurboInstinct,
+            model(4_096, 4_096, (false, false, false), (1.5, 2.0)),
+        ),
+        (
+            ChatModel::GPT3_5Turbo0613,
+            model(4_096, 4_096, (true, false, false), (1.5, 2.0)),
+        ),
+        (
+            ChatModel::GPT3_5Turbo16k0613,
+            model(16_385, 4_096, (true, false, false), (3.0, 4.0)),
+        ),
+        (
+            ChatModel::GPT3_5Turbo0301,
+            model(4_096, 4_096, (false, false, false), (1.5, 2.0)),
+        ),
+        (
+            ChatModel::GPT4_1106Preview,
+            model(128_000, 4_096, (true, false, false), (10.0, 30.0)),
+        ),
+        (
+            ChatModel::Gpt4VisionPreview,
+            model(128_000, 4_096, (false, true, false), (10.0, 30.0)),
+        ),
+        (
+            ChatModel::GPT4,
+            model(8_192, 8_192, (true, false, false), (30.0, 60.0)),
+        ),
+        (
+            ChatModel::GPT4_32k,
+            model(32_768, 32_768, (true, false, false), (60.0, 120.0)),
+        ),
+        (
+            ChatModel::GPT4_0613,
+            model(8_192, 8_192, (true, false, false), (30.0, 60.0)),
+        ),
+        (
+            ChatModel::GPT4_32k0613,
+            model(32_768, 32_768, (true, false, false), (60.0, 120.0)),
+        ),
+        (
+            ChatModel::GPT4_0314,
+            model(8_192, 8_192, (false, false, false), (30.0, 60.0)),
+        ),
+        (
+            ChatModel::GPT4_32k0314,
+            model(32_768, 32_768, (false, false, false), (60.0, 120.0)),
+        ),
+    ];
+
+    let completion_models = [
+        (
+            CompletionModel::GPT3_5TurboInstruct,
+            model(4_096, 4_096, (false, false, false), (1.5, 2.0)),
+        ),
+        (
+            CompletionModel::Davinci002,
+            model(16_384, 16_384, (false, false, false), (2.0, 2.0)),
+        ),
+        (
+            CompletionModel::Babbage002,
+            model(16_384, 16_384, (false, false, false), (0.4, 0.4)),
+        ),
+    ];
+
+    let embedding_models = [
+        (EmbeddingModel::TextEmbedding3Large, 0.13),
+        (EmbeddingModel::TextEmbedding3Small, 0.02),
+        (EmbeddingModel::TextEmbeddingAda002, 0.1),
+    ];
+
+    let image_models = [
+        (ImageGenerationModel::Dalle3, 0.04),
+        (ImageGenerationModel::Dalle2, 0.02),
+    ];
+
+    let audio_models = [(AudioModel::Whisper1, 0.006)];
+
+    let tts_models = [(TtsModel::Tts1, 15.0), (TtsModel::Tts1Hd, 30.0)];
+
+    let mut registry = ModelRegistry::empty();
+
+    for (model, info) in chat_models {
+        registry.insert(model, info);
+    }
+
+    for (model, info) in completion_models {
+        registry.insert(model, info);
+    }
+
+    for (model, price) in embedding_models {
+        registry.insert(model, ModelInfo::new(8_191).with_prices(price, 0.0));
+    }
+
+    for (model, price) in image_models {
+        registry.insert(model, ModelInfo::new(0).with_image_price(price));
+    }
+
+    for (model, price) in audio_models {
+        registry.insert(model, ModelInfo::new(0).with_audio_price_per_minute(price));
+    }
+
+    for (model, price) in tts_models {
+        registry.insert(
+            model,
+            ModelInfo::new(0).with_character_price_per_million(price),
+        );
+    }
+
+    registry
+}
+
+fn model(
+    context_window: usize,
+    max_output_tokens: usize,
+    (tools, vision, json_schema): (bool, bool, bool),
+    (input_price, output_price): (f64, f64),
+) -> ModelInfo {
+    ModelInfo::new(context_window)
+        .with_max_output_tokens(max_output_tokens)
+        .with_capabilities(tools, vision, json_schema)
+        .with_prices(input_price, output_price)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn resolves_models_correctly() {
+        let registry = ModelRegistry::default();
+
+        let gpt4o_mini = registry.get(ChatModel::GPT4oMini).unwrap();
+        assert_eq!(gpt4o_mini.context_window, 128_000);
+        assert!(gpt4o_mini.supports_json_schema);
+
+        let fine_tuned = registry
+            .get("ft:gpt-4o-mini-2024-07-18:my-org:custom:abc123")
+            .unwrap();
+        assert_eq!(
+            fine_tuned,
+            &registry
+                .get(ChatModel::GPT4oMini2024_07_18)
+                .unwrap()
+                .without_prices()
+        );
+        assert!(fine_tuned.supports_json_schema);
+        assert_eq!(fine_tuned.input_price_per_million, None);
+
+        let snapshot = registry.get("gpt-4.1-nano-2099-01-01").unwrap();
+        assert_eq!(snapshot, registry.get(ChatModel::GPT4_1Nano).unwrap());
+
+        let short_snapshot = registry.get("gpt-4-0999").unwrap();
+        assert_eq!(short_snapshot, registry.get(ChatModel::GPT4).unwrap());
+
+        assert!(registry.get("my-model").is_none());
+        assert!(registry.get("gpt-4.5-preview").is_none());
+        assert!(registry.get("o1-pro").is_none());
+        assert!(registry.get("o3-pro-2025-06-10").is_none());
+        assert!(registry.get("gpt-4o-20990101").is_none());
+    }
+
+    #[test]
+    fn overrides_models_from_json() {
+        let mut registry = ModelRegistry::default();
+        registry
+            .extend_from_json(
+                r#"{
+                  "my-model": { "context_window": 8192, "supports_tools": true },
+                  "ft:gpt-4o-mini-2024-07-18:my-org::abc123": {
+                    "context_window": 64000,
+                    "input_price_per_million": 0.3,
+                    "output_price_per_million": 1.2
+                  }
+                }"#,
+            )
+            .unwrap();
+
+        let my_model = registry.get("my-model").unwrap();
+        assert_eq!(my_model.context_window, 8192);
+        assert!(my_model.supports_tools);
+        assert!(!my_model.supports_vision);
+
+        let fine_tuned = registry
+            .get("ft:gpt-4o-mini-2024-07-18:my-org::abc123")
+            .unwrap();
+        assert_eq!(fine_tuned.context_window, 64000);
+
+        let usage = TokenUsage {
+            prompt_tokens: 1_000_000,
+            completion_tokens: 500_000,
+            total_tokens: 1_500_000,
+        };
+        assert_eq!(fine_tuned.cost(&usage), Some(0.9));
+    }
+}
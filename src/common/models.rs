@@ -1,263 +1,626 @@
-use std::fmt::Display;
+use std::{convert::Infallible, fmt::Display, str::FromStr};
 
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 
-/// Audio model options
-#[derive(Clone, Debug, Default, PartialEq)]
-pub enum AudioModel {
-    #[default]
-    /// Whisper is an automatic speech recognition (ASR) system trained on 680,000 hours of
-    /// multilingual and multitask supervised data collected from the web
-    Whisper1,
+use super::{ModelInfo, ModelRegistry};
+
+/// Defines an enum of the models known to this crate, with an `Other` variant for any other model ID,
+/// so that new models returned by the API don't break deserialization.
+macro_rules! model_enum {
+    (
+        $(#[$meta:meta])*
+        $vis:vis enum $name:ident {
+            $(
+                $(#[$variant_meta:meta])*
+                $variant:ident => $id:literal $(| $alias:literal)*,
+            )*
+        }
+    ) => {
+        $(#[$meta])*
+        #[derive(Clone, Debug, PartialEq, Eq, Hash)]
+        $vis enum $name {
+            $(
+                $(#[$variant_meta])*
+                $variant,
+            )*
+
+            /// A model not known to this crate, such as a new snapshot or a fine-tuned model
+            Other(String),
+        }
+
+        impl $name {
+            /// All models known to this crate
+            pub const KNOWN: &'static [Self] = &[$(Self::$variant),*];
+
+            /// The ID of the model, as used by the API
+            pub fn as_str(&self) -> &str {
+                match self {
+                    $(Self::$variant => $id,)*
+                    Self::Other(id) => id,
+                }
+            }
+        }
+
+        impl std::fmt::Display for $name {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                f.write_str(self.as_str())
+            }
+        }
+
+        impl From<&str> for $name {
+            fn from(id: &str) -> Self {
+                match id {
+                    $($id $(| $alias)* => Self::$variant,)*
+                    id => Self::Other(id.to_string()),
+                }
+            }
+        }
+
+        impl From<String> for $name {
+            fn from(id: String) -> Self {
+                match Self::from(id.as_str()) {
+                    Self::Other(_) => Self::Other(id),
+                    model => model,
+                }
+            }
+        }
+
+        impl From<$crate::common::ModelId> for $name {
+            fn from(id: $crate::common::ModelId) -> Self {
+                Self::from(String::from(id))
+            }
+        }
+
+        impl From<$name> for $crate::common::ModelId {
+            fn from(model: $name) -> Self {
+                Self::new(model.as_str())
+            }
+        }
+
+        impl std::str::FromStr for $name {
+            type Err = std::convert::Infallible;
+
+            fn from_str(id: &str) -> Result<Self, Self::Err> {
+                Ok(Self::from(id))
+            }
+        }
+
+        impl serde::Serialize for $name {
+            fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                serializer.serialize_str(self.as_str())
+            }
+        }
+
+        impl<'de> serde::Deserialize<'de> for $name {
+            fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                <String as serde::Deserialize>::deserialize(deserializer).map(Self::from)
+            }
+        }
+    };
+}
+
+#[cfg(feature = "fine_tunning")]
+pub(crate) use model_enum;
+
+/// The ID of any model, known to this crate or not, e.g. a fine-tuned model.
+///
+/// All model enums convert into it, so methods taking `impl Into<ModelId>` accept
+/// them as well as plain strings.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct ModelId(String);
+
+impl ModelId {
+    /// Creates a model ID.
+    pub fn new<S: Into<String>>(id: S) -> Self {
+        Self(id.into())
+    }
+
+    /// The ID of the model, as used by the API
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
 }
 
-impl Display for AudioModel {
+impl Display for ModelId {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match self {
-            AudioModel::Whisper1 => write!(f, "whisper-1"),
-        }
+        f.write_str(&self.0)
     }
 }
 
-/// Chat model options
-#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
-pub enum ChatModel {
-    /// <b>Updated GPT 3.5 Turbo</b>
-    ///
-    // The latest GPT-3.5 Turbo model with higher accuracy at responding in requested formats and a fix for a bug which caused a text encoding issue for non-English language function calls. Returns a maximum of 4,096 output tokens.
-    /// See [new embedding models and api updates](https://openai.com/blog/new-embedding-models-and-api-updates)
-    ///
-    /// Context window - 16,385 tokens
-    /// Training data - Up to Sep 2021
-    #[serde(rename = "gpt-3.5-turbo-0125")]
-    #[default]
-    GPT3_5Turbo0125,
-
-    /// Currently points to gpt-3.5-turbo-0613. Will point to gpt-3.5-turbo-1106 starting Dec 11, 2023.
-    /// See [continuous model upgrades](https://platform.openai.com/docs/models/continuous-model-upgrades)
-    ///
-    /// Context window - 4,096 tokens
-    /// Training data - Up to Sep 2021
-    #[serde(rename = "gpt-3.5-turbo")]
-    GPT3_5Turbo,
+impl From<&str> for ModelId {
+    fn from(id: &str) -> Self {
+        Self::new(id)
+    }
+}
 
-    /// Currently points to gpt-3.5-turbo-0613. Will point to gpt-3.5-turbo-1106 starting Dec 11, 2023.
-    /// See [continuous model upgrades](https://platform.openai.com/docs/models/continuous-model-upgrades)
-    ///
-    /// Context window - 16,385 tokens
-    /// Training data - Up to Sep 2021
-    #[serde(rename = "gpt-3.5-turbo-16k")]
-    GPT3_5Turbo16k,
+impl From<String> for ModelId {
+    fn from(id: String) -> Self {
+        Self(id)
+    }
+}
 
-    /// Similar capabilities as text-davinci-003 but compatible with legacy Completions endpoint and not Chat Completions.
-    ///
-    /// Context window - 4,096 tokens
-    /// Training data - Up to Sep 2021
-    #[serde(rename = "gpt-3.5-turbo-instruct")]
-    GPT3_5TurboInstinct,
+impl From<&String> for ModelId {
+    fn from(id: &String) -> Self {
+        Self::new(id.as_str())
+    }
+}
 
-    /// Snapshot of gpt-3.5-turbo from June 13th 2023. Will be
-    /// [deprecated](https://platform.openai.com/docs/deprecations) on June 13, 2024.
-    ///
-    /// Context window - 4,096 tokens
-    /// Training data - Up to Sep 2021
-    #[serde(rename = "gpt-3.5-turbo-0613")]
-    GPT3_5Turbo0613,
+impl From<ModelId> for String {
+    fn from(id: ModelId) -> Self {
+        id.0
+    }
+}
 
-    /// Snapshot of gpt-3.5-16k-turbo from June 13th 2023. Will be
-    /// [deprecated](https://platform.openai.com/docs/deprecations) on June 13, 2024.
-    ///
-    /// Context window - 16,385 tokens
-    /// Training data - Up to Sep 2021
-    #[serde(rename = "gpt-3.5-turbo-16k-0613")]
-    GPT3_5Turbo16k0613,
+impl FromStr for ModelId {
+    type Err = Infallible;
 
-    /// Snapshot of gpt-3.5-turbo from March 1st 2023. Will be
-    /// [deprecated](https://platform.openai.com/docs/deprecations) on June 13, 2024.
-    ///
-    /// Context window - 4,096 tokens
-    /// Training data - Up to Sep 2021
-    #[serde(rename = "gpt-3.5-turbo-0301")]
-    GPT3_5Turbo0301,
+    fn from_str(id: &str) -> Result<Self, Self::Err> {
+        Ok(Self::new(id))
+    }
+}
 
-    /// <b>GPT-4 Turbo</b>
-    ///
-    /// The latest GPT-4 model with improved instruction following, JSON mode, reproducible outputs, parallel function calling, and more.
-    /// Returns a maximum of 4,096 output tokens. This preview model is not yet suited for production traffic.
-    /// [Learn more](https://openai.com/blog/new-models-and-developer-products-announced-at-devday)
-    ///
-    /// Context window - 128,000 tokens
-    /// Training data - Up to Apr 2023
-    #[serde(rename = "gpt-4-1106-preview")]
-    GPT4_1106Preview,
+impl Serialize for ModelId {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.0)
+    }
+}
 
-    /// <b>GPT-4 Turbo with vision</b>
-    ///
-    /// Ability to understand images, in addition to all other GPT-4 Turbo capabilities.
-    /// Returns a maximum of 4,096 output tokens. This is a preview model version and not suited yet for production traffic.
-    /// [Learn more](https://openai.com/blog/new-models-and-developer-products-announced-at-devday)
-    ///
-    /// Context window - 128,000 tokens
-    /// Training data - Up to Apr 2023
-    #[serde(rename = "gpt-4-vision-preview")]
-    Gpt4VisionPreview,
+impl<'de> Deserialize<'de> for ModelId {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        String::deserialize(deserializer).map(Self)
+    }
+}
 
-    /// Currently points to gpt-4-0613
-    /// See [continuous model upgrades](https://platform.openai.com/docs/models/continuous-model-upgrades)  
-    ///
-    /// Context window - 8,192 tokens
-    /// Training data - Up to Sep 2021
-    #[serde(rename = "gpt-4")]
-    GPT4,
+model_enum! {
+    /// Audio model options
+    #[derive(Default)]
+    pub enum AudioModel {
+        /// Whisper is an automatic speech recognition (ASR) system trained on 680,000 hours of
+        /// multilingual and multitask supervised data collected from the web
+        #[default]
+        Whisper1 => "whisper-1",
 
-    /// Currently points to gpt-4-32k-0613.
-    /// See (continuous model upgrades)[https://platform.openai.com/docs/models/continuous-model-upgrades]
-    ///
-    /// Context window - 32,768 tokens
-    /// Training data - Up to Sep 2021
-    #[serde(rename = "gpt-4-32k")]
-    GPT4_32k,
+        /// Speech to text model powered by GPT-4o, with better accuracy than Whisper.
+        /// Supports transcriptions only.
+        Gpt4oTranscribe => "gpt-4o-transcribe",
 
-    /// Snapshot of gpt-4 from June 13th 2023 with improved function calling support.
-    ///
-    /// Context window - 8,192 tokens
-    /// Training data - Up to Sep 2021
-    #[serde(rename = "gpt-4-0613")]
-    GPT4_0613,
+        /// Speech to text model powered by GPT-4o mini. Supports transcriptions only.
+        Gpt4oMiniTranscribe => "gpt-4o-mini-transcribe",
+    }
+}
 
-    /// Snapshot of gpt-4-32k from June 13th 2023 with improved function calling support.
-    ///
-    /// Context window - 32,768 tokens
-    /// Training data - Up to Sep 2021
-    #[serde(rename = "gpt-4-32k-0613")]
-    GPT4_32k0613,
+model_enum! {
+    /// Chat model options
+    #[derive(Default)]
+    pub enum ChatModel {
+        /// <b>GPT-4.1</b>
+        ///
+        /// Flagship model for complex tasks, with strong instruction following and long context.
+        ///
+        /// Context window - 1,047,576 tokens
+        GPT4_1 => "gpt-4.1",
 
-    /// Snapshot of gpt-4 from March 14th 2023 with function calling support. This model version will be
-    /// [deprecated](https://platform.openai.com/docs/deprecations) on June 13, 2024.
-    ///
-    /// Context window - 4,096 tokens
-    /// Training data - Up to Sep 2021
-    #[serde(rename = "gpt-4-0314")]
-    GPT4_0314,
+        /// Balance of intelligence, speed and cost of the GPT-4.1 family.
+        ///
+        /// Context window - 1,047,576 tokens
+        GPT4_1Mini => "gpt-4.1-mini",
+
+        /// Fastest and cheapest model of the GPT-4.1 family.
+        ///
+        /// Context window - 1,047,576 tokens
+        GPT4_1Nano => "gpt-4.1-nano",
+
+        /// <b>GPT-4o</b>
+        ///
+        /// Multimodal flagship model, accepting text and image inputs. Currently points to gpt-4o-2024-08-06.
+        ///
+        /// Context window - 128,000 tokens
+        GPT4o => "gpt-4o",
+
+        /// Snapshot of gpt-4o from May 13th 2024.
+        ///
+        /// Context window - 128,000 tokens
+        GPT4o2024_05_13 => "gpt-4o-2024-05-13",
+
+        /// Snapshot of gpt-4o from August 6th 2024, the first snapshot supporting Structured Outputs.
+        ///
+        /// Context window - 128,000 tokens
+        GPT4o2024_08_06 => "gpt-4o-2024-08-06",
+
+        /// Snapshot of gpt-4o from November 20th 2024.
+        ///
+        /// Context window - 128,000 tokens
+        GPT4o2024_11_20 => "gpt-4o-2024-11-20",
 
-    /// Snapshot of gpt-4-32k from March 14th 2023 with function calling support. This model version will be
-    /// [deprecated](https://platform.openai.com/docs/deprecations) on June 13, 2024.
+        /// Points to the GPT-4o model used in ChatGPT, updated frequently.
+        ///
+        /// Context window - 128,000 tokens
+        ChatGPT4oLatest => "chatgpt-4o-latest",
+
+        /// <b>GPT-4o mini</b>
+        ///
+        /// Small, fast and affordable model for focused tasks. Currently points to gpt-4o-mini-2024-07-18.
+        ///
+        /// Context window - 128,000 tokens
+        GPT4oMini => "gpt-4o-mini",
+
+        /// Snapshot of gpt-4o-mini from July 18th 2024.
+        ///
+        /// Context window - 128,000 tokens
+        GPT4oMini2024_07_18 => "gpt-4o-mini-2024-07-18",
+
+        /// <b>o1</b>
+        ///
+        /// Reasoning model trained to think before it answers.
+        ///
+        /// Context window - 200,000 tokens
+        O1 => "o1",
+
+        /// Small and fast reasoning model, superseded by o3-mini.
+        ///
+        /// Context window - 128,000 tokens
+        O1Mini => "o1-mini",
+
+        /// <b>o3</b>
+        ///
+        /// Reasoning model for math, science, coding and visual reasoning tasks.
+        ///
+        /// Context window - 200,000 tokens
+        O3 => "o3",
+
+        /// Small reasoning model, with the capabilities of o1-mini at lower cost.
+        ///
+        /// Context window - 200,000 tokens
+        O3Mini => "o3-mini",
+
+        /// Small reasoning model, optimized for fast and cost efficient reasoning.
+        ///
+        /// Context window - 200,000 tokens
+        O4Mini => "o4-mini",
+
+        /// <b>GPT-4 Turbo</b>
+        ///
+        /// GPT-4 Turbo with vision, JSON mode and function calling. Currently points to gpt-4-turbo-2024-04-09.
+        ///
+        /// Context window - 128,000 tokens
+        /// Training data - Up to Dec 2023
+        GPT4Turbo => "gpt-4-turbo",
+
+        /// Snapshot of gpt-4-turbo from April 9th 2024.
+        ///
+        /// Context window - 128,000 tokens
+        /// Training data - Up to Dec 2023
+        GPT4Turbo2024_04_09 => "gpt-4-turbo-2024-04-09",
+
+        /// Currently points to gpt-4-0125-preview.
+        ///
+        /// Context window - 128,000 tokens
+        /// Training data - Up to Dec 2023
+        GPT4TurboPreview => "gpt-4-turbo-preview",
+
+        /// GPT-4 Turbo preview model, intended to reduce cases of "laziness" where the model doesn't complete a task.
+        ///
+        /// Context window - 128,000 tokens
+        /// Training data - Up to Dec 2023
+        GPT4_0125Preview => "gpt-4-0125-preview",
+
+        /// <b>Updated GPT 3.5 Turbo</b>
+        ///
+        // The latest GPT-3.5 Turbo model with higher accuracy at responding in requested formats and a fix for a bug which caused a text encoding issue for non-English language function calls. Returns a maximum of 4,096 output tokens.
+        /// See [new embedding models and api updates](https://openai.com/blog/new-embedding-models-and-api-updates)
+        ///
+        /// Context window - 16,385 tokens
+        /// Training data - Up to Sep 2021
+        #[default]
+        GPT3_5Turbo0125 => "gpt-3.5-turbo-0125",
+
+        /// Currently points to gpt-3.5-turbo-0613. Will point to gpt-3.5-turbo-1106 starting Dec 11, 2023.
+        /// See [continuous model upgrades](https://platform.openai.com/docs/models/continuous-model-upgrades)
+        ///
+        /// Context window - 4,096 tokens
+        /// Training data - Up to Sep 2021
+        GPT3_5Turbo => "gpt-3.5-turbo",
+
+        /// Snapshot of gpt-3.5-turbo from November 6th 2023, with improved instruction following, JSON mode and parallel function calling.
+        ///
+        /// Context window - 16,385 tokens
+        /// Training data - Up to Sep 2021
+        GPT3_5Turbo1106 => "gpt-3.5-turbo-1106",
+
+        /// Currently points to gpt-3.5-turbo-0613. Will point to gpt-3.5-turbo-1106 starting Dec 11, 2023.
+        /// See [continuous model upgrades](https://platform.openai.com/docs/models/continuous-model-upgrades)
+        ///
+        /// Context window - 16,385 tokens
+        /// Training data - Up to Sep 2021
+        GPT3_5Turbo16k => "gpt-3.5-turbo-16k",
+
+        /// Similar capabilities as text-davinci-003 but compatible with legacy Completions endpoint and not Chat Completions.
+        ///
+        /// Context window - 4,096 tokens
+        /// Training data - Up to Sep 2021
+        GPT3_5TurboInstinct => "gpt-3.5-turbo-instruct",
+
+        /// Snapshot of gpt-3.5-turbo from June 13th 2023. Will be
+        /// [deprecated](https://platform.openai.com/docs/deprecations) on June 13, 2024.
+        ///
+        /// Context window - 4,096 tokens
+        /// Training data - Up to Sep 2021
+        GPT3_5Turbo0613 => "gpt-3.5-turbo-0613",
+
+        /// Snapshot of gpt-3.5-16k-turbo from June 13th 2023. Will be
+        /// [deprecated](https://platform.openai.com/docs/deprecations) on June 13, 2024.
+        ///
+        /// Context window - 16,385 tokens
+        /// Training data - Up to Sep 2021
+        GPT3_5Turbo16k0613 => "gpt-3.5-turbo-16k-0613",
+
+        /// Snapshot of gpt-3.5-turbo from March 1st 2023. Will be
+        /// [deprecated](https://platform.openai.com/docs/deprecations) on June 13, 2024.
+        ///
+        /// Context window - 4,096 tokens
+        /// Training data - Up to Sep 2021
+        GPT3_5Turbo0301 => "gpt-3.5-turbo-0301",
+
+        /// <b>GPT-4 Turbo</b>
+        ///
+        /// The latest GPT-4 model with improved instruction following, JSON mode, reproducible outputs, parallel function calling, and more.
+        /// Returns a maximum of 4,096 output tokens. This preview model is not yet suited for production traffic.
+        /// [Learn more](https://openai.com/blog/new-models-and-developer-products-announced-at-devday)
+        ///
+        /// Context window - 128,000 tokens
+        /// Training data - Up to Apr 2023
+        GPT4_1106Preview => "gpt-4-1106-preview",
+
+        /// <b>GPT-4 Turbo with vision</b>
+        ///
+        /// Ability to understand images, in addition to all other GPT-4 Turbo capabilities.
+        /// Returns a maximum of 4,096 output tokens. This is a preview model version and not suited yet for production traffic.
+        /// [Learn more](https://openai.com/blog/new-models-and-developer-products-announced-at-devday)
+        ///
+        /// Context window - 128,000 tokens
+        /// Training data - Up to Apr 2023
+        Gpt4VisionPreview => "gpt-4-vision-preview",
+
+        /// Currently points to gpt-4-0613
+        /// See [continuous model upgrades](https://platform.openai.com/docs/models/continuous-model-upgrades)
+        ///
+        /// Context window - 8,192 tokens
+        /// Training data - Up to Sep 2021
+        GPT4 => "gpt-4",
+
+        /// Currently points to gpt-4-32k-0613.
+        /// See (continuous model upgrades)[https://platform.openai.com/docs/models/continuous-model-upgrades]
+        ///
+        /// Context window - 32,768 tokens
+        /// Training data - Up to Sep 2021
+        GPT4_32k => "gpt-4-32k",
+
+        /// Snapshot of gpt-4 from June 13th 2023 with improved function calling support.
+        ///
+        /// Context window - 8,192 tokens
+        /// Training data - Up to Sep 2021
+        GPT4_0613 => "gpt-4-0613",
+
+        /// Snapshot of gpt-4-32k from June 13th 2023 with improved function calling support.
+        ///
+        /// Context window - 32,768 tokens
+        /// Training data - Up to Sep 2021
+        GPT4_32k0613 => "gpt-4-32k-0613",
+
+        /// Snapshot of gpt-4 from March 14th 2023 with function calling support. This model version will be
+        /// [deprecated](https://platform.openai.com/docs/deprecations) on June 13, 2024.
+        ///
+        /// Context window - 8,192 tokens
+        /// Training data - Up to Sep 2021
+        GPT4_0314 => "gpt-4-0314",
+
+        /// Snapshot of gpt-4-32k from March 14th 2023 with function calling support. This model version will be
+        /// [deprecated](https://platform.openai.com/docs/deprecations) on June 13, 2024.
+        ///
+        /// Context window - 32,768 tokens
+        /// Training data - Up to Sep 2021
+        GPT4_32k0314 => "gpt-4-32k-0314",
+    }
+}
+
+impl ChatModel {
+    /// The capabilities and prices of the model, from the [built-in registry](ModelRegistry::builtin).
+    /// Use [`ChatModel::info_in`] with [`OpenAIClient::model_registry`](crate::OpenAIClient::model_registry)
+    /// to take the models registered on the client into account.
+    pub fn info(&self) -> Option<&'static ModelInfo> {
+        self.info_in(ModelRegistry::builtin())
+    }
+
+    /// The capabilities and prices of the model, from the given registry.
+    pub fn info_in<'a>(&self, model_registry: &'a ModelRegistry) -> Option<&'a ModelInfo> {
+        model_registry.get(self.as_str())
+    }
+
+    /// The maximum number of tokens of the prompt and the completion together.
     ///
-    /// Context window - 4,096 tokens
-    /// Training data - Up to Sep 2021
-    #[serde(rename = "gpt-4-32k-0314")]
-    GPT4_32k0314,
+    /// Other models resolve as described in [`ModelRegistry`], e.g. a new `gpt-4o` snapshot has the
+    /// context window of `gpt-4o`. `None` if the model is unknown.
+    pub fn context_window(&self) -> Option<usize> {
+        self.info().map(|info| info.context_window)
+    }
 }
 
-impl Display for ChatModel {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match self {
-            ChatModel::GPT3_5Turbo0125 => write!(f, "gpt-3.5-turbo-0125"),
-            ChatModel::GPT3_5Turbo => write!(f, "gpt-3.5-turbo"),
-            ChatModel::GPT3_5Turbo16k => write!(f, "gpt-3.5-turbo-16k"),
-            ChatModel::GPT3_5TurboInstinct => write!(f, "gpt-3.5-turbo-instruct"),
-            ChatModel::GPT3_5Turbo0613 => write!(f, "gpt-3.5-turbo-0613"),
-            ChatModel::GPT3_5Turbo16k0613 => write!(f, "gpt-3.5-turbo-16k-0613"),
-            ChatModel::GPT3_5Turbo0301 => write!(f, "gpt-3.5-turbo-0301"),
-            ChatModel::GPT4_1106Preview => write!(f, "gpt-4-1106-preview"),
-            ChatModel::Gpt4VisionPreview => write!(f, "gpt-4-vision-preview"),
-            ChatModel::GPT4 => write!(f, "gpt-4"),
-            ChatModel::GPT4_32k => write!(f, "gpt-4-32k"),
-            ChatModel::GPT4_0613 => write!(f, "gpt-4-0613"),
-            ChatModel::GPT4_32k0613 => write!(f, "gpt-4-32k-0613"),
-            ChatModel::GPT4_0314 => write!(f, "gpt-4-0314"),
-            ChatModel::GPT4_32k0314 => write!(f, "gpt-4-32k-0314"),
-        }
+model_enum! {
+    /// Models of the legacy [Completions](https://platform.openai.com/docs/api-reference/completions) endpoint.
+    /// Fine-tuned models, such as `ft:davinci-002:my-org::abc123`, are [`CompletionModel::Other`].
+    #[derive(Default)]
+    pub enum CompletionModel {
+        /// Similar capabilities as text-davinci-003 but compatible with legacy Completions endpoint and not Chat Completions.
+        ///
+        /// Context window - 4,096 tokens
+        /// Training data - Up to Sep 2021
+        #[default]
+        GPT3_5TurboInstruct => "gpt-3.5-turbo-instruct",
+
+        /// Replacement for the GPT-3 curie and davinci base models, which can be fine-tuned.
+        ///
+        /// Context window - 16,384 tokens
+        /// Training data - Up to Sep 2021
+        Davinci002 => "davinci-002",
+
+        /// Replacement for the GPT-3 ada and babbage base models, which can be fine-tuned.
+        ///
+        /// Context window - 16,384 tokens
+        /// Training data - Up to Sep 2021
+        Babbage002 => "babbage-002",
     }
 }
 
-/// Embedding model options
-#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
-pub enum EmbeddingModel {
-    #[serde(rename = "text-embedding-ada-002")]
-    #[serde(alias = "text-embedding-ada-002-v2")]
-    #[default]
-    /// Designed to replace the previous 16 first-generation embedding models at a fraction of the cost.
-    ///
-    /// Context window - 8,191 tokens
-    /// Trainning data - Up to Jun 2021
-    TextEmbeddingAda002,
+model_enum! {
+    /// Embedding model options
+    #[derive(Default)]
+    pub enum EmbeddingModel {
+        /// Most capable embedding model for both english and non-english tasks.
+        ///
+        /// Output dimension - 3,072
+        TextEmbedding3Large => "text-embedding-3-large",
 
-    /// Can do language tasks with better quality and consistency than the curie, babbage, or ada models. Will be
-    /// [deprecated](https://platform.openai.com/docs/deprecations) on Jan 4 2024.
-    ///
-    /// Context window - 4,096 tokens
-    /// Trainning data - Up to Jun 2021
-    #[serde(rename = "text-davinci-003")]
-    TextDavinci003,
+        /// Increased performance over the 2nd generation ada embedding model.
+        ///
+        /// Output dimension - 1,536
+        TextEmbedding3Small => "text-embedding-3-small",
 
-    /// Similar capabilities to text-davinci-003 but trained with supervised fine-tuning instead of reinforcement learning. Will be
-    /// [deprecated](https://platform.openai.com/docs/deprecations) on Jan 4 2024.
-    ///
-    /// Context window - 4,096 tokens
-    /// Trainning data - Up to Jun 2021
-    #[serde(rename = "text-davinci-002")]
-    TextDavinci002,
+        /// Designed to replace the previous 16 first-generation embedding models at a fraction of the cost.
+        ///
+        /// Context window - 8,191 tokens
+        /// Trainning data - Up to Jun 2021
+        #[default]
+        TextEmbeddingAda002 => "text-embedding-ada-002" | "text-embedding-ada-002-v2",
+
+        /// Can do language tasks with better quality and consistency than the curie, babbage, or ada models. Will be
+        /// [deprecated](https://platform.openai.com/docs/deprecations) on Jan 4 2024.
+        ///
+        /// Context window - 4,096 tokens
+        /// Trainning data - Up to Jun 2021
+        TextDavinci003 => "text-davinci-003",
+
+        /// Similar capabilities to text-davinci-003 but trained with supervised fine-tuning instead of reinforcement learning. Will be
+        /// [deprecated](https://platform.openai.com/docs/deprecations) on Jan 4 2024.
+        ///
+        /// Context window - 4,096 tokens
+        /// Trainning data - Up to Jun 2021
+        TextDavinci002 => "text-davinci-002",
 
-    /// Optimized for code-completion tasks. Will be
-    /// [deprecated](https://platform.openai.com/docs/deprecations) on Jan 4 2024.
+        /// Optimized for code-completion tasks. Will be
+        /// [deprecated](https://platform.openai.com/docs/deprecations) on Jan 4 2024.
+        ///
+        /// Context window - 8,001 tokens
+        /// Trainning data - Up to Jun 2021
+        CodeDavinci002 => "code-davinci-002",
+    }
+}
+
+model_enum! {
+    /// Image generation model options
     ///
-    /// Context window - 8,001 tokens
-    /// Trainning data - Up to Jun 2021
-    #[serde(rename = "code-davinci-002")]
-    CodeDavinci002,
+    /// DALL·E is a AI system that can create realistic images and art from a description in natural language.
+    /// DALL·E 3 currently supports the ability, given a prompt, to create a new image with a specific size.
+    /// DALL·E 2 also support the ability to edit an existing image, or create variations of a user provided image.
+    ///
+    /// [DALL·E 3](https://openai.com/dall-e-3) is available through our
+    /// [Images API](https://platform.openai.com/docs/guides/images/introduction) along with
+    /// [DALL·E 2](https://openai.com/blog/dall-e-api-now-available-in-public-beta). You can try DALL·E 3 through
+    /// [ChatGPT Plus](https://chat.openai.com/).
+    #[derive(Default)]
+    pub enum ImageGenerationModel {
+        /// Natively multimodal image generation model, supporting generations and edits.
+        GptImage1 => "gpt-image-1",
+
+        /// The latest DALL·E model released in Nov 2023. [Learn more](https://openai.com/blog/new-models-and-developer-products-announced-at-devday).
+        Dalle3 => "dall-e-3",
+
+        /// The previous DALL·E model released in Nov 2022. The 2nd iteration of DALL·E with more realistic, accurate, and 4x greater resolution images than the original model.
+        #[default]
+        Dalle2 => "dall-e-2",
+    }
 }
 
-/// Image generation model options
-///
-/// DALL·E is a AI system that can create realistic images and art from a description in natural language.
-/// DALL·E 3 currently supports the ability, given a prompt, to create a new image with a specific size.
-/// DALL·E 2 also support the ability to edit an existing image, or create variations of a user provided image.
-///
-/// [DALL·E 3](https://openai.com/dall-e-3) is available through our
-/// [Images API](https://platform.openai.com/docs/guides/images/introduction) along with
-/// [DALL·E 2](https://openai.com/blog/dall-e-api-now-available-in-public-beta). You can try DALL·E 3 through
-/// [ChatGPT Plus](https://chat.openai.com/).
-#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
-pub enum ImageGenerationModel {
-    /// The latest DALL·E model released in Nov 2023. [Learn more](https://openai.com/blog/new-models-and-developer-products-announced-at-devday).
-    #[serde(rename = "dall-e-3")]
-    Dalle3,
-
-    /// The previous DALL·E model released in Nov 2022. The 2nd iteration of DALL·E with more realistic, accurate, and 4x greater resolution images than the original model.
-    #[serde(rename = "dall-e-2")]
-    #[default]
-    Dalle2,
+model_enum! {
+    /// Moderation model options
+    #[derive(Default)]
+    pub enum ModerationModel {
+        /// Multimodal moderation model, classifying text and images into more categories than the text models.
+        /// Currently points to omni-moderation-2024-09-26.
+        OmniModerationLatest => "omni-moderation-latest",
+
+        /// Snapshot of omni-moderation from September 26th 2024.
+        OmniModeration2024_09_26 => "omni-moderation-2024-09-26",
+
+        /// Most capable moderation model. Accuracy will be slightly higher than the stable model.
+        ///
+        /// Context window - 32,768 tokens
+        TextModerationLatest => "text-moderation-latest",
+
+        /// Almost as capable as the latest model, but slightly older.
+        ///
+        /// Context window - 32,768 tokens
+        #[default]
+        TextModerationStable => "text-moderation-stable",
+    }
 }
 
-/// Moderation model options
-#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
-pub enum ModerationModel {
-    /// Most capable moderation model. Accuracy will be slightly higher than the stable model.
-    ///
-    /// Context window - 32,768 tokens
-    #[serde(rename = "text-moderation-stable")]
-    #[default]
-    TextModerationLatest,
+model_enum! {
+    /// TTS is an AI model that converts text to natural sounding spoken text.
+    /// We offer two different model variates, `tts-1` is optimized for real time text to speech use cases
+    /// and `tts-1-hd` is optimized for quality. These models can be used with the
+    /// [Speech endpoint in the Audio API](https://platform.openai.com/docs/guides/text-to-speech).
+    pub enum TtsModel {
+        /// The latest text to speech model, optimized for speed.
+        Tts1 => "tts-1",
 
-    /// Almost as capable as the latest model, but slightly older.
-    ///
-    /// Context window - 32,768 tokens
-    #[serde(rename = "text-moderation-latest")]
-    TextModerationStable,
+        /// The latest text to speech model, optimized for quality.
+        Tts1Hd => "tts-1-hd",
+
+        /// Text to speech model powered by GPT-4o mini, which can be instructed how to speak.
+        Gpt4oMiniTts => "gpt-4o-mini-tts",
+    }
 }
 
-/// TTS is an AI model that converts text to natural sounding spoken text.
-/// We offer two different model variates, `tts-1` is optimized for real time text to speech use cases
-/// and `tts-1-hd` is optimized for quality. These models can be used with the
-/// [Speech endpoint in the Audio API](https://platform.openai.com/docs/guides/text-to-speech).
-#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
-pub enum TtsModel {
-    /// The latest text to speech model, optimized for speed.
-    #[serde(rename = "tts-1")]
-    Tts1,
-
-    /// The latest text to speech model, optimized for quality.
-    #[serde(rename = "tts-1-hd")]
-    Tts1Hd,
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn deserializes_unknown_models_correctly() {
+        let known: ChatModel = serde_json::from_str("\"gpt-4o-2024-08-06\"").unwrap();
+        let unknown: ChatModel = serde_json::from_str("\"gpt-4o-2099-01-01\"").unwrap();
+        let alias: EmbeddingModel = serde_json::from_str("\"text-embedding-ada-002-v2\"").unwrap();
+
+        assert_eq!(known, ChatModel::GPT4o2024_08_06);
+        assert_eq!(unknown, ChatModel::Other("gpt-4o-2099-01-01".to_string()));
+        assert_eq!(alias, EmbeddingModel::TextEmbeddingAda002);
+
+        assert_eq!(
+            serde_json::to_string(&unknown).unwrap(),
+            "\"gpt-4o-2099-01-01\""
+        );
+        assert_eq!(ChatModel::from("gpt-4o"), ChatModel::GPT4o);
+    }
+
+    #[test]
+    fn resolves_context_window_of_snapshots() {
+        assert_eq!(ChatModel::GPT4oMini.context_window(), Some(128_000));
+        assert_eq!(
+            ChatModel::from("gpt-4.1-mini-2099-01-01").context_window(),
+            Some(1_047_576)
+        );
+        assert_eq!(ChatModel::from("my-model").context_window(), None);
+    }
+
+    #[test]
+    fn converts_models_into_ids() {
+        let id: ModelId = ChatModel::GPT4o.into();
+        assert_eq!(id.as_str(), "gpt-4o");
+
+        let id: ModelId = "ft:gpt-4o-mini-2024-07-18:my-org::abc123".into();
+        assert_eq!(
+            ChatModel::from(id.clone()),
+            ChatModel::Other(id.to_string())
+        );
+    }
 }
@@ -0,0 +1,107 @@
+use reqwest::{
+    multipart::{Form, Part},
+    Body,
+};
+use tokio_util::codec::BytesCodec;
+
+use super::{OpenAIError, OpenAIFile};
+
+const FILE_MIME_TYPE: &str = "application/octet-stream";
+
+/// A multipart form that, unlike [`reqwest::multipart::Form`], can be turned into a request body
+/// more than once, so requests with file uploads can be retried.
+#[derive(Debug, Default)]
+pub struct MultipartForm {
+    parts: Vec<(String, MultipartPart)>,
+}
+
+/// A single field of a [`MultipartForm`].
+#[derive(Debug)]
+pub enum MultipartPart {
+    /// A text field
+    Text(String),
+
+    /// A file field
+    File(OpenAIFile),
+}
+
+impl MultipartForm {
+    /// Creates an empty form.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a text field to the form.
+    pub fn text<N, V>(mut self, name: N, value: V) -> Self
+    where
+        N: Into<String>,
+        V: Into<String>,
+    {
+        self.parts
+            .push((name.into(), MultipartPart::Text(value.into())));
+        self
+    }
+
+    /// Adds a file field to the form.
+    pub fn file<N>(mut self, name: N, file: OpenAIFile) -> Self
+    where
+        N: Into<String>,
+    {
+        self.parts.push((name.into(), MultipartPart::File(file)));
+        self
+    }
+
+    /// Returns the fields of the form.
+    pub fn parts(&self) -> &[(String, MultipartPart)] {
+        &self.parts
+    }
+
+    /// Returns the value of a text field.
+    pub fn text_field(&self, name: &str) -> Option<&str> {
+        self.parts.iter().find_map(|(part_name, part)| match part {
+            MultipartPart::Text(value) if part_name == name => Some(value.as_str()),
+            _ => None,
+        })
+    }
+
+    /// Creates a copy of the form. Files are cloned with [`OpenAIFile::try_clone`].
+    pub async fn try_clone(&self) -> Result<Self, OpenAIError> {
+        let mut parts = Vec::with_capacity(self.parts.len());
+
+        for (name, part) in &self.parts {
+            let part = match part {
+                MultipartPart::Text(value) => MultipartPart::Text(value.clone()),
+                MultipartPart::File(file) => MultipartPart::File(file.try_clone().await?),
+            };
+
+            parts.push((name.clone(), part));
+        }
+
+        Ok(Self { parts })
+    }
+
+    /// Builds a [`reqwest::multipart::Form`] out of the fields. Files are streamed from
+    /// cloned handles rewound to the start, so the form can be built again for a retry.
+    pub(crate) async fn to_form(&self) -> Result<Form, OpenAIError> {
+        let mut form = Form::new();
+
+        for (name, part) in &self.parts {
+            form = match part {
+                MultipartPart::Text(value) => form.text(name.clone(), value.clone()),
+                MultipartPart::File(file) => {
+                    let file_name = file.name.to_owned();
+                    let file_body =
+                        Body::wrap_stream(file.try_clone().await?.into_stream(BytesCodec::new()));
+
+                    let file_part = Part::stream(file_body)
+                        .file_name(file_name)
+                        .mime_str(FILE_MIME_TYPE)?;
+
+                    form.part(name.clone(), file_part)
+                }
+            };
+        }
+
+        Ok(form)
+    }
+}
@@ -1,6 +1,52 @@
-/// Query parameters for OpenAI API requets.
-#[derive(Debug, Default, Clone)]
-pub struct OpenAIQueryParameters {
+use std::fmt::{self, Display};
+
+use serde::{Deserialize, Serialize};
+
+/// Query parameters of an OpenAI API request.
+pub trait QueryParameters {
+    /// Converts the parameters to the query of the request.
+    fn to_query(&self) -> Vec<(String, String)>;
+}
+
+/// Query parameters of a list endpoint paginated with an `after` cursor.
+pub(crate) trait PaginatedParams: Clone {
+    /// Returns the `after` cursor of the parameters.
+    fn after_mut(&mut self) -> &mut Option<String>;
+
+    /// Returns a copy of the parameters fetching the page after the cursor,
+    /// or the page set by the parameters themselves when there's no cursor yet.
+    fn with_after(&self, after: Option<String>) -> Self {
+        let mut parameters = self.clone();
+        let cursor = parameters.after_mut();
+        *cursor = after.or(cursor.take());
+
+        parameters
+    }
+}
+
+/// Sort order by the `created_at` timestamp of the objects.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Order {
+    /// Ascending order
+    Asc,
+
+    /// Descending order
+    Desc,
+}
+
+impl Display for Order {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Order::Asc => write!(f, "asc"),
+            Order::Desc => write!(f, "desc"),
+        }
+    }
+}
+
+/// Query parameters of the list endpoints paginated in both directions, e.g. assistants and runs.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct ListParams {
     /// A cursor for use in pagination. after is an object ID that defines your place in the list.
     /// For instance, if you make a list request and receive 100 objects, ending with obj_foo,
     /// your subsequent call can include after=obj_foo in order to fetch the next page of the list.
@@ -11,76 +57,138 @@ pub struct OpenAIQueryParameters {
     /// your subsequent call can include before=obj_foo in order to fetch the previous page of the list.
     pub before: Option<String>,
 
-    /// A limit on the number of objects to be returned.
+    /// A limit on the number of objects to be returned. Limit can range between 1 and 100, and the default is 20.
     pub limit: Option<u32>,
 
-    /// Sort order by the created_at timestamp of the objects. asc for ascending order and desc for descending order.
-    pub order: Option<String>,
-
-    /// Only return files with the given purpose.
-    pub purpose: Option<String>,
+    /// Sort order by the created_at timestamp of the objects.
+    pub order: Option<Order>,
 }
 
-impl OpenAIQueryParameters {
-    /// Create a new query parameters object.
+impl ListParams {
+    /// Create new list parameters.
     pub fn new() -> Self {
         Self::default()
     }
 
     /// Sets the after ID for pagination.
-    pub fn after<S: Into<String>>(&mut self, after: S) -> &mut Self {
+    pub fn after<S: Into<String>>(mut self, after: S) -> Self {
         self.after = Some(after.into());
         self
     }
 
     /// Sets the before ID for pagination.
-    pub fn before<S: Into<String>>(&mut self, before: S) -> &mut Self {
+    pub fn before<S: Into<String>>(mut self, before: S) -> Self {
         self.before = Some(before.into());
         self
     }
 
     /// Set a limit on the number of objects to be returned.
-    pub fn limit(&mut self, limit: u32) -> &mut Self {
+    pub fn limit(mut self, limit: u32) -> Self {
         self.limit = Some(limit);
         self
     }
 
-    /// Set the sort order by the created_at timestamp of the objects. asc for ascending order and desc for descending order.
-    pub fn order<S: Into<String>>(&mut self, order: S) -> &mut Self {
-        self.order = Some(order.into());
+    /// Set the sort order by the created_at timestamp of the objects.
+    pub fn order(mut self, order: Order) -> Self {
+        self.order = Some(order);
         self
     }
+}
 
-    /// Only return files with the given purpose.
-    pub fn purpose<S: Into<String>>(&mut self, purpose: S) -> &mut Self {
-        self.purpose = Some(purpose.into());
-        self
+impl PaginatedParams for ListParams {
+    fn after_mut(&mut self) -> &mut Option<String> {
+        &mut self.after
     }
+}
 
-    /// Convert the query parameters to a header map.
-    pub fn to_query(self) -> Vec<(String, String)> {
+impl QueryParameters for ListParams {
+    fn to_query(&self) -> Vec<(String, String)> {
         let mut query = vec![];
 
-        if let Some(after) = self.after {
-            query.push(("after".to_string(), after));
-        }
+        push_query(&mut query, "after", self.after.as_ref());
+        push_query(&mut query, "before", self.before.as_ref());
+        push_query(&mut query, "limit", self.limit.as_ref());
+        push_query(&mut query, "order", self.order.as_ref());
 
-        if let Some(before) = self.before {
-            query.push(("before".to_string(), before));
-        }
+        query
+    }
+}
 
-        if let Some(limit) = self.limit {
-            query.push(("limit".to_string(), limit.to_string()));
-        }
+/// Query parameters of the list endpoints only paginated forward, e.g. fine-tuning jobs and batches.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct CursorParams {
+    /// Identifier for the last object from the previous pagination request.
+    pub after: Option<String>,
 
-        if let Some(order) = self.order {
-            query.push(("order".to_string(), order));
-        }
+    /// Number of objects to retrieve, 20 by default.
+    pub limit: Option<u32>,
+}
 
-        if let Some(purpose) = self.purpose {
-            query.push(("purpose".to_string(), purpose));
-        }
+impl CursorParams {
+    /// Create new cursor parameters.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the after ID for pagination.
+    pub fn after<S: Into<String>>(mut self, after: S) -> Self {
+        self.after = Some(after.into());
+        self
+    }
+
+    /// Set a limit on the number of objects to be returned.
+    pub fn limit(mut self, limit: u32) -> Self {
+        self.limit = Some(limit);
+        self
+    }
+}
+
+impl PaginatedParams for CursorParams {
+    fn after_mut(&mut self) -> &mut Option<String> {
+        &mut self.after
+    }
+}
+
+impl QueryParameters for CursorParams {
+    fn to_query(&self) -> Vec<(String, String)> {
+        let mut query = vec![];
+
+        push_query(&mut query, "after", self.after.as_ref());
+        push_query(&mut query, "limit", self.limit.as_ref());
 
         query
     }
 }
+
+/// Adds the parameter to the query if it's set.
+pub(crate) fn push_query<T>(query: &mut Vec<(String, String)>, name: &str, value: Option<&T>)
+where
+    T: Display + ?Sized,
+{
+    if let Some(value) = value {
+        query.push((name.to_string(), value.to_string()));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn converts_to_query_correctly() {
+        let parameters = ListParams::new()
+            .limit(10)
+            .order(Order::Asc)
+            .after("obj_foo");
+
+        assert_eq!(
+            parameters.to_query(),
+            [
+                ("after".to_string(), "obj_foo".to_string()),
+                ("limit".to_string(), "10".to_string()),
+                ("order".to_string(), "asc".to_string()),
+            ]
+        );
+        assert!(CursorParams::new().to_query().is_empty());
+    }
+}
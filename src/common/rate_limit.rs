@@ -0,0 +1,385 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use reqwest::header::HeaderMap;
+
+const LIMIT_REQUESTS_HEADER: &str = "x-ratelimit-limit-requests";
+const LIMIT_TOKENS_HEADER: &str = "x-ratelimit-limit-tokens";
+const REMAINING_REQUESTS_HEADER: &str = "x-ratelimit-remaining-requests";
+const REMAINING_TOKENS_HEADER: &str = "x-ratelimit-remaining-tokens";
+const RESET_REQUESTS_HEADER: &str = "x-ratelimit-reset-requests";
+const RESET_TOKENS_HEADER: &str = "x-ratelimit-reset-tokens";
+
+/// Rate limit information returned by OpenAI in the `x-ratelimit-*` response headers.
+/// See [rate limits](https://platform.openai.com/docs/guides/rate-limits/rate-limits-in-headers).
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct RateLimitInfo {
+    /// The maximum number of requests that are permitted before exhausting the rate limit.
+    pub limit_requests: Option<u64>,
+
+    /// The maximum number of tokens that are permitted before exhausting the rate limit.
+    pub limit_tokens: Option<u64>,
+
+    /// The remaining number of requests that are permitted before exhausting the rate limit.
+    pub remaining_requests: Option<u64>,
+
+    /// The remaining number of tokens that are permitted before exhausting the rate limit.
+    pub remaining_tokens: Option<u64>,
+
+    /// The time until the request rate limit resets to its initial state.
+    pub reset_requests: Option<Duration>,
+
+    /// The time until the token rate limit resets to its initial state.
+    pub reset_tokens: Option<Duration>,
+}
+
+impl RateLimitInfo {
+    /// Parses rate limit headers. Returns `None` if the response carried none of them.
+    pub fn from_headers(headers: &HeaderMap) -> Option<Self> {
+        let header = |name: &str| headers.get(name).and_then(|value| value.to_str().ok());
+        let number = |name: &str| header(name).and_then(|value| value.trim().parse::<u64>().ok());
+        let duration = |name: &str| header(name).and_then(parse_reset_duration);
+
+        let info = Self {
+            limit_requests: number(LIMIT_REQUESTS_HEADER),
+            limit_tokens: number(LIMIT_TOKENS_HEADER),
+            remaining_requests: number(REMAINING_REQUESTS_HEADER),
+            remaining_tokens: number(REMAINING_TOKENS_HEADER),
+            reset_requests: duration(RESET_REQUESTS_HEADER),
+            reset_tokens: duration(RESET_TOKENS_HEADER),
+        };
+
+        (info != Self::default()).then_some(info)
+    }
+}
+
+/// Parses durations in the format OpenAI uses for reset headers, such as `20ms`, `1.5s` or `6m0s`.
+fn parse_reset_duration(value: &str) -> Option<Duration> {
+    let mut total = Duration::ZERO;
+    let mut rest = value.trim();
+
+    if rest.is_empty() {
+        return None;
+    }
+
+    while !rest.is_empty() {
+        let number_end = rest
+            .find(|c: char| !(c.is_ascii_digit() || c == '.'))
+            .unwrap_or(rest.len());
+        let (number, tail) = rest.split_at(number_end);
+        let number = number.parse::<f64>().ok()?;
+
+        let unit_end = tail
+            .find(|c: char| c.is_ascii_digit() || c == '.')
+            .unwrap_or(tail.len());
+        let (unit, tail) = tail.split_at(unit_end);
+
+        let seconds = match unit {
+            "h" => number * 3600.0,
+            "m" => number * 60.0,
+            "s" | "" => number,
+            "ms" => number / 1_000.0,
+            "us" | "µs" => number / 1_000_000.0,
+            "ns" => number / 1_000_000_000.0,
+            _ => return None,
+        };
+
+        total += Duration::try_from_secs_f64(seconds).ok()?;
+        rest = tail;
+    }
+
+    Some(total)
+}
+
+/// Request and token budgets for a single model, per minute.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct RateLimit {
+    /// Maximum number of requests per minute.
+    pub requests_per_minute: Option<u32>,
+
+    /// Maximum number of tokens per minute.
+    pub tokens_per_minute: Option<u32>,
+}
+
+impl RateLimit {
+    /// Creates a rate limit without any budgets.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the maximum number of requests per minute.
+    pub fn requests_per_minute(mut self, requests_per_minute: u32) -> Self {
+        self.requests_per_minute = Some(requests_per_minute);
+        self
+    }
+
+    /// Sets the maximum number of tokens per minute.
+    pub fn tokens_per_minute(mut self, tokens_per_minute: u32) -> Self {
+        self.tokens_per_minute = Some(tokens_per_minute);
+        self
+    }
+}
+
+/// Client-side limiter that delays requests so they stay within the configured
+/// requests-per-minute and tokens-per-minute budgets of each model.
+///
+/// Budgets refill continuously and are additionally lowered to whatever OpenAI reports as remaining
+/// in the rate limit headers, so several processes sharing one API key back off together.
+/// Clones of the limiter, and of the client it is installed in, share the same budgets.
+#[derive(Debug, Clone, Default)]
+pub struct RateLimiter {
+    limits: HashMap<String, RateLimit>,
+
+    default_limit: Option<RateLimit>,
+
+    budgets: Arc<Mutex<HashMap<String, Budgets>>>,
+}
+
+impl RateLimiter {
+    /// Creates a limiter without any limits.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the limit for a specific model, such as `gpt-4`.
+    pub fn model_limit<S>(mut self, model: S, limit: RateLimit) -> Self
+    where
+        S: Into<String>,
+    {
+        let _ = self.limits.insert(model.into(), limit);
+        self
+    }
+
+    /// Sets the limit for models without a specific limit.
+    pub fn default_limit(mut self, limit: RateLimit) -> Self {
+        self.default_limit = Some(limit);
+        self
+    }
+
+    /// Waits until the model has budget for one more request with the given number of tokens.
+    pub(crate) async fn acquire(&self, model: &str, tokens: u32) {
+        let Some(limit) = self.limits.get(model).or(self.default_limit.as_ref()) else {
+            return;
+        };
+
+        loop {
+            let wait = {
+                let mut budgets = self.budgets.lock().unwrap_or_else(|e| e.into_inner());
+                let budget = budgets
+                    .entry(model.to_string())
+                    .or_insert_with(|| Budgets::new(limit));
+
+                budget.try_take(tokens)
+            };
+
+            match wait {
+                None => return,
+                Some(wait) => {
+                    debug!(model, tokens, ?wait, "Waiting for rate limit budget");
+                    tokio::time::sleep(wait).await;
+                }
+            }
+        }
+    }
+
+    /// Lowers the budgets of the model to what OpenAI reported as remaining.
+    pub(crate) fn update(&self, model: &str, info: &RateLimitInfo) {
+        let mut budgets = self.budgets.lock().unwrap_or_else(|e| e.into_inner());
+
+        if let Some(budget) = budgets.get_mut(model) {
+            budget.sync(info);
+        }
+    }
+}
+
+#[derive(Debug)]
+struct Budgets {
+    requests: Option<Bucket>,
+
+    tokens: Option<Bucket>,
+}
+
+impl Budgets {
+    fn new(limit: &RateLimit) -> Self {
+        Self {
+            requests: limit.requests_per_minute.map(Bucket::per_minute),
+            tokens: limit.tokens_per_minute.map(Bucket::per_minute),
+        }
+    }
+
+    /// Takes one request and the tokens from the budgets, or returns how long to wait for them.
+    fn try_take(&mut self, tokens: u32) -> Option<Duration> {
+        let now = Instant::now();
+        let request_wait = self.requests.as_mut().map(|bucket| bucket.wait(1.0, now));
+        let token_wait = self
+            .tokens
+            .as_mut()
+            .map(|bucket| bucket.wait(f64::from(tokens), now));
+
+        let wait = request_wait.max(token_wait).unwrap_or_default();
+
+        if !wait.is_zero() {
+            return Some(wait);
+        }
+
+        if let Some(bucket) = self.requests.as_mut() {
+            bucket.take(1.0);
+        }
+
+        if let Some(bucket) = self.tokens.as_mut() {
+            bucket.take(f64::from(tokens));
+        }
+
+        None
+    }
+
+    fn sync(&mut self, info: &RateLimitInfo) {
+        if let (Some(bucket), Some(remaining)) = (self.requests.as_mut(), info.remaining_requests) {
+            bucket.lower_to(remaining as f64);
+        }
+
+        if let (Some(bucket), Some(remaining)) = (self.tokens.as_mut(), info.remaining_tokens) {
+            bucket.lower_to(remaining as f64);
+        }
+    }
+}
+
+/// A token bucket that refills continuously up to its capacity.
+#[derive(Debug)]
+struct Bucket {
+    capacity: f64,
+
+    available: f64,
+
+    refill_per_second: f64,
+
+    updated_at: Instant,
+}
+
+impl Bucket {
+    fn per_minute(limit: u32) -> Self {
+        let capacity = f64::from(limit.max(1));
+
+        Self {
+            capacity,
+            available: capacity,
+            refill_per_second: capacity / 60.0,
+            updated_at: Instant::now(),
+        }
+    }
+
+    fn refill(&mut self, now: Instant) {
+        let elapsed = now.saturating_duration_since(self.updated_at).as_secs_f64();
+        self.available = (self.available + elapsed * self.refill_per_second).min(self.capacity);
+        self.updated_at = now;
+    }
+
+    /// Returns how long until `amount` is available. Amounts above the capacity only wait for a full bucket.
+    fn wait(&mut self, amount: f64, now: Instant) -> Duration {
+        self.refill(now);
+
+        let missing = amount.min(self.capacity) - self.available;
+
+        if missing <= 0.0 {
+            Duration::ZERO
+        } else {
+            Duration::from_secs_f64(missing / self.refill_per_second)
+        }
+    }
+
+    fn take(&mut self, amount: f64) {
+        self.available -= amount.min(self.capacity);
+    }
+
+    fn lower_to(&mut self, remaining: f64) {
+        self.available = self.available.min(remaining);
+    }
+}
+
+/// Roughly estimates the number of tokens a request consumes from the token budget:
+/// about four characters of the body per token, plus the requested completion tokens.
+pub(crate) fn estimate_tokens(body: &serde_json::Value) -> u32 {
+    let prompt_tokens = body.to_string().len() / 4;
+
+    let completion_tokens = body
+        .get("max_tokens")
+        .and_then(serde_json::Value::as_u64)
+        .unwrap_or_default();
+
+    let choices = body
+        .get("n")
+        .and_then(serde_json::Value::as_u64)
+        .unwrap_or(1);
+
+    let tokens = prompt_tokens as u64 + completion_tokens * choices;
+    u32::try_from(tokens).unwrap_or(u32::MAX)
+}
+
+#[cfg(test)]
+mod tests {
+    use reqwest::header::HeaderValue;
+
+    use super::*;
+
+    #[test]
+    fn parses_rate_limit_headers_correctly() {
+        let mut headers = HeaderMap::new();
+        let _ = headers.insert(LIMIT_REQUESTS_HEADER, HeaderValue::from_static("60"));
+        let _ = headers.insert(LIMIT_TOKENS_HEADER, HeaderValue::from_static("150000"));
+        let _ = headers.insert(REMAINING_REQUESTS_HEADER, HeaderValue::from_static("59"));
+        let _ = headers.insert(REMAINING_TOKENS_HEADER, HeaderValue::from_static("149984"));
+        let _ = headers.insert(RESET_REQUESTS_HEADER, HeaderValue::from_static("1s"));
+        let _ = headers.insert(RESET_TOKENS_HEADER, HeaderValue::from_static("6m0.5s"));
+
+        let info = RateLimitInfo::from_headers(&headers).unwrap();
+
+        let expectation = RateLimitInfo {
+            limit_requests: Some(60),
+            limit_tokens: Some(150000),
+            remaining_requests: Some(59),
+            remaining_tokens: Some(149984),
+            reset_requests: Some(Duration::from_secs(1)),
+            reset_tokens: Some(Duration::from_millis(360_500)),
+        };
+
+        assert_eq!(info, expectation);
+        assert_eq!(RateLimitInfo::from_headers(&HeaderMap::new()), None);
+    }
+
+    #[test]
+    fn parses_reset_durations_correctly() {
+        assert_eq!(
+            parse_reset_duration("20ms"),
+            Some(Duration::from_millis(20))
+        );
+        assert_eq!(
+            parse_reset_duration("1h2m3s"),
+            Some(Duration::from_secs(3723))
+        );
+        assert_eq!(parse_reset_duration("invalid"), None);
+    }
+
+    #[test]
+    fn budgets_wait_when_exhausted() {
+        let mut budgets = Budgets::new(
+            &RateLimit::new()
+                .requests_per_minute(2)
+                .tokens_per_minute(100),
+        );
+
+        assert_eq!(budgets.try_take(40), None);
+        assert_eq!(budgets.try_take(40), None);
+        assert!(budgets.try_take(1).is_some());
+
+        let mut budgets = Budgets::new(&RateLimit::new().tokens_per_minute(100));
+        budgets.sync(&RateLimitInfo {
+            remaining_tokens: Some(10),
+            ..Default::default()
+        });
+
+        assert!(budgets.try_take(50).is_some());
+    }
+}
@@ -1,56 +1,122 @@
-use reqwest::{multipart::Form, Method};
+use reqwest::{
+    header::{HeaderMap, HeaderName, HeaderValue},
+    Method,
+};
 use serde::Serialize;
 
-use crate::{OpenAIError, OpenAIQueryParameters};
+use crate::{MultipartForm, OpenAIError, QueryParameters};
 
-pub(crate) trait JsonTrait: Serialize + std::fmt::Debug {}
-impl<T: Serialize + std::fmt::Debug> JsonTrait for T {}
+/// The body of an [`OpenAIRequest`].
+#[derive(Debug)]
+pub enum RequestBody {
+    /// The request has no body
+    Empty,
+
+    /// A JSON body
+    Json(serde_json::Value),
 
-pub(crate) trait FormTrait: TryInto<Form, Error = OpenAIError> + std::fmt::Debug {}
-impl<T: TryInto<Form, Error = OpenAIError> + std::fmt::Debug> FormTrait for T {}
+    /// A multipart form body
+    Multipart(MultipartForm),
+}
 
+impl RequestBody {
+    /// Creates a copy of the body. Files of multipart bodies are cloned with [`crate::OpenAIFile::try_clone`].
+    pub async fn try_clone(&self) -> Result<Self, OpenAIError> {
+        Ok(match self {
+            Self::Empty => Self::Empty,
+            Self::Json(body) => Self::Json(body.clone()),
+            Self::Multipart(form) => Self::Multipart(form.try_clone().await?),
+        })
+    }
+}
+
+/// A request to the OpenAI API, before it's sent by the client.
 #[derive(Debug)]
-pub(crate) struct OpenAIRequest<T> {
-    pub(crate) body: Option<T>,
-    pub(crate) query_parameters: OpenAIQueryParameters,
-    pub(crate) url: String,
-    pub(crate) method: Method,
+pub struct OpenAIRequest {
+    /// The HTTP method
+    pub method: Method,
+
+    /// The path of the endpoint, relative to the host of the client
+    pub url: String,
+
+    /// Query parameters
+    pub query: Vec<(String, String)>,
+
+    /// Headers sent in addition to the default headers of the client
+    pub headers: HeaderMap,
+
+    /// The body of the request
+    pub body: RequestBody,
 }
 
-impl<T> OpenAIRequest<T> {
-    pub(crate) fn new(method: Method, url: String) -> Self {
+impl OpenAIRequest {
+    /// Creates a request without a body.
+    pub fn new(method: Method, url: String) -> Self {
         OpenAIRequest {
-            body: None,
-            query_parameters: Default::default(),
-            url,
             method,
+            url,
+            query: vec![],
+            headers: HeaderMap::new(),
+            body: RequestBody::Empty,
         }
     }
 
-    pub(crate) fn with_query_parameters(mut self, query_parameters: OpenAIQueryParameters) -> Self {
-        self.query_parameters = query_parameters;
-        self
+    /// Creates a request with a JSON body.
+    pub fn with_body<T>(method: Method, url: String, body: T) -> Result<Self, OpenAIError>
+    where
+        T: Serialize,
+    {
+        Ok(OpenAIRequest {
+            body: RequestBody::Json(serde_json::to_value(body)?),
+            ..Self::new(method, url)
+        })
     }
-}
 
-impl<T: JsonTrait> OpenAIRequest<T> {
-    pub(crate) fn with_body(method: Method, url: String, body: T) -> Self {
+    /// Creates a request with a multipart form body.
+    pub fn with_form<T>(method: Method, url: String, body: T) -> Self
+    where
+        T: Into<MultipartForm>,
+    {
         OpenAIRequest {
-            body: Some(body),
-            query_parameters: Default::default(),
-            url,
-            method,
+            body: RequestBody::Multipart(body.into()),
+            ..Self::new(method, url)
         }
     }
-}
 
-impl<T: FormTrait> OpenAIRequest<T> {
-    pub(crate) fn with_form(method: Method, url: String, body: T) -> Self {
-        OpenAIRequest {
-            body: Some(body),
-            query_parameters: Default::default(),
-            url,
-            method,
+    /// Sets the query parameters of the request.
+    pub fn with_query_parameters<Q: QueryParameters>(mut self, query_parameters: Q) -> Self {
+        self.query = query_parameters.to_query();
+        self
+    }
+
+    /// Sets a header, replacing any previous value.
+    pub fn set_header(&mut self, name: &str, value: &str) -> Result<(), OpenAIError> {
+        let name = HeaderName::from_bytes(name.as_bytes())
+            .map_err(|e| OpenAIError::Exception(e.to_string()))?;
+        let value =
+            HeaderValue::from_str(value).map_err(|e| OpenAIError::Exception(e.to_string()))?;
+
+        let _ = self.headers.insert(name, value);
+        Ok(())
+    }
+
+    /// Returns the model the request is made for, if the body names one.
+    pub fn model(&self) -> Option<&str> {
+        match &self.body {
+            RequestBody::Empty => None,
+            RequestBody::Json(body) => body.get("model").and_then(serde_json::Value::as_str),
+            RequestBody::Multipart(form) => form.text_field("model"),
         }
     }
+
+    /// Creates a copy of the request.
+    pub async fn try_clone(&self) -> Result<Self, OpenAIError> {
+        Ok(Self {
+            method: self.method.clone(),
+            url: self.url.clone(),
+            query: self.query.clone(),
+            headers: self.headers.clone(),
+            body: self.body.try_clone().await?,
+        })
+    }
 }
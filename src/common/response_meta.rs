@@ -0,0 +1,66 @@
+use std::{cell::RefCell, future::Future};
+
+use reqwest::{header::HeaderMap, Response, StatusCode};
+
+use super::{RateLimitInfo, REQUEST_ID_HEADER};
+
+tokio::task_local! {
+    static RESPONSE_META: RefCell<Option<ResponseMeta>>;
+}
+
+/// The metadata of an OpenAI API response, such as its request ID and rate limits.
+#[derive(Debug, Clone)]
+pub struct ResponseMeta {
+    /// HTTP status code of the response.
+    pub status: StatusCode,
+
+    /// The value of the `x-request-id` header, useful when contacting OpenAI support.
+    pub request_id: Option<String>,
+
+    /// The headers of the response.
+    pub headers: HeaderMap,
+}
+
+impl ResponseMeta {
+    /// Reads the metadata of a response.
+    pub(crate) fn from_response(response: &Response) -> Self {
+        let headers = response.headers().clone();
+        let request_id = headers
+            .get(REQUEST_ID_HEADER)
+            .and_then(|value| value.to_str().ok())
+            .map(ToString::to_string);
+
+        Self {
+            status: response.status(),
+            request_id,
+            headers,
+        }
+    }
+
+    /// The rate limit information sent along with the response, if any.
+    pub fn rate_limit_info(&self) -> Option<RateLimitInfo> {
+        RateLimitInfo::from_headers(&self.headers)
+    }
+}
+
+/// Runs the call and returns its output together with the metadata of the last response it received.
+pub(crate) async fn with_response_meta<F>(call: F) -> (F::Output, Option<ResponseMeta>)
+where
+    F: Future,
+{
+    RESPONSE_META
+        .scope(RefCell::new(None), async move {
+            let output = call.await;
+            let meta = RESPONSE_META.with(RefCell::take);
+
+            (output, meta)
+        })
+        .await
+}
+
+/// Stores the metadata of the response, if it's being captured by [`with_response_meta`].
+pub(crate) fn capture_response_meta(response: &Response) {
+    let _ = RESPONSE_META.try_with(|meta| {
+        let _ = meta.replace(Some(ResponseMeta::from_response(response)));
+    });
+}
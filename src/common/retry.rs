@@ -0,0 +1,234 @@
+use std::time::Duration;
+
+use rand::Rng;
+use reqwest::{header::HeaderMap, StatusCode};
+
+use super::OpenAIError;
+
+const RETRY_AFTER_MS_HEADER: &str = "retry-after-ms";
+const RETRY_AFTER_HEADER: &str = "retry-after";
+
+/// Describes if and how failed requests are retried by the client.
+///
+/// Delays grow exponentially from `base_delay` up to `max_delay`. When OpenAI answers with
+/// `429 Too Many Requests` or `503 Service Unavailable` and provides a `retry-after-ms` or
+/// `retry-after` header, the delay requested by the server is used instead.
+#[derive(Debug, Clone)]
+pub struct RetryPolicy {
+    max_attempts: u32,
+
+    base_delay: Duration,
+
+    max_delay: Duration,
+
+    jitter: bool,
+
+    retry_statuses: Vec<StatusCode>,
+
+    retry_timeouts: bool,
+
+    retry_connection_errors: bool,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            base_delay: Duration::from_millis(500),
+            max_delay: Duration::from_secs(8),
+            jitter: true,
+            retry_statuses: vec![
+                StatusCode::REQUEST_TIMEOUT,
+                StatusCode::CONFLICT,
+                StatusCode::TOO_MANY_REQUESTS,
+                StatusCode::INTERNAL_SERVER_ERROR,
+                StatusCode::BAD_GATEWAY,
+                StatusCode::SERVICE_UNAVAILABLE,
+                StatusCode::GATEWAY_TIMEOUT,
+            ],
+            retry_timeouts: true,
+            retry_connection_errors: true,
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Creates a retry policy with default settings: 3 attempts, delays between 500ms and 8s
+    /// with jitter, retrying timeouts, connection errors, rate limits and server errors.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a policy that never retries.
+    pub fn never() -> Self {
+        Self {
+            max_attempts: 1,
+            ..Default::default()
+        }
+    }
+
+    /// Sets the maximum number of attempts, including the first one.
+    pub fn max_attempts(mut self, max_attempts: u32) -> Self {
+        self.max_attempts = max_attempts.max(1);
+        self
+    }
+
+    /// Sets the delay before the first retry.
+    pub fn base_delay(mut self, base_delay: Duration) -> Self {
+        self.base_delay = base_delay;
+        self
+    }
+
+    /// Sets the upper bound for the exponential backoff delay.
+    pub fn max_delay(mut self, max_delay: Duration) -> Self {
+        self.max_delay = max_delay;
+        self
+    }
+
+    /// Enables or disables randomization of the backoff delay.
+    pub fn jitter(mut self, jitter: bool) -> Self {
+        self.jitter = jitter;
+        self
+    }
+
+    /// Sets the HTTP statuses that should be retried.
+    pub fn retry_statuses<I>(mut self, statuses: I) -> Self
+    where
+        I: IntoIterator<Item = StatusCode>,
+    {
+        self.retry_statuses = statuses.into_iter().collect();
+        self
+    }
+
+    /// Sets whether requests that timed out should be retried.
+    pub fn retry_timeouts(mut self, retry_timeouts: bool) -> Self {
+        self.retry_timeouts = retry_timeouts;
+        self
+    }
+
+    /// Sets whether requests that failed to connect should be retried.
+    pub fn retry_connection_errors(mut self, retry_connection_errors: bool) -> Self {
+        self.retry_connection_errors = retry_connection_errors;
+        self
+    }
+
+    /// Returns the maximum number of attempts, including the first one.
+    pub fn attempts(&self) -> u32 {
+        self.max_attempts
+    }
+
+    /// Whether the error is worth retrying under this policy.
+    pub fn should_retry(&self, error: &OpenAIError) -> bool {
+        match error {
+            OpenAIError::Api(error) => {
+                self.retry_statuses.contains(&error.status) && !error.is_insufficient_quota()
+            }
+            OpenAIError::Client(error) => {
+                (self.retry_timeouts && error.is_timeout())
+                    || (self.retry_connection_errors && error.is_connect())
+            }
+            _ => false,
+        }
+    }
+
+    /// Returns how long to wait before the next attempt, given the number of attempts made so far.
+    pub fn delay(&self, attempt: u32, error: &OpenAIError) -> Duration {
+        let requested_delay = error
+            .api_error()
+            .filter(|error| {
+                matches!(
+                    error.status,
+                    StatusCode::TOO_MANY_REQUESTS | StatusCode::SERVICE_UNAVAILABLE
+                )
+            })
+            .and_then(|error| retry_after(&error.headers));
+
+        if let Some(delay) = requested_delay {
+            return delay;
+        }
+
+        let exponent = attempt.saturating_sub(1).min(31);
+        let delay = self
+            .base_delay
+            .saturating_mul(2u32.pow(exponent))
+            .min(self.max_delay);
+
+        if self.jitter {
+            // Equal jitter keeps at least half of the delay while spreading concurrent retries.
+            let half = delay / 2;
+            half + half.mul_f64(rand::thread_rng().gen::<f64>())
+        } else {
+            delay
+        }
+    }
+}
+
+/// Reads the delay requested by the server from the `retry-after-ms` or `retry-after` headers.
+///
+/// Only the delay-seconds form of `retry-after` is supported, HTTP dates are ignored.
+pub(crate) fn retry_after(headers: &HeaderMap) -> Option<Duration> {
+    let header = |name: &str| headers.get(name).and_then(|value| value.to_str().ok());
+
+    if let Some(milliseconds) = header(RETRY_AFTER_MS_HEADER).and_then(|v| v.parse::<f64>().ok()) {
+        if milliseconds.is_finite() && milliseconds >= 0.0 {
+            return Some(Duration::from_secs_f64(milliseconds / 1000.0));
+        }
+    }
+
+    header(RETRY_AFTER_HEADER)
+        .and_then(|value| value.trim().parse::<u64>().ok())
+        .map(Duration::from_secs)
+}
+
+#[cfg(test)]
+mod tests {
+    use reqwest::header::HeaderValue;
+
+    use crate::ApiError;
+
+    use super::*;
+
+    fn api_error(status: StatusCode, headers: HeaderMap) -> OpenAIError {
+        ApiError::from_parts(status, headers, "").into()
+    }
+
+    #[test]
+    fn backs_off_exponentially() {
+        let policy = RetryPolicy::new()
+            .base_delay(Duration::from_millis(100))
+            .max_delay(Duration::from_millis(350))
+            .jitter(false);
+
+        let error = api_error(StatusCode::INTERNAL_SERVER_ERROR, HeaderMap::new());
+
+        assert_eq!(policy.delay(1, &error), Duration::from_millis(100));
+        assert_eq!(policy.delay(2, &error), Duration::from_millis(200));
+        assert_eq!(policy.delay(3, &error), Duration::from_millis(350));
+    }
+
+    #[test]
+    fn respects_retry_after_headers() {
+        let policy = RetryPolicy::new().jitter(false);
+
+        let mut headers = HeaderMap::new();
+        let _ = headers.insert(RETRY_AFTER_HEADER, HeaderValue::from_static("3"));
+        let error = api_error(StatusCode::TOO_MANY_REQUESTS, headers.clone());
+        assert_eq!(policy.delay(1, &error), Duration::from_secs(3));
+
+        let _ = headers.insert(RETRY_AFTER_MS_HEADER, HeaderValue::from_static("1500"));
+        let error = api_error(StatusCode::SERVICE_UNAVAILABLE, headers.clone());
+        assert_eq!(policy.delay(1, &error), Duration::from_millis(1500));
+
+        let error = api_error(StatusCode::BAD_GATEWAY, headers);
+        assert_eq!(policy.delay(1, &error), Duration::from_millis(500));
+    }
+
+    #[test]
+    fn retries_only_configured_statuses() {
+        let policy = RetryPolicy::new().retry_statuses([StatusCode::TOO_MANY_REQUESTS]);
+
+        assert!(policy.should_retry(&api_error(StatusCode::TOO_MANY_REQUESTS, HeaderMap::new())));
+        assert!(!policy.should_retry(&api_error(StatusCode::BAD_GATEWAY, HeaderMap::new())));
+        assert!(!policy.should_retry(&OpenAIError::Exception("error".to_string())));
+    }
+}
@@ -0,0 +1,267 @@
+use serde_json::{Map, Value};
+
+/// String formats the OpenAI strict mode accepts
+const SUPPORTED_FORMATS: &[&str] = &[
+    "date-time",
+    "time",
+    "date",
+    "duration",
+    "email",
+    "hostname",
+    "ipv4",
+    "ipv6",
+    "uuid",
+];
+
+/// A type whose JSON Schema can be sent to OpenAI, as the parameters of a function
+/// or the format of a structured output.
+///
+/// With the `schema` feature, it's implemented for every type deriving [`schemars::JsonSchema`],
+/// using doc comments as descriptions. Without it, implement [`ToolSchema::json_schema`] by hand.
+pub trait ToolSchema {
+    /// Returns the JSON Schema of the type.
+    fn json_schema() -> Value;
+
+    /// Returns the JSON Schema of the type, with the constraints of the OpenAI strict mode applied.
+    /// See [`strict_schema`].
+    fn strict_json_schema() -> Value {
+        strict_schema(Self::json_schema())
+    }
+}
+
+#[cfg(feature = "schema")]
+impl<T> ToolSchema for T
+where
+    T: schemars::JsonSchema,
+{
+    fn json_schema() -> Value {
+        let settings = schemars::gen::SchemaSettings::draft07().with(|settings| {
+            settings.inline_subschemas = true;
+            settings.option_add_null_type = true;
+        });
+
+        let schema = settings.into_generator().into_root_schema_for::<T>();
+        let mut schema = serde_json::to_value(schema).unwrap_or_default();
+
+        if let Some(schema) = schema.as_object_mut() {
+            let _ = schema.remove("$schema");
+            let _ = schema.remove("title");
+        }
+
+        schema
+    }
+}
+
+/// Applies the constraints of the OpenAI strict mode to a JSON Schema: every object gets
+/// `additionalProperties: false` and lists all its properties as required, nullable enums list `null`,
+/// `oneOf` becomes `anyOf`, and unsupported string formats are removed. Optional fields should allow `null` instead of being left out.
+///
+/// Strict mode can't express maps, such as a `HashMap` field, so map schemas are left as they are
+/// and OpenAI rejects them, instead of them being restricted to empty objects.
+pub fn strict_schema(mut schema: Value) -> Value {
+    make_strict(&mut schema);
+    schema
+}
+
+fn make_strict(schema: &mut Value) {
+    match schema {
+        Value::Object(schema) => {
+            if is_object_schema(schema) && !is_map_schema(schema) {
+                let required = schema
+                    .get("properties")
+                    .and_then(Value::as_object)
+                    .map(|properties| properties.keys().cloned().map(Value::String).collect())
+                    .unwrap_or_default();
+
+                let _ = schema.insert("required".to_string(), Value::Array(required));
+                let _ = schema.insert("additionalProperties".to_string(), Value::Bool(false));
+            }
+
+            if schema
+                .get("format")
+                .and_then(Value::as_str)
+                .is_some_and(|format| !SUPPORTED_FORMATS.contains(&format))
+            {
+                let _ = schema.remove("format");
+            }
+
+            // Strict mode rejects `oneOf`, and the variants of serde enums are exclusive already
+            if !schema.contains_key("anyOf") {
+                if let Some(one_of) = schema.remove("oneOf") {
+                    let _ = schema.insert("anyOf".to_string(), one_of);
+                }
+            }
+
+            // Nullable enums have to list `null` as a value too
+            let nullable = schema
+                .get("type")
+                .and_then(Value::as_array)
+                .is_some_and(|types| types.iter().any(|_type| _type == "null"));
+
+            if let Some(Value::Array(values)) = schema.get_mut("enum") {
+                if nullable && !values.contains(&Value::Null) {
+                    values.push(Value::Null);
+                }
+            }
+
+            for (key, value) in schema.iter_mut() {
+                match key.as_str() {
+                    // Maps of subschemas
+                    "properties" | "definitions" | "$defs" => {
+                        if let Value::Object(subschemas) = value {
+                            subschemas.values_mut().for_each(make_strict);
+                        }
+                    }
+                    "items" | "anyOf" | "allOf" | "oneOf" | "not" => make_strict(value),
+                    _ => {}
+                }
+            }
+        }
+        Value::Array(schemas) => schemas.iter_mut().for_each(make_strict),
+        _ => {}
+    }
+}
+
+fn is_object_schema(schema: &Map<String, Value>) -> bool {
+    match schema.get("type") {
+        Some(Value::String(_type)) => _type == "object",
+        Some(Value::Array(types)) => types.iter().any(|_type| _type == "object"),
+        _ => schema.contains_key("properties"),
+    }
+}
+
+/// Maps allow properties following a schema, instead of only the listed properties
+fn is_map_schema(schema: &Map<String, Value>) -> bool {
+    schema
+        .get("additionalProperties")
+        .is_some_and(|additional_properties| additional_properties != &Value::Bool(false))
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn makes_schema_strict_correctly() {
+        let schema = json!({
+            "type": "object",
+            "properties": {
+                "city": { "type": "string" },
+                "days": { "type": ["integer", "null"], "format": "uint8" },
+                "stops": {
+                    "type": "array",
+                    "items": { "type": "object", "properties": { "at": { "type": "string", "format": "date-time" } } }
+                }
+            },
+            "required": ["city"]
+        });
+
+        let expected = json!({
+            "type": "object",
+            "properties": {
+                "city": { "type": "string" },
+                "days": { "type": ["integer", "null"] },
+                "stops": {
+                    "type": "array",
+                    "items": {
+                        "type": "object",
+                        "properties": { "at": { "type": "string", "format": "date-time" } },
+                        "required": ["at"],
+                        "additionalProperties": false
+                    }
+                }
+            },
+            "required": ["city", "days", "stops"],
+            "additionalProperties": false
+        });
+
+        assert_eq!(strict_schema(schema), expected);
+    }
+
+    #[cfg(feature = "schema")]
+    #[test]
+    fn derives_schema_correctly() {
+        #[allow(dead_code)]
+        #[derive(schemars::JsonSchema)]
+        /// Weather query
+        struct WeatherArguments {
+            /// The city to get the weather for
+            city: String,
+
+            /// The unit of the temperature
+            unit: Option<Unit>,
+        }
+
+        #[allow(dead_code)]
+        #[derive(schemars::JsonSchema)]
+        #[serde(rename_all = "lowercase")]
+        enum Unit {
+            Celsius,
+            Fahrenheit,
+        }
+
+        let expected = json!({
+            "description": "Weather query",
+            "type": "object",
+            "properties": {
+                "city": { "description": "The city to get the weather for", "type": "string" },
+                "unit": {
+                    "description": "The unit of the temperature",
+                    "type": ["string", "null"],
+                    "enum": ["celsius", "fahrenheit", null]
+                }
+            },
+            "required": ["city", "unit"],
+            "additionalProperties": false
+        });
+
+        assert_eq!(WeatherArguments::strict_json_schema(), expected);
+    }
+
+    #[cfg(feature = "schema")]
+    #[test]
+    fn leaves_map_schemas_alone() {
+        #[allow(dead_code)]
+        #[derive(schemars::JsonSchema)]
+        struct Inventory {
+            store: String,
+            stock: std::collections::HashMap<String, u32>,
+        }
+
+        let schema = Inventory::json_schema();
+        let strict_schema = Inventory::strict_json_schema();
+
+        assert_eq!(
+            strict_schema["properties"]["stock"],
+            schema["properties"]["stock"]
+        );
+        assert!(schema["properties"]["stock"]["additionalProperties"].is_object());
+        assert_eq!(strict_schema["required"], json!(["stock", "store"]));
+        assert_eq!(strict_schema["additionalProperties"], json!(false));
+    }
+
+    #[cfg(feature = "schema")]
+    #[test]
+    fn replaces_one_of_with_any_of() {
+        #[allow(dead_code)]
+        #[derive(schemars::JsonSchema)]
+        #[serde(tag = "kind")]
+        enum Shape {
+            Circle { radius: f64 },
+            Square { side: f64 },
+        }
+
+        let schema = Shape::json_schema();
+        let strict_schema = Shape::strict_json_schema();
+
+        assert!(schema["oneOf"].is_array());
+        assert!(strict_schema.get("oneOf").is_none());
+
+        let variants = strict_schema["anyOf"].as_array().unwrap();
+        assert_eq!(variants.len(), 2);
+        assert_eq!(variants[0]["required"], json!(["kind", "radius"]));
+        assert_eq!(variants[0]["additionalProperties"], json!(false));
+    }
+}
@@ -0,0 +1,161 @@
+use serde::{Deserialize, Serialize};
+
+/// A server-sent event, as sent by OpenAI on streaming endpoints.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SseEvent {
+    /// The name of the event, set with the `event:` field. Unnamed events are `message` events.
+    pub event: Option<String>,
+
+    /// The data of the event. Data split over several `data:` lines is joined with new lines.
+    pub data: String,
+
+    /// The ID of the event, set with the `id:` field.
+    pub id: Option<String>,
+
+    /// The reconnection time in milliseconds, set with the `retry:` field. The client never reconnects,
+    /// since reopening a stream would send the request, and bill it, again.
+    pub retry: Option<u64>,
+}
+
+impl SseEvent {
+    /// Whether the event is an `error` event.
+    pub fn is_error(&self) -> bool {
+        self.event.as_deref() == Some("error")
+    }
+
+    /// Whether the event is the `[DONE]` marker that ends OpenAI streams.
+    pub fn is_done(&self) -> bool {
+        self.data == "[DONE]"
+    }
+}
+
+/// An incremental decoder of the `text/event-stream` format.
+///
+/// Bytes are fed in as they arrive, in chunks of any size, and complete events are returned
+/// once the blank line ending them is received.
+#[derive(Debug, Default)]
+pub struct SseDecoder {
+    buffer: Vec<u8>,
+    event: SseEvent,
+    has_data: bool,
+    skip_line_feed: bool,
+}
+
+impl SseDecoder {
+    /// Creates an empty decoder.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Decodes a chunk of the stream, returning the events it completes.
+    pub fn decode(&mut self, chunk: &[u8]) -> Vec<SseEvent> {
+        let mut events = vec![];
+
+        for &byte in chunk {
+            // A `\r\n` line ending was already handled at the `\r`
+            if std::mem::take(&mut self.skip_line_feed) && byte == b'\n' {
+                continue;
+            }
+
+            match byte {
+                b'\r' | b'\n' => {
+                    self.skip_line_feed = byte == b'\r';
+
+                    let line = std::mem::take(&mut self.buffer);
+                    if let Some(event) = self.process_line(&line) {
+                        events.push(event);
+                    }
+                }
+                byte => self.buffer.push(byte),
+            }
+        }
+
+        events
+    }
+
+    fn process_line(&mut self, line: &[u8]) -> Option<SseEvent> {
+        if line.is_empty() {
+            return self.dispatch();
+        }
+
+        let line = String::from_utf8_lossy(line);
+        let line = line.strip_prefix('\u{feff}').unwrap_or(&line);
+
+        // Comments, usually sent to keep the connection alive
+        if line.starts_with(':') {
+            return None;
+        }
+
+        let (field, value) = match line.split_once(':') {
+            Some((field, value)) => (field, value.strip_prefix(' ').unwrap_or(value)),
+            None => (line, ""),
+        };
+
+        match field {
+            "event" => self.event.event = Some(value.to_string()),
+            "data" => {
+                if self.has_data {
+                    self.event.data.push('\n');
+                }
+
+                self.event.data.push_str(value);
+                self.has_data = true;
+            }
+            "id" if !value.contains('\0') => self.event.id = Some(value.to_string()),
+            "retry" => self.event.retry = value.parse().ok().or(self.event.retry),
+            _ => {}
+        }
+
+        None
+    }
+
+    fn dispatch(&mut self) -> Option<SseEvent> {
+        let event = std::mem::take(&mut self.event);
+
+        // Events without data are not dispatched, like in browsers
+        std::mem::take(&mut self.has_data).then_some(event)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decodes_events_correctly() {
+        let mut decoder = SseDecoder::new();
+
+        let mut events = decoder.decode(b": keep-alive\n\ndata: {\"a\":");
+        assert!(events.is_empty());
+
+        events.extend(decoder.decode(b"1}\r\n\r\nevent: error\r"));
+        events.extend(
+            decoder.decode(b"\ndata: first\ndata:second\nid: 7\nretry: 300\n\ndata: [DONE]\n"),
+        );
+        assert_eq!(events.len(), 2);
+        events.extend(decoder.decode(b"\n"));
+
+        assert_eq!(
+            events,
+            vec![
+                SseEvent {
+                    data: "{\"a\":1}".to_string(),
+                    ..Default::default()
+                },
+                SseEvent {
+                    event: Some("error".to_string()),
+                    data: "first\nsecond".to_string(),
+                    id: Some("7".to_string()),
+                    retry: Some(300),
+                },
+                SseEvent {
+                    data: "[DONE]".to_string(),
+                    ..Default::default()
+                },
+            ]
+        );
+
+        assert!(events[1].is_error());
+        assert!(events[2].is_done());
+    }
+}
@@ -1,62 +1,222 @@
-use futures_util::StreamExt;
-use reqwest_eventsource::Event;
+use std::{
+    pin::Pin,
+    task::{Context, Poll},
+    time::Duration,
+};
 
-use super::OpenAIError;
+use futures_util::{Stream, StreamExt};
+use reqwest::{header::HeaderMap, Response, StatusCode};
+use serde_json::Value;
+use tokio::{
+    sync::mpsc::{Receiver, Sender},
+    task::JoinHandle,
+};
 
-/// OpenAIStream is a wrapper around [`tokio::sync::mpsc::UnboundedReceiver<Result<T, OpenAIError>>`] that
-/// provides a convenient way to read values from stream.
+use super::{ApiError, ApiErrorObject, OpenAIError, SseDecoder, SseEvent};
+
+/// The number of values buffered before the stream stops reading from the connection
+const STREAM_BUFFER_SIZE: usize = 32;
+
+/// Called with every value read from the stream, before it's buffered
+pub(crate) type StreamInspector<T> = Box<dyn Fn(&T) + Send + Sync>;
+
+/// OpenAIStream is a [`Stream`] of the values sent by OpenAI as server-sent events.
+///
+/// The events are read by a background task into a bounded buffer, so a slow consumer applies
+/// backpressure to the connection. Dropping the stream stops the task and closes the connection.
+///
+/// The stream is never reopened: if the connection fails, the error is the last value of the stream.
+/// A stream ending without the `[DONE]` marker fails with [`OpenAIError::StreamTruncated`],
+/// so truncated output can't be mistaken for a finished one.
+/// `error` events, and events carrying an `error` object, are returned as [`OpenAIError::Api`].
 #[derive(Debug)]
 pub struct OpenAIStream<T> {
-    rx: tokio::sync::mpsc::UnboundedReceiver<Result<T, OpenAIError>>,
+    rx: Receiver<Result<T, OpenAIError>>,
+    handle: JoinHandle<()>,
 }
 
 impl<T> OpenAIStream<T>
 where
     T: serde::de::DeserializeOwned + std::fmt::Debug + Send + Sync + 'static,
 {
-    /// Creates a new receiver for data from the stream.
-    pub async fn new(event_source: reqwest_eventsource::EventSource) -> Self {
-        let (tx, rx) = tokio::sync::mpsc::unbounded_channel();
-        let handle = tokio::spawn(async move { handle_event_source(event_source, tx).await });
-        drop(handle);
+    /// Creates a new receiver for data from the event stream in the body of the response.
+    pub async fn new(response: Response) -> Self {
+        Self::with_idle_timeout(response, None)
+    }
+
+    /// Creates a new receiver for data from the event stream in the body of the response,
+    /// which fails with [`OpenAIError::StreamIdleTimeout`] if no data is received for the given time.
+    pub fn with_idle_timeout(response: Response, idle_timeout: Option<Duration>) -> Self {
+        Self::with_inspector(response, idle_timeout, None)
+    }
+
+    /// Creates a new receiver for data from the event stream in the body of the response,
+    /// calling the inspector with every value as soon as it's read.
+    pub(crate) fn with_inspector(
+        response: Response,
+        idle_timeout: Option<Duration>,
+        inspector: Option<StreamInspector<T>>,
+    ) -> Self {
+        let (tx, rx) = tokio::sync::mpsc::channel(STREAM_BUFFER_SIZE);
+        let handle = tokio::spawn(async move {
+            handle_event_source(response, tx, idle_timeout, inspector).await
+        });
 
-        Self { rx }
+        Self { rx, handle }
     }
 
-    /// Reads values from the stream.
+    /// Waits for the next value of the stream. Returns `None` once the stream is finished.
+    pub async fn next(&mut self) -> Option<Result<T, OpenAIError>> {
+        self.rx.recv().await
+    }
+
+    /// Reads a value from the stream without waiting.
+    /// Fails with [`OpenAIError::StreamReceiveError`] if no value is available yet.
     pub fn read_values(&mut self) -> Result<T, OpenAIError> {
         self.rx.try_recv()?
     }
 }
 
+impl<T> Stream for OpenAIStream<T> {
+    type Item = Result<T, OpenAIError>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.rx.poll_recv(cx)
+    }
+}
+
+impl<T> Drop for OpenAIStream<T> {
+    fn drop(&mut self) {
+        self.handle.abort();
+    }
+}
+
 async fn handle_event_source<T>(
-    mut event_source: reqwest_eventsource::EventSource,
-    tx: tokio::sync::mpsc::UnboundedSender<Result<T, OpenAIError>>,
+    response: Response,
+    tx: Sender<Result<T, OpenAIError>>,
+    idle_timeout: Option<Duration>,
+    inspector: Option<StreamInspector<T>>,
 ) where
     T: serde::de::DeserializeOwned + std::fmt::Debug,
 {
-    while let Some(es) = event_source.next().await {
-        match es {
-            Err(e) => {
-                if let Err(_e) = tx.send(Err(OpenAIError::StreamError(e.to_string()))) {
-                    break;
-                }
-            }
-            Ok(event) => match event {
-                Event::Open => continue,
-                Event::Message(message) => {
-                    if message.data == "[DONE]" {
-                        break;
-                    }
-                    let event_data = serde_json::from_str::<T>(&message.data).map_err(Into::into);
-
-                    if let Err(_e) = tx.send(event_data) {
-                        break;
-                    }
+    let status = response.status();
+    let headers = response.headers().clone();
+    let mut bytes = response.bytes_stream();
+    let mut decoder = SseDecoder::new();
+
+    loop {
+        let chunk = match idle_timeout {
+            Some(idle_timeout) => match tokio::time::timeout(idle_timeout, bytes.next()).await {
+                Ok(chunk) => chunk,
+                Err(_) => {
+                    let _ = tx
+                        .send(Err(OpenAIError::StreamIdleTimeout(idle_timeout)))
+                        .await;
+                    return;
                 }
             },
+            None => bytes.next().await,
+        };
+
+        let chunk = match chunk {
+            Some(Ok(chunk)) => chunk,
+            Some(Err(error)) => {
+                let _ = tx.send(Err(error.into())).await;
+                return;
+            }
+            None => {
+                let _ = tx.send(Err(OpenAIError::StreamTruncated)).await;
+                return;
+            }
+        };
+
+        for event in decoder.decode(&chunk) {
+            if event.is_done() {
+                return;
+            }
+
+            if let Some(error) = event_error(status, &headers, &event) {
+                let _ = tx.send(Err(error.into())).await;
+                return;
+            }
+
+            let event_data = serde_json::from_str::<T>(&event.data).map_err(Into::into);
+
+            if let (Some(inspector), Ok(value)) = (&inspector, &event_data) {
+                inspector(value);
+            }
+
+            if let Err(_e) = tx.send(event_data).await {
+                return;
+            }
         }
     }
+}
+
+/// Returns the error carried by the event, if it's an `error` event or its data is an error object
+fn event_error(status: StatusCode, headers: &HeaderMap, event: &SseEvent) -> Option<ApiError> {
+    let data = serde_json::from_str::<Value>(&event.data).ok();
+
+    if data
+        .as_ref()
+        .and_then(|data| data.get("error"))
+        .is_some_and(Value::is_object)
+    {
+        return Some(ApiError::from_parts(status, headers.clone(), &event.data));
+    }
+
+    if !event.is_error() {
+        return None;
+    }
+
+    // The data of an `error` event may be a bare error object, or just a message
+    let error = data
+        .and_then(|data| serde_json::from_value::<ApiErrorObject>(data).ok())
+        .filter(|error| !error.message.is_empty())
+        .unwrap_or_else(|| ApiErrorObject {
+            message: event.data.clone(),
+            ..Default::default()
+        });
+
+    let body = serde_json::json!({ "error": error }).to_string();
+
+    Some(ApiError::from_parts(status, headers.clone(), &body))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn fails_when_stream_is_idle() {
+        let body = reqwest::Body::wrap_stream(futures_util::stream::pending::<
+            Result<Vec<u8>, std::io::Error>,
+        >());
+        let response = Response::from(http::Response::new(body));
+
+        let mut stream =
+            OpenAIStream::<Value>::with_idle_timeout(response, Some(Duration::from_millis(10)));
+
+        let error = stream.next().await.unwrap().unwrap_err();
+
+        assert!(matches!(error, OpenAIError::StreamIdleTimeout(_)));
+        assert!(stream.next().await.is_none());
+    }
+
+    #[tokio::test]
+    async fn fails_when_stream_ends_before_done() {
+        for body in ["data: {\"id\": 1}\n\ndata: {\"id\"", "{\"id\": 1}"] {
+            let response = Response::from(http::Response::new(body));
+            let mut stream = OpenAIStream::<Value>::new(response).await;
+
+            let mut values = vec![];
+            while let Some(value) = stream.next().await {
+                values.push(value);
+            }
 
-    event_source.close();
+            let error = values.pop().unwrap().unwrap_err();
+            assert!(matches!(error, OpenAIError::StreamTruncated));
+            assert!(values.iter().all(Result::is_ok));
+        }
+    }
 }
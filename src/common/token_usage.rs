@@ -1,7 +1,7 @@
 use serde::{Deserialize, Serialize};
 
 /// Usage statistics for the completion request.
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
 pub struct TokenUsage {
     /// Number of tokens in the prompt.
     #[serde(default)]
@@ -0,0 +1,95 @@
+use async_trait::async_trait;
+use reqwest::{
+    header::{HeaderValue, ACCEPT},
+    Client, RequestBuilder, Response,
+};
+use url::Url;
+
+use super::{OpenAIError, OpenAIRequest, RequestBody};
+
+/// Sends requests to the API on behalf of the client, installed with
+/// [`ClientBuilder::transport`](crate::ClientBuilder::transport).
+///
+/// Retries, rate limiting and middlewares are handled by the client, so a transport only has to
+/// deliver a single attempt of a request and hand back the raw response. Unsuccessful statuses should
+/// be returned as responses, not errors, so the client can turn them into [`OpenAIError::Api`].
+#[async_trait]
+pub trait Transport: std::fmt::Debug + Send + Sync {
+    /// Sends a request without a body or with a JSON body.
+    async fn send_json(&self, request: &OpenAIRequest) -> Result<Response, OpenAIError>;
+
+    /// Sends a request with a multipart form body.
+    async fn send_multipart(&self, request: &OpenAIRequest) -> Result<Response, OpenAIError>;
+
+    /// Sends a request whose response body is a stream of server-sent events.
+    async fn open_stream(&self, request: &OpenAIRequest) -> Result<Response, OpenAIError>;
+}
+
+/// The default [`Transport`], sending requests over the network with [`reqwest`].
+#[derive(Debug, Clone)]
+pub struct ReqwestTransport {
+    client: Client,
+    host: Url,
+}
+
+impl ReqwestTransport {
+    /// Creates a transport sending requests to the host with the client.
+    pub fn new(client: Client, host: Url) -> Self {
+        Self { client, host }
+    }
+
+    /// Builds a request for the given method, url, headers and query parameters
+    fn request_builder(&self, request: &OpenAIRequest) -> Result<RequestBuilder, OpenAIError> {
+        let url = self.host.join(&request.url)?;
+        let mut request_builder = self
+            .client
+            .request(request.method.clone(), url)
+            .headers(request.headers.clone());
+
+        if !request.query.is_empty() {
+            request_builder = request_builder.query(&request.query);
+        }
+
+        Ok(request_builder)
+    }
+
+    /// Adds a JSON body, if the request has one
+    fn json_request_builder(&self, request: &OpenAIRequest) -> Result<RequestBuilder, OpenAIError> {
+        let request_builder = self.request_builder(request)?;
+
+        match &request.body {
+            RequestBody::Json(body) => Ok(request_builder.json(body)),
+            _ => Ok(request_builder),
+        }
+    }
+}
+
+#[async_trait]
+impl Transport for ReqwestTransport {
+    async fn send_json(&self, request: &OpenAIRequest) -> Result<Response, OpenAIError> {
+        Ok(self.json_request_builder(request)?.send().await?)
+    }
+
+    async fn send_multipart(&self, request: &OpenAIRequest) -> Result<Response, OpenAIError> {
+        let RequestBody::Multipart(form) = &request.body else {
+            return self.send_json(request).await;
+        };
+
+        // Every attempt gets a freshly built form, since the file streams are consumed on send
+        let form = form.to_form().await?;
+
+        Ok(self
+            .request_builder(request)?
+            .multipart(form)
+            .send()
+            .await?)
+    }
+
+    async fn open_stream(&self, request: &OpenAIRequest) -> Result<Response, OpenAIError> {
+        let request_builder = self
+            .json_request_builder(request)?
+            .header(ACCEPT, HeaderValue::from_static("text/event-stream"));
+
+        Ok(request_builder.send().await?)
+    }
+}
@@ -0,0 +1,399 @@
+use std::{
+    collections::{BTreeMap, HashSet},
+    future::Future,
+    sync::{Arc, Mutex},
+};
+
+use serde::{Deserialize, Serialize};
+
+use super::{ModelId, ModelInfo, ModelRegistry, TokenUsage};
+
+tokio::task_local! {
+    static USAGE_LABEL: String;
+}
+
+type UsageSink = Arc<dyn Fn(&UsageRecord) + Send + Sync>;
+
+/// The usage of a single API call, as recorded by a [`UsageTracker`].
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct UsageRecord {
+    /// The model used by the call
+    pub model: String,
+
+    /// The `user` of the request, if any
+    #[serde(default)]
+    pub user: Option<String>,
+
+    /// The label the call was made under, see [`UsageTracker::labeled`]
+    #[serde(default)]
+    pub label: Option<String>,
+
+    /// Number of tokens in the prompt
+    #[serde(default)]
+    pub prompt_tokens: u64,
+
+    /// Number of tokens in the completion
+    #[serde(default)]
+    pub completion_tokens: u64,
+
+    /// Number of generated images
+    #[serde(default)]
+    pub images: u64,
+
+    /// Seconds of transcribed or translated audio
+    #[serde(default)]
+    pub audio_seconds: f64,
+
+    /// Number of characters converted to speech
+    #[serde(default)]
+    pub tts_characters: u64,
+
+    /// Whether some of the usage of the call is unknown or can't be priced, e.g. the duration of audio
+    /// transcribed without the `verbose_json` format, or images in a non-default size. Incomplete records are not priced.
+    #[serde(default)]
+    pub incomplete: bool,
+
+    /// The cost of the call in USD, `None` if the prices of the model are unknown
+    #[serde(default)]
+    pub cost: Option<f64>,
+}
+
+impl UsageRecord {
+    /// Creates an empty record for the model.
+    pub fn new<M>(model: M) -> Self
+    where
+        M: Into<ModelId>,
+    {
+        Self {
+            model: model.into().into(),
+            ..Default::default()
+        }
+    }
+
+    /// Sets the `user` of the request.
+    pub fn with_user(mut self, user: Option<String>) -> Self {
+        self.user = user;
+        self
+    }
+
+    /// Sets the prompt and completion tokens.
+    pub fn with_tokens(mut self, usage: &TokenUsage) -> Self {
+        self.prompt_tokens = usage.prompt_tokens.max(0) as u64;
+        self.completion_tokens = usage.completion_tokens.max(0) as u64;
+        self
+    }
+
+    /// Sets the number of generated images.
+    pub fn with_images(mut self, images: u64) -> Self {
+        self.images = images;
+        self
+    }
+
+    /// Sets the seconds of transcribed or translated audio.
+    pub fn with_audio_seconds(mut self, audio_seconds: f64) -> Self {
+        self.audio_seconds = audio_seconds;
+        self
+    }
+
+    /// Sets the number of characters converted to speech.
+    pub fn with_tts_characters(mut self, tts_characters: u64) -> Self {
+        self.tts_characters = tts_characters;
+        self
+    }
+
+    /// Marks the usage of the call as partly unknown, leaving the record unpriced.
+    pub fn incomplete(mut self) -> Self {
+        self.incomplete = true;
+        self
+    }
+
+    /// The cost of the record with the prices of the model.
+    /// `None` if a price of the used units is unknown, or the record is [incomplete](UsageRecord::incomplete).
+    pub fn cost_with(&self, info: &ModelInfo) -> Option<f64> {
+        if self.incomplete {
+            return None;
+        }
+
+        fn price(units: f64, price: Option<f64>) -> Option<f64> {
+            if units == 0.0 {
+                return Some(0.0);
+            }
+
+            price.map(|price| units * price)
+        }
+
+        let costs = [
+            price(
+                self.prompt_tokens as f64 / 1_000_000.0,
+                info.input_price_per_million,
+            )?,
+            price(
+                self.completion_tokens as f64 / 1_000_000.0,
+                info.output_price_per_million,
+            )?,
+            price(self.images as f64, info.image_price)?,
+            price(self.audio_seconds / 60.0, info.audio_price_per_minute)?,
+            price(
+                self.tts_characters as f64 / 1_000_000.0,
+                info.character_price_per_million,
+            )?,
+        ];
+
+        Some(costs.iter().sum())
+    }
+}
+
+/// Aggregated usage of a set of API calls.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct UsageTotals {
+    /// Number of recorded calls
+    pub requests: u64,
+
+    /// Number of tokens in the prompts
+    pub prompt_tokens: u64,
+
+    /// Number of tokens in the completions
+    pub completion_tokens: u64,
+
+    /// Number of generated images
+    pub images: u64,
+
+    /// Seconds of transcribed or translated audio
+    pub audio_seconds: f64,
+
+    /// Number of characters converted to speech
+    pub tts_characters: u64,
+
+    /// The cost of the calls with known prices, in USD
+    pub cost: f64,
+
+    /// Number of calls whose cost is unknown, and missing from `cost`
+    pub unpriced_requests: u64,
+}
+
+impl UsageTotals {
+    fn add(&mut self, record: &UsageRecord) {
+        self.requests += 1;
+        self.prompt_tokens += record.prompt_tokens;
+        self.completion_tokens += record.completion_tokens;
+        self.images += record.images;
+        self.audio_seconds += record.audio_seconds;
+        self.tts_characters += record.tts_characters;
+
+        match record.cost {
+            Some(cost) => self.cost += cost,
+            None => self.unpriced_requests += 1,
+        }
+    }
+}
+
+/// The usage recorded by a [`UsageTracker`], in total and broken down by model, user and label.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct UsageSnapshot {
+    /// The usage of all calls
+    pub total: UsageTotals,
+
+    /// The usage per model
+    pub by_model: BTreeMap<String, UsageTotals>,
+
+    /// The usage per `user` of the requests, for requests with a user
+    pub by_user: BTreeMap<String, UsageTotals>,
+
+    /// The usage per label, for calls made under a label
+    pub by_label: BTreeMap<String, UsageTotals>,
+}
+
+impl UsageSnapshot {
+    fn add(&mut self, record: &UsageRecord) {
+        self.total.add(record);
+        self.by_model
+            .entry(record.model.clone())
+            .or_default()
+            .add(record);
+
+        if let Some(user) = &record.user {
+            self.by_user.entry(user.clone()).or_default().add(record);
+        }
+
+        if let Some(label) = &record.label {
+            self.by_label.entry(label.clone()).or_default().add(record);
+        }
+    }
+}
+
+/// Records the tokens, images, audio and speech used by a client, and their cost.
+///
+/// Install it with [`ClientBuilder::usage_tracker`](crate::ClientBuilder::usage_tracker). Chat completions,
+/// completions, embeddings, images, speech, transcriptions and translations are recorded once they succeed.
+/// Streamed completions are recorded from their last chunk, which only carries the usage when
+/// `stream_options.include_usage` is set on the request. Runs are recorded once, from the first response
+/// of the run carrying its usage, which happens when the run ends.
+///
+/// Costs are computed with the prices of a [`ModelRegistry`]: the one set with [`UsageTracker::with_prices`],
+/// or else the [registry of the client](crate::ClientBuilder::model_registry) recording the call.
+/// Calls recorded directly with [`UsageTracker::record`] fall back to the built-in registry. Clones share the recorded usage.
+#[derive(Clone, Default)]
+pub struct UsageTracker {
+    usage: Arc<Mutex<UsageSnapshot>>,
+    recorded_ids: Arc<Mutex<HashSet<String>>>,
+    prices: Option<Arc<ModelRegistry>>,
+    sinks: Vec<UsageSink>,
+}
+
+impl std::fmt::Debug for UsageTracker {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("UsageTracker")
+            .field("usage", &self.usage)
+            .field("prices", &self.prices)
+            .field("sinks", &self.sinks.len())
+            .finish()
+    }
+}
+
+impl UsageTracker {
+    /// Creates a tracker computing costs with the built-in prices.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Computes costs with the prices of the registry.
+    pub fn with_prices(mut self, prices: ModelRegistry) -> Self {
+        self.prices = Some(Arc::new(prices));
+        self
+    }
+
+    /// Adds a sink, called with every recorded call, e.g. to export them to a metrics system.
+    pub fn with_sink<F>(mut self, sink: F) -> Self
+    where
+        F: Fn(&UsageRecord) + Send + Sync + 'static,
+    {
+        self.sinks.push(Arc::new(sink));
+        self
+    }
+
+    /// Runs the future, recording the usage of the calls it makes under the label.
+    /// Labels don't propagate to tasks spawned by the future.
+    pub async fn labeled<S, F>(label: S, future: F) -> F::Output
+    where
+        S: Into<String>,
+        F: Future,
+    {
+        USAGE_LABEL.scope(label.into(), future).await
+    }
+
+    /// Returns the label of the current [labeled](UsageTracker::labeled) scope, if any.
+    pub(crate) fn current_label() -> Option<String> {
+        USAGE_LABEL.try_with(Clone::clone).ok()
+    }
+
+    /// Records a call. The label is set from the current [labeled](UsageTracker::labeled) scope,
+    /// and the cost from the prices, unless they are set already.
+    pub fn record(&self, record: UsageRecord) {
+        self.record_with(record, ModelRegistry::builtin());
+    }
+
+    /// Records a call, computing its cost with the given prices unless the tracker has its own.
+    pub(crate) fn record_with(&self, mut record: UsageRecord, default_prices: &ModelRegistry) {
+        if record.label.is_none() {
+            record.label = Self::current_label();
+        }
+
+        if record.cost.is_none() {
+            let prices = self.prices.as_deref().unwrap_or(default_prices);
+            record.cost = prices
+                .get(record.model.as_str())
+                .and_then(|info| record.cost_with(info));
+        }
+
+        self.usage
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .add(&record);
+
+        for sink in &self.sinks {
+            sink(&record);
+        }
+    }
+
+    /// Records a call, unless a call with the same id was recorded already,
+    /// e.g. for runs, which report their usage every time they are retrieved.
+    pub(crate) fn record_once_with(
+        &self,
+        id: &str,
+        record: UsageRecord,
+        default_prices: &ModelRegistry,
+    ) {
+        let first = self
+            .recorded_ids
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert(id.to_string());
+
+        if first {
+            self.record_with(record, default_prices);
+        }
+    }
+
+    /// Returns the usage recorded so far.
+    pub fn snapshot(&self) -> UsageSnapshot {
+        self.usage.lock().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+
+    /// Returns the usage recorded so far, and starts over.
+    pub fn reset(&self) -> UsageSnapshot {
+        std::mem::take(&mut *self.usage.lock().unwrap_or_else(|e| e.into_inner()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{AudioModel, ChatModel, TtsModel};
+
+    #[tokio::test]
+    async fn aggregates_usage_correctly() {
+        let records = Arc::new(Mutex::new(vec![]));
+        let sink_records = records.clone();
+        let tracker = UsageTracker::new()
+            .with_sink(move |record| sink_records.lock().unwrap().push(record.clone()));
+
+        let usage = TokenUsage {
+            prompt_tokens: 1_000_000,
+            completion_tokens: 100_000,
+            total_tokens: 1_100_000,
+        };
+
+        UsageTracker::labeled("search", async {
+            tracker.record(
+                UsageRecord::new(ChatModel::GPT4oMini)
+                    .with_user(Some("user-1".to_string()))
+                    .with_tokens(&usage),
+            );
+        })
+        .await;
+
+        tracker.record(UsageRecord::new(TtsModel::Tts1).with_tts_characters(100_000));
+        tracker.record(UsageRecord::new("my-model").with_tokens(&usage));
+        tracker.record(UsageRecord::new(AudioModel::Whisper1).incomplete());
+
+        let snapshot = tracker.snapshot();
+
+        assert_eq!(snapshot.total.requests, 4);
+        assert_eq!(snapshot.total.prompt_tokens, 2_000_000);
+        assert_eq!(snapshot.total.unpriced_requests, 2);
+        assert!((snapshot.total.cost - (0.15 + 0.06 + 1.5)).abs() < 1e-9);
+
+        assert_eq!(snapshot.by_label["search"].requests, 1);
+        assert_eq!(snapshot.by_user["user-1"].prompt_tokens, 1_000_000);
+        assert_eq!(snapshot.by_model["tts-1"].tts_characters, 100_000);
+
+        let records = records.lock().unwrap();
+        assert_eq!(records.len(), 4);
+        assert_eq!(records[0].label.as_deref(), Some("search"));
+        assert_eq!(records[2].cost, None);
+        assert_eq!(records[3].cost, None);
+
+        assert_eq!(tracker.reset(), snapshot);
+        assert_eq!(tracker.snapshot(), UsageSnapshot::default());
+    }
+}
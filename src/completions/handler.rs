@@ -0,0 +1,73 @@
+use reqwest::Method;
+
+use crate::{
+    base_client::BaseClient,
+    common::{OpenAIError, OpenAIStream, UsageRecord},
+    OpenAIRequest,
+};
+
+use super::{CompletionResponse, CreateCompletionRequest};
+
+const COMPLETION_URL: &str = "/v1/completions";
+
+/// Legacy completions handler for OpenAI API
+#[derive(Debug, Clone)]
+pub struct CompletionsHandler<'a> {
+    client: &'a BaseClient,
+}
+
+impl<'a> CompletionsHandler<'a> {
+    pub(crate) fn new(client: &'a BaseClient) -> Self {
+        Self { client }
+    }
+
+    /// Creates a completion for the provided prompt and parameters.
+    pub async fn create_completion(
+        &self,
+        request: CreateCompletionRequest,
+    ) -> Result<CompletionResponse, OpenAIError> {
+        let user = request.user.clone();
+        let openai_request =
+            OpenAIRequest::with_body(Method::POST, COMPLETION_URL.to_string(), request)?;
+
+        let response = self.client.send(openai_request).await;
+        let response: CompletionResponse = response?.json().await?;
+
+        if let Some(usage) = &response.usage {
+            self.client.track_usage(
+                UsageRecord::new(response.model.clone())
+                    .with_user(user)
+                    .with_tokens(usage),
+            );
+        }
+
+        Ok(response)
+    }
+
+    /// Creates a completion for the provided prompt and parameters and returns a streaming response.
+    /// `stream` is set on the request.
+    ///
+    /// The usage is recorded from the last chunk, which only carries it when `stream_options.include_usage` is set.
+    pub async fn create_completion_streaming(
+        &self,
+        mut request: CreateCompletionRequest,
+    ) -> Result<OpenAIStream<CompletionResponse>, OpenAIError> {
+        request.stream = Some(true);
+
+        let user = request.user.clone();
+        let openai_request =
+            OpenAIRequest::with_body(Method::POST, COMPLETION_URL.to_string(), request)?;
+
+        self.client
+            .create_stream(openai_request, move |chunk: &CompletionResponse| {
+                let usage = chunk.usage.as_ref()?;
+
+                Some(
+                    UsageRecord::new(chunk.model.clone())
+                        .with_user(user.clone())
+                        .with_tokens(usage),
+                )
+            })
+            .await
+    }
+}
@@ -0,0 +1,11 @@
+//! Given a prompt, the model will return one or more predicted completions along with the probabilities of alternative tokens at each position.
+//! Most developers should use the [Chat Completions API](https://platform.openai.com/docs/guides/text-generation#text-generation-models)
+//! to leverage our best and newest models. Related guide: [Completions](https://platform.openai.com/docs/guides/completions)
+
+mod handler;
+mod request;
+mod response;
+
+pub use self::handler::*;
+pub use self::request::*;
+pub use self::response::*;
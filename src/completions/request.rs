@@ -0,0 +1,263 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::common::CompletionModel;
+
+/// POST https://api.openai.com/v1/completions
+/// Creates a completion for the provided prompt and parameters
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct CreateCompletionRequest {
+    /// ID of the model to use, such as `gpt-3.5-turbo-instruct` or a fine-tuned `davinci-002` or `babbage-002` model.
+    pub model: CompletionModel,
+
+    /// The prompt(s) to generate completions for, encoded as a string, array of strings, array of tokens, or array of token arrays.
+    pub prompt: CompletionPrompt,
+
+    /// Generates `best_of` completions server-side and returns the "best" (the one with the highest log probability per token).
+    /// Results cannot be streamed.
+    ///
+    /// When used with `n`, `best_of` controls the number of candidate completions and `n` specifies how many to return –
+    /// `best_of` must be greater than `n`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub best_of: Option<i32>,
+
+    /// Echo back the prompt in addition to the completion
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub echo: Option<bool>,
+
+    /// Number between -2.0 and 2.0. Positive values penalize new tokens based on their existing frequency in the text so far,
+    /// decreasing the model's likelihood to repeat the same line verbatim.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub frequency_penalty: Option<f32>,
+
+    /// Modify the likelihood of specified tokens appearing in the completion.
+    ///
+    /// Accepts a JSON object that maps tokens (specified by their token ID in the GPT tokenizer) to an associated bias value from -100 to 100.
+    #[serde(skip_serializing_if = "HashMap::is_empty")]
+    pub logit_bias: HashMap<String, i32>,
+
+    /// Include the log probabilities on the `logprobs` most likely output tokens, as well the chosen tokens.
+    /// For example, if `logprobs` is 5, the API will return a list of the 5 most likely tokens. The maximum value is 5.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub logprobs: Option<u8>,
+
+    /// The maximum number of tokens that can be generated in the completion. Defaults to 16.
+    ///
+    /// The token count of your prompt plus `max_tokens` cannot exceed the model's context length.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_tokens: Option<i32>,
+
+    /// How many completions to generate for each prompt.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub n: Option<i64>,
+
+    /// Number between -2.0 and 2.0. Positive values penalize new tokens based on whether they appear in the text so far,
+    /// increasing the model's likelihood to talk about new topics.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub presence_penalty: Option<f32>,
+
+    /// If specified, our system will make a best effort to sample deterministically,
+    /// such that repeated requests with the same `seed` and parameters should return the same result.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub seed: Option<i64>,
+
+    /// Up to 4 sequences where the API will stop generating further tokens. The returned text will not contain the stop sequence.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub stop: Vec<String>,
+
+    /// Whether to stream back partial progress. If set, tokens will be sent as data-only server-sent events
+    /// as they become available, with the stream terminated by a data: [DONE] message
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stream: Option<bool>,
+
+    /// Options for streaming responses. Only set this when `stream` is `true`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stream_options: Option<CompletionStreamOptions>,
+
+    /// The suffix that comes after a completion of inserted text. Only supported for `gpt-3.5-turbo-instruct`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub suffix: Option<String>,
+
+    /// What sampling temperature to use, between 0 and 2.
+    /// Higher values like 0.8 will make the output more random,
+    /// while lower values like 0.2 will make it more focused and deterministic.
+    ///
+    /// We generally recommend altering this or top_p but not both.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub temperature: Option<f32>,
+
+    /// An alternative to sampling with temperature, called nucleus sampling,
+    /// where the model considers the results of the tokens with top_p probability mass.
+    ///
+    /// We generally recommend altering this or temperature but not both.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub top_p: Option<f32>,
+
+    /// A unique identifier representing your end-user, which can help OpenAI to monitor and detect abuse
+    /// [More](https://platform.openai.com/docs/guides/safety-best-practices/end-user-ids)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub user: Option<String>,
+}
+
+impl CreateCompletionRequest {
+    /// Creates a new [`CreateCompletionRequest`] for the model and prompt.
+    pub fn new<M, P>(model: M, prompt: P) -> Self
+    where
+        M: Into<CompletionModel>,
+        P: Into<CompletionPrompt>,
+    {
+        Self {
+            model: model.into(),
+            prompt: prompt.into(),
+            ..Default::default()
+        }
+    }
+
+    /// Sets the maximum number of generated tokens.
+    pub fn set_max_tokens(mut self, max_tokens: i32) -> Self {
+        self.max_tokens = Some(max_tokens);
+        self
+    }
+
+    /// Sets the sampling temperature.
+    pub fn set_temperature(mut self, temperature: f32) -> Self {
+        self.temperature = Some(temperature);
+        self
+    }
+
+    /// Requests the log probabilities of the `logprobs` most likely tokens at each position, at most 5.
+    pub fn set_logprobs(mut self, logprobs: u8) -> Self {
+        self.logprobs = Some(logprobs);
+        self
+    }
+
+    /// Sets the suffix after the inserted text.
+    pub fn set_suffix<S>(mut self, suffix: S) -> Self
+    where
+        S: Into<String>,
+    {
+        self.suffix = Some(suffix.into());
+        self
+    }
+
+    /// Sets the stop sequences.
+    pub fn set_stop<I, S>(mut self, stop: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.stop = stop.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Sets the user.
+    pub fn set_user<S>(mut self, user: S) -> Self
+    where
+        S: Into<String>,
+    {
+        self.user = Some(user.into());
+        self
+    }
+}
+
+/// The prompt(s) of a completion request
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum CompletionPrompt {
+    /// Single text prompt
+    Text(String),
+
+    /// Multiple text prompts
+    Texts(Vec<String>),
+
+    /// Single prompt encoded as tokens
+    Tokens(Vec<u32>),
+
+    /// Multiple prompts encoded as tokens
+    TokenArrays(Vec<Vec<u32>>),
+}
+
+impl Default for CompletionPrompt {
+    fn default() -> Self {
+        Self::Text(String::new())
+    }
+}
+
+impl From<&str> for CompletionPrompt {
+    fn from(value: &str) -> Self {
+        Self::Text(value.to_string())
+    }
+}
+
+impl From<String> for CompletionPrompt {
+    fn from(value: String) -> Self {
+        Self::Text(value)
+    }
+}
+
+impl From<Vec<String>> for CompletionPrompt {
+    fn from(value: Vec<String>) -> Self {
+        Self::Texts(value)
+    }
+}
+
+impl From<Vec<u32>> for CompletionPrompt {
+    fn from(value: Vec<u32>) -> Self {
+        Self::Tokens(value)
+    }
+}
+
+impl From<Vec<Vec<u32>>> for CompletionPrompt {
+    fn from(value: Vec<Vec<u32>>) -> Self {
+        Self::TokenArrays(value)
+    }
+}
+
+/// Options for streaming responses
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct CompletionStreamOptions {
+    /// If set, an additional chunk is streamed before the `data: [DONE]` message, with the token usage
+    /// of the entire request in its `usage` field and an empty `choices` array.
+    pub include_usage: bool,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn serializes_request_correctly() {
+        let request = CreateCompletionRequest::new(
+            "ft:davinci-002:my-org::abc123",
+            vec![vec![1212_u32, 318], vec![257]],
+        )
+        .set_max_tokens(1)
+        .set_logprobs(2)
+        .set_stop(["\n"]);
+
+        let expected = json!({
+            "model": "ft:davinci-002:my-org::abc123",
+            "prompt": [[1212, 318], [257]],
+            "logprobs": 2,
+            "max_tokens": 1,
+            "stop": ["\n"],
+        });
+
+        let serialized = serde_json::to_value(request).unwrap();
+        assert_eq!(serialized, expected);
+
+        let request = CreateCompletionRequest::new(CompletionModel::GPT3_5TurboInstruct, "Say")
+            .set_suffix(" again");
+
+        let expected = json!({
+            "model": "gpt-3.5-turbo-instruct",
+            "prompt": "Say",
+            "suffix": " again",
+        });
+
+        let serialized = serde_json::to_value(request).unwrap();
+        assert_eq!(serialized, expected);
+    }
+}
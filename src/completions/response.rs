@@ -0,0 +1,147 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::common::TokenUsage;
+
+/// Represents a completion response from the API. Streamed chunks have the same shape, without `usage`.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct CompletionResponse {
+    /// A unique identifier for the completion.
+    pub id: String,
+
+    /// The object type, which is always `text_completion`
+    pub object: String,
+
+    /// The Unix timestamp (in seconds) of when the completion was created.
+    pub created: u32,
+
+    /// The model used for completion.
+    pub model: String,
+
+    /// The list of completion choices the model generated for the input prompt.
+    pub choices: Vec<CompletionChoice>,
+
+    /// This fingerprint represents the backend configuration that the model runs with.
+    #[serde(default)]
+    pub system_fingerprint: Option<String>,
+
+    /// Usage statistics for the completion request. Missing from streamed chunks, unless
+    /// `stream_options.include_usage` is set, in the last chunk.
+    #[serde(default)]
+    pub usage: Option<TokenUsage>,
+}
+
+impl CompletionResponse {
+    /// Returns the text of the first choice
+    pub fn first_text(&self) -> Option<&str> {
+        self.choices.first().map(|choice| choice.text.as_str())
+    }
+}
+
+/// A completion choice generated by the model.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct CompletionChoice {
+    /// The generated text
+    pub text: String,
+
+    /// The index of the choice in the list of choices
+    pub index: i32,
+
+    /// Log probability information of the tokens, if `logprobs` was set on the request
+    #[serde(default)]
+    pub logprobs: Option<CompletionLogprobs>,
+
+    /// The reason the model stopped generating tokens. This will be `stop` if the model hit a natural stop point
+    /// or a provided stop sequence, `length` if the maximum number of tokens specified in the request was reached,
+    /// or `content_filter` if content was omitted due to a flag from our content filters. `None` in streamed chunks
+    /// until the last one.
+    #[serde(default)]
+    pub finish_reason: Option<String>,
+}
+
+/// Log probability information of a completion choice.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct CompletionLogprobs {
+    /// The generated tokens, preceded by the prompt tokens with `echo`
+    #[serde(default)]
+    pub tokens: Vec<String>,
+
+    /// The log probabilities of the tokens. `None` for the first token of an echoed prompt.
+    #[serde(default)]
+    pub token_logprobs: Vec<Option<f64>>,
+
+    /// The most likely tokens with their log probabilities at each position
+    #[serde(default)]
+    pub top_logprobs: Vec<Option<HashMap<String, f64>>>,
+
+    /// The offsets of the tokens in the text
+    #[serde(default)]
+    pub text_offset: Vec<u32>,
+}
+
+impl CompletionLogprobs {
+    /// The probabilities of the most likely tokens at the position, most likely first. Useful for classifiers
+    /// whose label is the first generated token.
+    pub fn probabilities_at(&self, position: usize) -> Vec<(&str, f64)> {
+        let mut probabilities: Vec<_> = self
+            .top_logprobs
+            .get(position)
+            .and_then(Option::as_ref)
+            .into_iter()
+            .flatten()
+            .map(|(token, logprob)| (token.as_str(), logprob.exp()))
+            .collect();
+
+        probabilities.sort_by(|a, b| b.1.total_cmp(&a.1));
+        probabilities
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn deserializes_response_correctly() {
+        let json = json!({
+          "id": "cmpl-uqkvlQyYK7bGYrRHQ0eXlWi7",
+          "object": "text_completion",
+          "created": 1589478378,
+          "model": "ft:davinci-002:my-org::abc123",
+          "system_fingerprint": "fp_44709d6fcb",
+          "choices": [
+            {
+              "text": " positive",
+              "index": 0,
+              "logprobs": {
+                "tokens": [" positive"],
+                "token_logprobs": [-0.1],
+                "top_logprobs": [{ " positive": -0.1, " negative": -2.5 }],
+                "text_offset": [7]
+              },
+              "finish_reason": "length"
+            }
+          ],
+          "usage": {
+            "prompt_tokens": 5,
+            "completion_tokens": 1,
+            "total_tokens": 6
+          }
+        });
+
+        let response: CompletionResponse = serde_json::from_value(json).unwrap();
+
+        assert_eq!(response.first_text(), Some(" positive"));
+        assert_eq!(response.usage.as_ref().unwrap().total_tokens, 6);
+
+        let logprobs = response.choices[0].logprobs.as_ref().unwrap();
+        let probabilities = logprobs.probabilities_at(0);
+
+        assert_eq!(probabilities[0].0, " positive");
+        assert!((probabilities[0].1 - (-0.1f64).exp()).abs() < 1e-9);
+        assert_eq!(probabilities[1].0, " negative");
+        assert!(logprobs.probabilities_at(1).is_empty());
+    }
+}
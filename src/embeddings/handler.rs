@@ -1,6 +1,10 @@
 use reqwest::Method;
 
-use crate::{base_client::BaseClient, common::OpenAIError, OpenAIRequest};
+use crate::{
+    base_client::BaseClient,
+    common::{OpenAIError, UsageRecord},
+    OpenAIRequest,
+};
 
 use super::{CreateEmbeddingsRequest, EmbeddingResponse};
 
@@ -22,11 +26,19 @@ impl<'a> EmbeddingsHandler<'a> {
         &self,
         request: CreateEmbeddingsRequest,
     ) -> Result<EmbeddingResponse, OpenAIError> {
+        let user = request.user.clone();
         let openai_request =
-            OpenAIRequest::with_body(Method::POST, EMBEDDING_URL.to_string(), request);
+            OpenAIRequest::with_body(Method::POST, EMBEDDING_URL.to_string(), request)?;
 
         let response = self.client.send(openai_request).await;
+        let response: EmbeddingResponse = response?.json().await?;
 
-        Ok(response?.json().await?)
+        self.client.track_usage(
+            UsageRecord::new(response.model.clone())
+                .with_user(user)
+                .with_tokens(&response.usage),
+        );
+
+        Ok(response)
     }
 }
@@ -1,6 +1,6 @@
 use serde::{Deserialize, Serialize};
 
-use crate::common::EmbeddingModel;
+use crate::common::{EmbeddingModel, ModelId};
 
 /// POST https://api.openai.com/v1/embeddings
 /// Creates an embedding vector representing the input text
@@ -84,8 +84,11 @@ impl CreateEmbeddingsRequest {
     }
 
     /// Sets the embedding model.
-    pub fn set_model(mut self, model: EmbeddingModel) -> Self {
-        self.model = model;
+    pub fn set_model<M>(mut self, model: M) -> Self
+    where
+        M: Into<ModelId>,
+    {
+        self.model = model.into().into();
         self
     }
 
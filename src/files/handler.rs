@@ -1,8 +1,15 @@
+use futures_util::Stream;
 use reqwest::Method;
 
-use crate::{base_client::BaseClient, common::OpenAIError, OpenAIRequest};
+use crate::{
+    base_client::BaseClient,
+    common::{paginate, OpenAIError, PaginatedParams},
+    OpenAIRequest,
+};
 
-use super::{DeleteFileResponse, FilesListResponse, FilesResponse, UploadFileRequest};
+use super::{
+    DeleteFileResponse, FilesListResponse, FilesResponse, ListFilesParams, UploadFileRequest,
+};
 
 const FILES_URL: &str = "/v1/files";
 
@@ -24,33 +31,54 @@ impl<'a> FileHandler<'a> {
     /// The Fine-tuning API only supports `.jsonl` files.
     ///
     /// Please [contact us](https://help.openai.com/) if you need to increase these storage limits.
-    pub async fn upload_file(&self, request: UploadFileRequest) -> Result<(), OpenAIError> {
-        let openai_request = OpenAIRequest::with_form(Method::GET, FILES_URL.to_string(), request);
+    pub async fn upload_file(
+        &self,
+        request: UploadFileRequest,
+    ) -> Result<FilesResponse, OpenAIError> {
+        let openai_request = OpenAIRequest::with_form(Method::POST, FILES_URL.to_string(), request);
 
-        let response = self.client.send_form(openai_request).await;
+        let response = self.client.send(openai_request).await;
 
         Ok(response?.json().await?)
     }
 
     /// Returns a list of files that belong to the user's organization.
-    pub async fn list_files<S: Into<String>>(
+    pub async fn list_files(
         &self,
-        _purpose: S,
+        parameters: ListFilesParams,
     ) -> Result<FilesListResponse, OpenAIError> {
-        let openai_request = OpenAIRequest::<()>::new(Method::POST, FILES_URL.to_string());
+        let openai_request = OpenAIRequest::new(Method::GET, FILES_URL.to_string())
+            .with_query_parameters(parameters);
 
         let response = self.client.send(openai_request).await;
 
         Ok(response?.json().await?)
     }
 
+    /// Streams the files that belong to the user's organization, fetching the pages lazily.
+    /// Stops after `max_items`, if set.
+    pub fn list_files_auto_paginate(
+        &self,
+        parameters: ListFilesParams,
+        max_items: Option<usize>,
+    ) -> impl Stream<Item = Result<FilesResponse, OpenAIError>> + 'a {
+        let client = self.client;
+
+        paginate(max_items, move |after| {
+            let handler = Self::new(client);
+            let parameters = parameters.with_after(after);
+
+            async move { handler.list_files(parameters).await }
+        })
+    }
+
     /// Returns information about a specific file.
     pub async fn retrieve_file<S: Into<String>>(
         &self,
         file_id: S,
     ) -> Result<FilesResponse, OpenAIError> {
         let url = format!("{}/{}", FILES_URL, file_id.into());
-        let openai_request = OpenAIRequest::<()>::new(Method::GET, url);
+        let openai_request = OpenAIRequest::new(Method::GET, url);
 
         let response = self.client.send(openai_request).await;
 
@@ -63,7 +91,7 @@ impl<'a> FileHandler<'a> {
         file_id: S,
     ) -> Result<DeleteFileResponse, OpenAIError> {
         let url = format!("{}/{}", FILES_URL, file_id.into());
-        let openai_request = OpenAIRequest::<()>::new(Method::DELETE, url);
+        let openai_request = OpenAIRequest::new(Method::DELETE, url);
 
         let response = self.client.send(openai_request).await;
 
@@ -76,10 +104,10 @@ impl<'a> FileHandler<'a> {
         file_id: S,
     ) -> Result<String, OpenAIError> {
         let url = format!("{}/{}/content", FILES_URL, file_id.into());
-        let openai_request = OpenAIRequest::<()>::new(Method::GET, url);
+        let openai_request = OpenAIRequest::new(Method::GET, url);
 
         let response = self.client.send(openai_request).await;
 
-        Ok(response?.json().await?)
+        Ok(response?.text().await?)
     }
 }
@@ -1,14 +1,11 @@
+use std::fmt::Display;
+
 use serde::{Deserialize, Serialize};
 
-/// Returns a list of files that belong to the user's organization.
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
-pub struct FilesListResponse {
-    /// The object type, which is always `list`.
-    pub object: String,
+use crate::common::{ListItem, ListResponse};
 
-    /// A list of document that has been uploaded to OpenAI..
-    pub data: Vec<FilesResponse>,
-}
+/// Returns a list of files that belong to the user's organization.
+pub type FilesListResponse = ListResponse<FilesResponse>;
 
 /// Represents a document that has been uploaded to OpenAI.
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -32,24 +29,82 @@ pub struct FilesResponse {
     pub purpose: Purpose,
 }
 
+impl ListItem for FilesResponse {
+    fn id(&self) -> &str {
+        &self.id
+    }
+}
+
 /// The intended purpose of the file.
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum Purpose {
     /// The file is used for fine-tuning.
-    #[serde(rename = "fine-tune")]
     FineTune,
 
     /// The file is used for fine-tuning results.
-    #[serde(rename = "fine-tune-results")]
     FineTuneResults,
 
     /// The file is used for assistants.
-    #[serde(rename = "assistants")]
     Assistants,
 
     /// The file is used for assistants output.
-    #[serde(rename = "assistants-output")]
     AssistantsOutput,
+
+    /// The file is used as input of a batch.
+    Batch,
+
+    /// The file is the output of a batch.
+    BatchOutput,
+
+    /// A purpose not known to this crate
+    Other(String),
+}
+
+impl Purpose {
+    /// The purpose, as used by the API
+    pub fn as_str(&self) -> &str {
+        match self {
+            Purpose::FineTune => "fine-tune",
+            Purpose::FineTuneResults => "fine-tune-results",
+            Purpose::Assistants => "assistants",
+            Purpose::AssistantsOutput => "assistants_output",
+            Purpose::Batch => "batch",
+            Purpose::BatchOutput => "batch_output",
+            Purpose::Other(purpose) => purpose,
+        }
+    }
+}
+
+impl Display for Purpose {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl From<&str> for Purpose {
+    fn from(purpose: &str) -> Self {
+        match purpose {
+            "fine-tune" => Purpose::FineTune,
+            "fine-tune-results" => Purpose::FineTuneResults,
+            "assistants" => Purpose::Assistants,
+            "assistants_output" => Purpose::AssistantsOutput,
+            "batch" => Purpose::Batch,
+            "batch_output" => Purpose::BatchOutput,
+            purpose => Purpose::Other(purpose.to_string()),
+        }
+    }
+}
+
+impl Serialize for Purpose {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl<'de> Deserialize<'de> for Purpose {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        String::deserialize(deserializer).map(|purpose| Purpose::from(purpose.as_str()))
+    }
 }
 
 #[cfg(test)]
@@ -103,8 +158,22 @@ mod tests {
                     purpose: Purpose::Assistants,
                 },
             ],
+            ..Default::default()
         };
 
         assert_eq!(response, expectation);
     }
+
+    #[test]
+    fn converts_purpose_correctly() {
+        assert_eq!(Purpose::AssistantsOutput.to_string(), "assistants_output");
+        assert_eq!(
+            serde_json::to_value(Purpose::FineTuneResults).unwrap(),
+            json!("fine-tune-results")
+        );
+
+        let purpose: Purpose = serde_json::from_value(json!("user_data")).unwrap();
+        assert_eq!(purpose, Purpose::Other("user_data".to_string()));
+        assert_eq!(purpose.to_string(), "user_data");
+    }
 }
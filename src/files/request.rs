@@ -1,7 +1,8 @@
-use reqwest::Body;
-use tokio_util::codec::BytesCodec;
+use crate::common::{
+    push_query, CursorParams, MultipartForm, OpenAIFile, Order, PaginatedParams, QueryParameters,
+};
 
-use crate::common::{OpenAIError, OpenAIFile};
+use super::Purpose;
 
 /// Request to upload a file to the OpenAI API.
 #[derive(Debug)]
@@ -11,28 +12,79 @@ pub struct UploadFileRequest {
 
     /// The intended purpose of the uploaded file.
     ///
-    /// Use "fine-tune" for [Fine-tuning](https://platform.openai.com/docs/api-reference/fine-tuning)
-    /// and "assistants" for [Assistants](https://platform.openai.com/docs/api-reference/assistants)
-    /// and [Messages](https://platform.openai.com/docs/api-reference/messages).
+    /// Use [`Purpose::FineTune`] for [Fine-tuning](https://platform.openai.com/docs/api-reference/fine-tuning)
+    /// and [`Purpose::Assistants`] for [Assistants](https://platform.openai.com/docs/api-reference/assistants)
+    /// and [Messages](https://platform.openai.com/docs/api-reference/messages),
+    /// and [`Purpose::Batch`] for the [Batch API](https://platform.openai.com/docs/api-reference/batch).
     /// This allows us to validate the format of the uploaded file is correct for fine-tuning.
-    pub purpose: String,
+    pub purpose: Purpose,
 }
 
-impl TryFrom<UploadFileRequest> for reqwest::multipart::Form {
-    type Error = OpenAIError;
+impl From<UploadFileRequest> for MultipartForm {
+    fn from(request: UploadFileRequest) -> Self {
+        MultipartForm::new()
+            .file("file", request.file)
+            .text("purpose", request.purpose.as_str())
+    }
+}
+
+/// Query parameters to list the files of the organization.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct ListFilesParams {
+    /// The pagination of the list. The limit can range between 1 and 10,000, and the default is 10,000.
+    pub cursor: CursorParams,
+
+    /// Sort order by the created_at timestamp of the objects.
+    pub order: Option<Order>,
+
+    /// Only return files with the given purpose.
+    pub purpose: Option<Purpose>,
+}
+
+impl ListFilesParams {
+    /// Create new list parameters.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the after ID for pagination.
+    pub fn after<S: Into<String>>(mut self, after: S) -> Self {
+        self.cursor = self.cursor.after(after);
+        self
+    }
 
-    fn try_from(request: UploadFileRequest) -> Result<Self, Self::Error> {
-        let file_name = request.file.name.to_owned();
-        let file_body = Body::wrap_stream(request.file.into_stream(BytesCodec::new()));
+    /// Set a limit on the number of objects to be returned.
+    pub fn limit(mut self, limit: u32) -> Self {
+        self.cursor = self.cursor.limit(limit);
+        self
+    }
+
+    /// Set the sort order by the created_at timestamp of the objects.
+    pub fn order(mut self, order: Order) -> Self {
+        self.order = Some(order);
+        self
+    }
+
+    /// Only return files with the given purpose.
+    pub fn purpose(mut self, purpose: Purpose) -> Self {
+        self.purpose = Some(purpose);
+        self
+    }
+}
+
+impl PaginatedParams for ListFilesParams {
+    fn after_mut(&mut self) -> &mut Option<String> {
+        self.cursor.after_mut()
+    }
+}
 
-        let file_part = reqwest::multipart::Part::stream(file_body)
-            .file_name(file_name)
-            .mime_str("application/octet-stream")?;
+impl QueryParameters for ListFilesParams {
+    fn to_query(&self) -> Vec<(String, String)> {
+        let mut query = self.cursor.to_query();
 
-        let form = reqwest::multipart::Form::new()
-            .part("file", file_part)
-            .text("purpose", request.purpose);
+        push_query(&mut query, "order", self.order.as_ref());
+        push_query(&mut query, "purpose", self.purpose.as_ref());
 
-        Ok(form)
+        query
     }
 }
@@ -1,10 +1,15 @@
+use futures_util::Stream;
 use reqwest::Method;
 
-use crate::{base_client::BaseClient, common::OpenAIError, OpenAIQueryParameters, OpenAIRequest};
+use crate::{
+    base_client::BaseClient,
+    common::{paginate, CursorParams, OpenAIError, PaginatedParams},
+    OpenAIRequest,
+};
 
 use super::{
-    CreateFineTunningJobRequest, FineTuningJobResponse, ListFineTuningJobResponse,
-    ListFineTunningJobEventResponse,
+    CreateFineTunningJobRequest, FineTuningJobEventResponse, FineTuningJobResponse,
+    ListFineTuningJobResponse, ListFineTunningJobEventResponse,
 };
 
 const FINE_TUNNING_URL: &str = "/v1/fine_tuning/jobs";
@@ -28,7 +33,7 @@ impl<'a> FineTuningHandler<'a> {
         request: CreateFineTunningJobRequest,
     ) -> Result<FineTuningJobResponse, OpenAIError> {
         let openai_request =
-            OpenAIRequest::with_body(Method::POST, FINE_TUNNING_URL.to_string(), request);
+            OpenAIRequest::with_body(Method::POST, FINE_TUNNING_URL.to_string(), request)?;
 
         let response = self.client.send(openai_request).await;
 
@@ -36,11 +41,11 @@ impl<'a> FineTuningHandler<'a> {
     }
 
     /// List your organization's fine-tuning jobs
-    pub async fn list_fine_tunning_jobs<S: Into<String>>(
+    pub async fn list_fine_tunning_jobs(
         &self,
-        parameters: OpenAIQueryParameters,
+        parameters: CursorParams,
     ) -> Result<ListFineTuningJobResponse, OpenAIError> {
-        let openai_request = OpenAIRequest::<()>::new(Method::GET, FINE_TUNNING_URL.to_string())
+        let openai_request = OpenAIRequest::new(Method::GET, FINE_TUNNING_URL.to_string())
             .with_query_parameters(parameters);
 
         let response = self.client.send(openai_request).await;
@@ -48,28 +53,68 @@ impl<'a> FineTuningHandler<'a> {
         Ok(response?.json().await?)
     }
 
+    /// Streams your organization's fine-tuning jobs, fetching the pages lazily.
+    /// Stops after `max_items`, if set.
+    pub fn list_fine_tunning_jobs_auto_paginate(
+        &self,
+        parameters: CursorParams,
+        max_items: Option<usize>,
+    ) -> impl Stream<Item = Result<FineTuningJobResponse, OpenAIError>> + 'a {
+        let client = self.client;
+
+        paginate(max_items, move |after| {
+            let handler = Self::new(client);
+            let parameters = parameters.with_after(after);
+
+            async move { handler.list_fine_tunning_jobs(parameters).await }
+        })
+    }
+
     /// Get status updates for a fine-tuning job.
     pub async fn list_fine_tunning_job_events<S: Into<String>>(
         &self,
         job_id: S,
-        parameters: OpenAIQueryParameters,
+        parameters: CursorParams,
     ) -> Result<ListFineTunningJobEventResponse, OpenAIError> {
         let url = format!("{}/{}/events", FINE_TUNNING_URL, job_id.into());
-        let openai_request =
-            OpenAIRequest::<()>::new(Method::GET, url).with_query_parameters(parameters);
+        let openai_request = OpenAIRequest::new(Method::GET, url).with_query_parameters(parameters);
 
         let response = self.client.send(openai_request).await;
 
         Ok(response?.json().await?)
     }
 
+    /// Streams the events of a fine-tuning job, fetching the pages lazily.
+    /// Stops after `max_items`, if set.
+    pub fn list_fine_tunning_job_events_auto_paginate<S: Into<String>>(
+        &self,
+        job_id: S,
+        parameters: CursorParams,
+        max_items: Option<usize>,
+    ) -> impl Stream<Item = Result<FineTuningJobEventResponse, OpenAIError>> + 'a {
+        let client = self.client;
+        let job_id = job_id.into();
+
+        paginate(max_items, move |after| {
+            let handler = Self::new(client);
+            let job_id = job_id.clone();
+            let parameters = parameters.with_after(after);
+
+            async move {
+                handler
+                    .list_fine_tunning_job_events(job_id, parameters)
+                    .await
+            }
+        })
+    }
+
     /// Get info about a fine-tuning job.
     pub async fn retrieve_fine_tunning_job<S: Into<String>>(
         &self,
         job_id: S,
     ) -> Result<FineTuningJobResponse, OpenAIError> {
         let url = format!("{}/{}", FINE_TUNNING_URL, job_id.into());
-        let openai_request = OpenAIRequest::<()>::new(Method::GET, url);
+        let openai_request = OpenAIRequest::new(Method::GET, url);
 
         let response = self.client.send(openai_request).await;
 
@@ -82,7 +127,7 @@ impl<'a> FineTuningHandler<'a> {
         job_id: S,
     ) -> Result<FineTuningJobResponse, OpenAIError> {
         let url = format!("{}/{}/cancel", FINE_TUNNING_URL, job_id.into());
-        let openai_request = OpenAIRequest::<()>::new(Method::POST, url);
+        let openai_request = OpenAIRequest::new(Method::POST, url);
 
         let response = self.client.send(openai_request).await;
 
@@ -1,14 +1,9 @@
 use serde::{Deserialize, Serialize};
 
-/// Get status updates for a fine-tuning job.
-#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
-pub struct ListFineTunningJobEventResponse {
-    /// The object type, which is always "list".
-    pub object: String,
+use crate::common::{ListItem, ListResponse};
 
-    /// A list of fine-tuning job events.
-    pub data: Vec<FineTuningJobEventResponse>,
-}
+/// Get status updates for a fine-tuning job.
+pub type ListFineTunningJobEventResponse = ListResponse<FineTuningJobEventResponse>;
 
 /// Fine tunning job event message
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
@@ -29,6 +24,12 @@ pub struct FineTuningJobEventResponse {
     pub object: String,
 }
 
+impl ListItem for FineTuningJobEventResponse {
+    fn id(&self) -> &str {
+        &self.id
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
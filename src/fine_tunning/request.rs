@@ -1,5 +1,7 @@
 use serde::{Deserialize, Serialize};
 
+use crate::common::model_enum;
+
 /// Creates a job that fine-tunes a specified model from a given dataset.
 ///
 /// Response includes details of the enqueued job including job status and the name of the fine-tuned models once complete.
@@ -52,28 +54,39 @@ pub struct RequestHyperparameters {
     pub n_epochs: Option<u32>,
 }
 
-/// Models supported for fine tunning
-#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
-pub enum SupportedModels {
-    /// /// The latest GPT-3.5 Turbo model with improved instruction following, JSON mode, reproducible outputs, parallel function calling, and more.
-    #[serde(rename = "gpt-3.5-turbo-1106")]
-    GPT3_5Turbo1106,
-
-    /// Snapshot of gpt-3.5-turbo from June 13th 2023. Will be deprecated
-    #[serde(rename = "gpt-3.5-turbo-0613")]
-    GPT3_5Turbo0613,
-
-    /// Conversational base model
-    #[serde(rename = "babbage-002")]
-    Babbage002,
-
-    /// Conversational base model
-    #[serde(rename = "davinci-002")]
-    Davinci002,
-
-    /// Snapshot of gpt-4 from June 13th 2023 with improved function calling support.
-    #[serde(rename = "gpt-4-0613")]
-    GPT4_0613,
+model_enum! {
+    /// Models supported for fine tunning
+    pub enum SupportedModels {
+        /// Snapshot of gpt-4.1 from April 14th 2025.
+        GPT4_1_2025_04_14 => "gpt-4.1-2025-04-14",
+
+        /// Snapshot of gpt-4.1-mini from April 14th 2025.
+        GPT4_1Mini2025_04_14 => "gpt-4.1-mini-2025-04-14",
+
+        /// Snapshot of gpt-4o from August 6th 2024.
+        GPT4o2024_08_06 => "gpt-4o-2024-08-06",
+
+        /// Snapshot of gpt-4o-mini from July 18th 2024.
+        GPT4oMini2024_07_18 => "gpt-4o-mini-2024-07-18",
+
+        /// The latest GPT-3.5 Turbo model with higher accuracy at responding in requested formats.
+        GPT3_5Turbo0125 => "gpt-3.5-turbo-0125",
+
+        /// The latest GPT-3.5 Turbo model with improved instruction following, JSON mode, reproducible outputs, parallel function calling, and more.
+        GPT3_5Turbo1106 => "gpt-3.5-turbo-1106",
+
+        /// Snapshot of gpt-3.5-turbo from June 13th 2023. Will be deprecated
+        GPT3_5Turbo0613 => "gpt-3.5-turbo-0613",
+
+        /// Conversational base model
+        Babbage002 => "babbage-002",
+
+        /// Conversational base model
+        Davinci002 => "davinci-002",
+
+        /// Snapshot of gpt-4 from June 13th 2023 with improved function calling support.
+        GPT4_0613 => "gpt-4-0613",
+    }
 }
 
 #[cfg(test)]
@@ -1,16 +1,11 @@
 use serde::{Deserialize, Serialize};
 
+use crate::common::{ListItem, ListResponse};
+
 use super::Status;
 
 /// List your organization's fine-tuning jobs
-#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
-pub struct ListFineTuningJobResponse {
-    /// The object type, which is always "list".
-    pub object: String,
-
-    /// A list of fine-tuning jobs.
-    pub data: Vec<FineTuningJobResponse>,
-}
+pub type ListFineTuningJobResponse = ListResponse<FineTuningJobResponse>;
 
 /// The `fine_tuning.job` object represents a fine-tuning job that has been created through the API.
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
@@ -62,6 +57,12 @@ pub struct FineTuningJobResponse {
     pub validation_file: Option<String>,
 }
 
+impl ListItem for FineTuningJobResponse {
+    fn id(&self) -> &str {
+        &self.id
+    }
+}
+
 /// Information about why the fine-tuning job failed.
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct FineTunningError {
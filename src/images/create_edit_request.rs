@@ -1,7 +1,4 @@
-use reqwest::Body;
-use tokio_util::codec::BytesCodec;
-
-use crate::common::{ImageGenerationModel, OpenAIError, OpenAIFile};
+use crate::common::{ImageGenerationModel, MultipartForm, OpenAIFile};
 
 use super::{ImageResponseFormat, ImageSize};
 
@@ -34,30 +31,14 @@ pub struct CreateImageEditRequest {
     pub user: Option<String>,
 }
 
-impl TryFrom<CreateImageEditRequest> for reqwest::multipart::Form {
-    type Error = OpenAIError;
-
-    fn try_from(request: CreateImageEditRequest) -> Result<Self, Self::Error> {
-        let file_name = request.image.name.to_owned();
-        let file_body = Body::wrap_stream(request.image.into_stream(BytesCodec::new()));
-
-        let file_part = reqwest::multipart::Part::stream(file_body)
-            .file_name(file_name)
-            .mime_str("application/octet-stream")?;
-
-        let mut form = reqwest::multipart::Form::new()
-            .part("file", file_part)
+impl From<CreateImageEditRequest> for MultipartForm {
+    fn from(request: CreateImageEditRequest) -> Self {
+        let mut form = MultipartForm::new()
+            .file("file", request.image)
             .text("prompt", request.prompt);
 
         if let Some(mask) = request.mask {
-            let file_name = mask.name.to_owned();
-            let file_body = Body::wrap_stream(mask.into_stream(BytesCodec::new()));
-
-            let file_part = reqwest::multipart::Part::stream(file_body)
-                .file_name(file_name)
-                .mime_str("application/octet-stream")?;
-
-            form = form.part("mask", file_part);
+            form = form.file("mask", mask);
         }
 
         if let Some(number_of_variations) = request.number_of_variations {
@@ -76,6 +57,6 @@ impl TryFrom<CreateImageEditRequest> for reqwest::multipart::Form {
             form = form.text("user", user);
         }
 
-        Ok(form)
+        form
     }
 }
@@ -1,7 +1,4 @@
-use reqwest::Body;
-use tokio_util::codec::BytesCodec;
-
-use crate::common::{ImageGenerationModel, OpenAIError, OpenAIFile};
+use crate::common::{ImageGenerationModel, MultipartForm, OpenAIFile};
 
 use super::{ImageResponseFormat, ImageSize};
 
@@ -28,18 +25,9 @@ pub struct CreateImageVariationRequest {
     pub user: Option<String>,
 }
 
-impl TryFrom<CreateImageVariationRequest> for reqwest::multipart::Form {
-    type Error = OpenAIError;
-
-    fn try_from(request: CreateImageVariationRequest) -> Result<Self, Self::Error> {
-        let file_name = request.image.name.to_owned();
-        let file_body = Body::wrap_stream(request.image.into_stream(BytesCodec::new()));
-
-        let file_part = reqwest::multipart::Part::stream(file_body)
-            .file_name(file_name)
-            .mime_str("application/octet-stream")?;
-
-        let mut form = reqwest::multipart::Form::new().part("file", file_part);
+impl From<CreateImageVariationRequest> for MultipartForm {
+    fn from(request: CreateImageVariationRequest) -> Self {
+        let mut form = MultipartForm::new().file("file", request.image);
 
         if let Some(number_of_variations) = request.number_of_variations {
             form = form.text("n", number_of_variations.to_string());
@@ -57,6 +45,6 @@ impl TryFrom<CreateImageVariationRequest> for reqwest::multipart::Form {
             form = form.text("user", user);
         }
 
-        Ok(form)
+        form
     }
 }
@@ -1,9 +1,14 @@
 use reqwest::Method;
 
-use crate::{base_client::BaseClient, common::OpenAIError, OpenAIRequest};
+use crate::{
+    base_client::BaseClient,
+    common::{ImageGenerationModel, OpenAIError, UsageRecord},
+    OpenAIRequest,
+};
 
 use super::{
     CreateImageEditRequest, CreateImageRequest, CreateImageVariationRequest, ImageResponse,
+    ImageSize,
 };
 
 const IMAGES_GENERATION_URL: &str = "/v1/images/generations";
@@ -26,12 +31,22 @@ impl<'a> ImagesHandler<'a> {
         &self,
         request: CreateImageRequest,
     ) -> Result<ImageResponse, OpenAIError> {
+        let usage = image_usage(
+            &request.model,
+            request.number_of_variations,
+            &request.size,
+            request.quality.as_deref(),
+            &request.user,
+        );
         let openai_request =
-            OpenAIRequest::with_body(Method::POST, IMAGES_GENERATION_URL.to_string(), request);
+            OpenAIRequest::with_body(Method::POST, IMAGES_GENERATION_URL.to_string(), request)?;
 
         let response = self.client.send(openai_request).await;
+        let response = response?.json().await?;
+
+        self.client.track_usage(usage);
 
-        Ok(response?.json().await?)
+        Ok(response)
     }
 
     /// Creates an edited or extended image given an original image and a prompt.
@@ -39,12 +54,22 @@ impl<'a> ImagesHandler<'a> {
         &self,
         request: CreateImageEditRequest,
     ) -> Result<ImageResponse, OpenAIError> {
+        let usage = image_usage(
+            &request.model,
+            request.number_of_variations,
+            &request.size,
+            None,
+            &request.user,
+        );
         let openai_request =
             OpenAIRequest::with_form(Method::POST, IMAGES_EDIT_IMAGES_URL.to_string(), request);
 
-        let response = self.client.send_form(openai_request).await;
+        let response = self.client.send(openai_request).await;
+        let response = response?.json().await?;
+
+        self.client.track_usage(usage);
 
-        Ok(response?.json().await?)
+        Ok(response)
     }
 
     /// Creates a variation of a given image.
@@ -52,11 +77,46 @@ impl<'a> ImagesHandler<'a> {
         &self,
         request: CreateImageVariationRequest,
     ) -> Result<ImageResponse, OpenAIError> {
+        let usage = image_usage(
+            &request.model,
+            request.number_of_variations,
+            &request.size,
+            None,
+            &request.user,
+        );
         let openai_request =
             OpenAIRequest::with_form(Method::POST, IMAGES_VARIATIONS_URL.to_string(), request);
 
-        let response = self.client.send_form(openai_request).await;
+        let response = self.client.send(openai_request).await;
+        let response = response?.json().await?;
+
+        self.client.track_usage(usage);
+
+        Ok(response)
+    }
+}
+
+/// The usage of an images request, which generates one image unless more are requested.
+///
+/// Image prices are those of `1024x1024` images in `standard` quality, so other sizes and qualities
+/// are recorded as incomplete, and left unpriced.
+fn image_usage(
+    model: &ImageGenerationModel,
+    number_of_images: Option<usize>,
+    size: &Option<ImageSize>,
+    quality: Option<&str>,
+    user: &Option<String>,
+) -> UsageRecord {
+    let record = UsageRecord::new(model.clone())
+        .with_user(user.clone())
+        .with_images(number_of_images.unwrap_or(1) as u64);
+
+    let default_size = matches!(size, None | Some(ImageSize::S1024x1024));
+    let default_quality = matches!(quality, None | Some("standard"));
 
-        Ok(response?.json().await?)
+    if default_size && default_quality {
+        record
+    } else {
+        record.incomplete()
     }
 }
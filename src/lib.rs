@@ -46,12 +46,21 @@ pub use self::client::*;
 pub use self::client_builder::*;
 pub use self::common::*;
 
+#[cfg(feature = "schema")]
+pub use schemars;
+
 #[cfg(feature = "audio")]
 pub mod audio;
 
+#[cfg(feature = "batches")]
+pub mod batches;
+
 #[cfg(feature = "chat")]
 pub mod chat;
 
+#[cfg(feature = "completions")]
+pub mod completions;
+
 #[cfg(feature = "embeddings")]
 pub mod embeddings;
 
@@ -70,6 +79,9 @@ pub mod models;
 #[cfg(feature = "moderations")]
 pub mod moderations;
 
+#[cfg(feature = "tokenizer")]
+pub mod tokenizer;
+
 #[cfg(feature = "assistants")]
 pub use self::beta::assistants;
 
@@ -2,7 +2,7 @@ use reqwest::Method;
 
 use crate::{base_client::BaseClient, common::OpenAIError, OpenAIRequest};
 
-use super::{ListResponse, ModelObjectResponse};
+use super::{ListModelsResponse, ModelObjectResponse};
 
 const MODEL_URL: &str = "/v1/models";
 
@@ -19,8 +19,8 @@ impl<'a> ModelsHandler<'a> {
     }
 
     /// Lists the currently available models, and provides basic information about each one such as the owner and availability.
-    pub async fn list_models(&self) -> Result<ListResponse, OpenAIError> {
-        let openai_request = OpenAIRequest::<()>::new(Method::GET, MODEL_URL.to_string());
+    pub async fn list_models(&self) -> Result<ListModelsResponse, OpenAIError> {
+        let openai_request = OpenAIRequest::new(Method::GET, MODEL_URL.to_string());
 
         let response = self.client.send(openai_request).await;
 
@@ -33,7 +33,7 @@ impl<'a> ModelsHandler<'a> {
         model: S,
     ) -> Result<ModelObjectResponse, OpenAIError> {
         let url = format!("{}/{}", MODEL_URL, model.into());
-        let openai_request = OpenAIRequest::<()>::new(Method::GET, url);
+        let openai_request = OpenAIRequest::new(Method::GET, url);
 
         let response = self.client.send(openai_request).await;
 
@@ -46,7 +46,7 @@ impl<'a> ModelsHandler<'a> {
         model: S,
     ) -> Result<(), OpenAIError> {
         let url = format!("{}/{}", MODEL_URL, model.into());
-        let openai_request = OpenAIRequest::<()>::new(Method::DELETE, url);
+        let openai_request = OpenAIRequest::new(Method::DELETE, url);
 
         let _ = self.client.send(openai_request).await;
 
@@ -1,14 +1,9 @@
 use serde::{Deserialize, Serialize};
 
-/// Lists the currently available models, and provides basic information about each one such as the owner and availability.
-#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
-pub struct ListResponse {
-    /// The object type, which is always "list".
-    pub object: String,
+use crate::common::ListItem;
 
-    /// A list of model data.
-    pub data: Vec<ModelObjectResponse>,
-}
+/// Lists the currently available models, and provides basic information about each one such as the owner and availability.
+pub type ListModelsResponse = crate::common::ListResponse<ModelObjectResponse>;
 
 /// Describes an OpenAI model offering that can be used with the API.
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
@@ -26,6 +21,12 @@ pub struct ModelObjectResponse {
     pub owned_by: String,
 }
 
+impl ListItem for ModelObjectResponse {
+    fn id(&self) -> &str {
+        &self.id
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -57,9 +58,9 @@ mod tests {
           ],
         });
 
-        let response: ListResponse = serde_json::from_value(json).unwrap();
+        let response: ListModelsResponse = serde_json::from_value(json).unwrap();
 
-        let expectation = ListResponse {
+        let expectation = ListModelsResponse {
             object: "list".into(),
             data: vec![
                 ModelObjectResponse {
@@ -81,6 +82,7 @@ mod tests {
                     owned_by: "openai".into(),
                 },
             ],
+            ..Default::default()
         };
 
         assert_eq!(response, expectation)
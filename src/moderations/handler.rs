@@ -24,7 +24,7 @@ impl<'a> ModerationsHandler<'a> {
         request: CreateModerationRequest,
     ) -> Result<CreateResponse, OpenAIError> {
         let openai_request =
-            OpenAIRequest::with_body(Method::POST, MODERATION_URL.to_string(), request);
+            OpenAIRequest::with_body(Method::POST, MODERATION_URL.to_string(), request)?;
 
         let response = self.client.send(openai_request).await;
 
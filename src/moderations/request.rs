@@ -1,6 +1,6 @@
 use serde::{Deserialize, Serialize};
 
-use crate::common::ModerationModel;
+use crate::common::{ModelId, ModerationModel};
 
 /// Request for [`super::ModerationHandler::create`]
 #[derive(Debug, Default, Clone, Serialize, Deserialize)]
@@ -30,8 +30,11 @@ impl CreateModerationRequest {
     }
 
     /// Sets models
-    pub fn set_model(mut self, model: ModerationModel) -> Self {
-        self.model = model;
+    pub fn set_model<M>(mut self, model: M) -> Self
+    where
+        M: Into<ModelId>,
+    {
+        self.model = model.into().into();
         self
     }
 }
@@ -0,0 +1,275 @@
+use serde_json::Value;
+
+use crate::{
+    chat::{
+        ChatContentPart, ChatMessageContent, ChatRequestMessage, CreateChatCompletionRequest,
+        ImageDetail, Tool,
+    },
+    common::OpenAIError,
+};
+
+use super::{Encoding, Tokenizer};
+
+/// Tokens of an image sent with `low` detail
+const LOW_DETAIL_IMAGE_TOKENS: usize = 85;
+
+/// Tokens of an image sent with `high` or `auto` detail, assuming a 1024x1024 image.
+/// The real number depends on the size of the image, which isn't known without decoding it.
+const HIGH_DETAIL_IMAGE_TOKENS: usize = 765;
+
+/// Counts the prompt tokens of a chat completion request, with the tokenizer of its model.
+///
+/// Follows the [rules OpenAI documents](https://cookbook.openai.com/examples/how_to_count_tokens_with_tiktoken)
+/// for the overhead of messages and tools. The count is exact for text messages, and an estimate for
+/// tools and images. Audio is not counted.
+pub fn count_tokens(request: &CreateChatCompletionRequest) -> Result<usize, OpenAIError> {
+    let tokenizer = Tokenizer::for_model(&request.model)?;
+
+    Ok(count_tokens_with(&tokenizer, request))
+}
+
+/// Counts the prompt tokens of a chat completion request with the given tokenizer, see [`count_tokens`].
+pub fn count_tokens_with(tokenizer: &Tokenizer, request: &CreateChatCompletionRequest) -> usize {
+    let overhead = MessageOverhead::for_model(&request.model);
+
+    let messages = request
+        .messages
+        .iter()
+        .map(|message| count_message(tokenizer, &overhead, message))
+        .sum::<usize>();
+
+    // Every reply is primed with <|start|>assistant<|message|>
+    messages + 3 + count_tools(tokenizer, &request.tools)
+}
+
+/// Tokens added to every message, and to messages with a name
+struct MessageOverhead {
+    per_message: usize,
+    per_name: isize,
+}
+
+impl MessageOverhead {
+    fn for_model(model: &str) -> Self {
+        if model.starts_with("gpt-3.5-turbo-0301") {
+            // Every message follows <|start|>{role/name}\n{content}<|end|>\n, and the role is omitted with a name
+            Self {
+                per_message: 4,
+                per_name: -1,
+            }
+        } else {
+            Self {
+                per_message: 3,
+                per_name: 1,
+            }
+        }
+    }
+}
+
+fn count_message(
+    tokenizer: &Tokenizer,
+    overhead: &MessageOverhead,
+    message: &ChatRequestMessage,
+) -> usize {
+    let mut tokens = overhead.per_message
+        + tokenizer.count(&message.role.to_string().to_lowercase())
+        + message
+            .content
+            .as_ref()
+            .map_or(0, |content| count_content(tokenizer, content));
+
+    if let Some(refusal) = &message.refusal {
+        tokens += tokenizer.count(refusal);
+    }
+
+    if let Some(name) = &message.name {
+        tokens = (tokens + tokenizer.count(name)).saturating_add_signed(overhead.per_name);
+    }
+
+    if let Some(tool_call_id) = &message.tool_call_id {
+        tokens += tokenizer.count(tool_call_id);
+    }
+
+    for tool_call in &message.tool_calls {
+        tokens += tokenizer.count(&tool_call.function.name)
+            + tokenizer.count(&tool_call.function.arguments);
+    }
+
+    tokens
+}
+
+fn count_content(tokenizer: &Tokenizer, content: &ChatMessageContent) -> usize {
+    match content {
+        ChatMessageContent::Text(text) => tokenizer.count(text),
+        ChatMessageContent::Parts(parts) => parts
+            .iter()
+            .map(|part| match part {
+                ChatContentPart::Text { text } => tokenizer.count(text),
+                ChatContentPart::ImageUrl { image_url } => match image_url.detail {
+                    Some(ImageDetail::Low) => LOW_DETAIL_IMAGE_TOKENS,
+                    _ => HIGH_DETAIL_IMAGE_TOKENS,
+                },
+                ChatContentPart::InputAudio { .. } => 0,
+            })
+            .sum(),
+    }
+}
+
+/// Counts the tokens of the tool definitions, which OpenAI renders into the system prompt
+fn count_tools(tokenizer: &Tokenizer, tools: &[Tool]) -> usize {
+    const PROPERTIES_INIT: usize = 3;
+    const PROPERTY_KEY: usize = 3;
+    const ENUM_ITEM: usize = 3;
+    const FUNCTIONS_END: usize = 12;
+
+    if tools.is_empty() {
+        return 0;
+    }
+
+    // Models using o200k_base render the start of a function with fewer tokens
+    let function_init = match tokenizer.encoding() {
+        Encoding::O200kBase => 7,
+        _ => 10,
+    };
+    let mut tokens = 0;
+
+    for tool in tools {
+        let function = &tool.function;
+        let description = function.description.as_deref().unwrap_or_default();
+
+        tokens += function_init
+            + tokenizer.count(&format!(
+                "{}:{}",
+                function.name,
+                description.trim_end_matches('.')
+            ));
+
+        let Some(properties) = function
+            .parameters
+            .get("properties")
+            .and_then(Value::as_object)
+            .filter(|properties| !properties.is_empty())
+        else {
+            continue;
+        };
+
+        tokens += PROPERTIES_INIT;
+
+        for (name, property) in properties {
+            let property_type = match property.get("type") {
+                Some(Value::String(property_type)) => property_type.clone(),
+                Some(property_type) => property_type.to_string(),
+                None => String::new(),
+            };
+            let description = property
+                .get("description")
+                .and_then(Value::as_str)
+                .unwrap_or_default();
+
+            tokens += PROPERTY_KEY
+                + tokenizer.count(&format!(
+                    "{}:{}:{}",
+                    name,
+                    property_type,
+                    description.trim_end_matches('.')
+                ));
+
+            if let Some(values) = property.get("enum").and_then(Value::as_array) {
+                // The enum itself takes 3 tokens less than it's counted as
+                tokens = tokens.saturating_sub(3);
+
+                for value in values {
+                    let value = match value {
+                        Value::String(value) => value.clone(),
+                        value => value.to_string(),
+                    };
+
+                    tokens += ENUM_ITEM + tokenizer.count(&value);
+                }
+            }
+        }
+    }
+
+    tokens + FUNCTIONS_END
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{chat::Function, ChatModel};
+
+    use super::*;
+
+    fn named(content: &str, name: &str) -> ChatRequestMessage {
+        ChatRequestMessage {
+            name: Some(name.to_string()),
+            ..ChatRequestMessage::system_message(content)
+        }
+    }
+
+    #[test]
+    fn counts_tokens_correctly() {
+        // The example of the OpenAI cookbook, which reports 129 prompt tokens for gpt-3.5-turbo
+        let request = CreateChatCompletionRequest {
+            messages: vec![
+                ChatRequestMessage::system_message("You are a helpful, pattern-following assistant that translates corporate jargon into plain English."),
+                named("New synergies will help drive top-line growth.", "example_user"),
+                named("Things working well together will increase revenue.", "example_assistant"),
+                named("Let's circle back when we have more bandwidth to touch base on opportunities for increased leverage.", "example_user"),
+                named("Let's talk later when we're less busy about how to do better.", "example_assistant"),
+                ChatRequestMessage::user_message("This late pivot means we don't have time to boil the ocean for the client deliverable."),
+            ],
+            ..Default::default()
+        }
+        .set_model(ChatModel::GPT3_5Turbo);
+
+        assert_eq!(count_tokens(&request).unwrap(), 129);
+    }
+
+    #[test]
+    fn counts_tool_tokens() {
+        // The tools example of the OpenAI cookbook, which reports 105 prompt tokens for gpt-3.5-turbo
+        // and gpt-4, and 101 for gpt-4o and gpt-4o-mini
+        let request = CreateChatCompletionRequest {
+            messages: vec![
+                ChatRequestMessage::system_message(
+                    "You are a helpful assistant that can answer to questions about the weather.",
+                ),
+                ChatRequestMessage::user_message("What's the weather like in San Francisco?"),
+            ],
+            tools: vec![Tool {
+                function: Function {
+                    name: "get_current_weather".to_string(),
+                    description: Some("Get the current weather in a given location".to_string()),
+                    parameters: serde_json::json!({
+                        "type": "object",
+                        "properties": {
+                            "location": {
+                                "type": "string",
+                                "description": "The city and state, e.g. San Francisco, CA"
+                            },
+                            "unit": {
+                                "type": "string",
+                                "description": "The unit of temperature to return",
+                                "enum": ["celsius", "fahrenheit"]
+                            }
+                        },
+                        "required": ["location"]
+                    }),
+                    strict: None,
+                },
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+
+        for (model, expected) in [
+            (ChatModel::GPT3_5Turbo, 105),
+            (ChatModel::GPT4, 105),
+            (ChatModel::GPT4o, 101),
+            (ChatModel::GPT4oMini, 101),
+        ] {
+            let request = request.clone().set_model(model.clone());
+
+            assert_eq!(count_tokens(&request).unwrap(), expected, "{model}");
+        }
+    }
+}
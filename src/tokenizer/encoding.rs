@@ -0,0 +1,240 @@
+use std::{
+    collections::HashMap,
+    path::Path,
+    sync::{Arc, OnceLock},
+};
+
+use base64::{engine::general_purpose, Engine as _};
+use tiktoken_rs::{CoreBPE, Rank};
+
+use crate::common::OpenAIError;
+
+const ENDOFTEXT: &str = "<|endoftext|>";
+const FIM_PREFIX: &str = "<|fim_prefix|>";
+const FIM_MIDDLE: &str = "<|fim_middle|>";
+const FIM_SUFFIX: &str = "<|fim_suffix|>";
+const ENDOFPROMPT: &str = "<|endofprompt|>";
+
+const CL100K_BASE_PATTERN: &str = r"(?i:'s|'t|'re|'ve|'m|'ll|'d)|[^\r\n\p{L}\p{N}]?\p{L}+|\p{N}{1,3}| ?[^\s\p{L}\p{N}]+[\r\n]*|\s*[\r\n]+|\s+(?!\S)|\s+";
+
+const O200K_BASE_PATTERN: &str = concat!(
+    r"[^\r\n\p{L}\p{N}]?[\p{Lu}\p{Lt}\p{Lm}\p{Lo}\p{M}]*[\p{Ll}\p{Lm}\p{Lo}\p{M}]+(?i:'s|'t|'re|'ve|'m|'ll|'d)?|",
+    r"[^\r\n\p{L}\p{N}]?[\p{Lu}\p{Lt}\p{Lm}\p{Lo}\p{M}]+[\p{Ll}\p{Lm}\p{Lo}\p{M}]*(?i:'s|'t|'re|'ve|'m|'ll|'d)?|",
+    r"\p{N}{1,3}|",
+    r" ?[^\s\p{L}\p{N}]+[\r\n/]*|",
+    r"\s*[\r\n]+|",
+    r"\s+(?!\S)|",
+    r"\s+"
+);
+
+/// The BPE encodings of OpenAI models.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Encoding {
+    /// Used by GPT-4, GPT-3.5 Turbo and the `text-embedding-3` models
+    Cl100kBase,
+
+    /// Used by GPT-4o and the `o` series models
+    O200kBase,
+}
+
+impl Encoding {
+    /// Returns the encoding used by the model. Unknown models use `cl100k_base`.
+    pub fn for_model(model: &str) -> Self {
+        // Fine-tuned models are named `ft:<base model>:...`
+        let model = model.strip_prefix("ft:").unwrap_or(model);
+
+        if [
+            "gpt-4o",
+            "gpt-4.1",
+            "gpt-4.5",
+            "gpt-5",
+            "chatgpt-4o",
+            "o1",
+            "o3",
+            "o4",
+        ]
+        .iter()
+        .any(|prefix| model.starts_with(prefix))
+        {
+            Self::O200kBase
+        } else {
+            Self::Cl100kBase
+        }
+    }
+
+    /// The name of the encoding, such as `cl100k_base`.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Cl100kBase => "cl100k_base",
+            Self::O200kBase => "o200k_base",
+        }
+    }
+
+    fn pattern(&self) -> &'static str {
+        match self {
+            Self::Cl100kBase => CL100K_BASE_PATTERN,
+            Self::O200kBase => O200K_BASE_PATTERN,
+        }
+    }
+
+    fn special_tokens(&self) -> Vec<(String, Rank)> {
+        let special_tokens: &[(&str, Rank)] = match self {
+            Self::Cl100kBase => &[
+                (ENDOFTEXT, 100257),
+                (FIM_PREFIX, 100258),
+                (FIM_MIDDLE, 100259),
+                (FIM_SUFFIX, 100260),
+                (ENDOFPROMPT, 100276),
+            ],
+            Self::O200kBase => &[(ENDOFTEXT, 199999), (ENDOFPROMPT, 200018)],
+        };
+
+        special_tokens
+            .iter()
+            .map(|(token, rank)| (token.to_string(), *rank))
+            .collect()
+    }
+}
+
+impl std::fmt::Display for Encoding {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+/// A tokenizer for one of the BPE [`Encoding`]s.
+///
+/// The rank files of the encodings are bundled, and loaded once per process on first use.
+/// They can also be loaded from disk, e.g. to pin a specific version of them.
+#[derive(Clone)]
+pub struct Tokenizer {
+    encoding: Encoding,
+    bpe: Arc<CoreBPE>,
+}
+
+impl std::fmt::Debug for Tokenizer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Tokenizer")
+            .field("encoding", &self.encoding)
+            .finish()
+    }
+}
+
+impl Tokenizer {
+    /// Returns the tokenizer of a bundled encoding.
+    pub fn new(encoding: Encoding) -> Result<Self, OpenAIError> {
+        static CL100K_BASE: OnceLock<Arc<CoreBPE>> = OnceLock::new();
+        static O200K_BASE: OnceLock<Arc<CoreBPE>> = OnceLock::new();
+
+        let (cell, load): (_, fn() -> anyhow::Result<CoreBPE>) = match encoding {
+            Encoding::Cl100kBase => (&CL100K_BASE, tiktoken_rs::cl100k_base),
+            Encoding::O200kBase => (&O200K_BASE, tiktoken_rs::o200k_base),
+        };
+
+        let bpe = match cell.get() {
+            Some(bpe) => bpe.clone(),
+            None => {
+                let bpe = Arc::new(load().map_err(|e| OpenAIError::Exception(e.to_string()))?);
+                cell.get_or_init(|| bpe).clone()
+            }
+        };
+
+        Ok(Self { encoding, bpe })
+    }
+
+    /// Returns the tokenizer of the encoding used by the model.
+    pub fn for_model(model: &str) -> Result<Self, OpenAIError> {
+        Self::new(Encoding::for_model(model))
+    }
+
+    /// Loads the ranks of an encoding from a `.tiktoken` rank file.
+    pub async fn from_rank_file<P>(encoding: Encoding, path: P) -> Result<Self, OpenAIError>
+    where
+        P: AsRef<Path>,
+    {
+        let ranks = tokio::fs::read_to_string(path).await?;
+
+        Self::from_ranks(encoding, &ranks)
+    }
+
+    /// Creates a tokenizer from the content of a `.tiktoken` rank file,
+    /// with a base64 encoded token and its rank on every line.
+    pub fn from_ranks(encoding: Encoding, ranks: &str) -> Result<Self, OpenAIError> {
+        let mut encoder = HashMap::new();
+
+        for line in ranks.lines().filter(|line| !line.trim().is_empty()) {
+            let (token, rank) = line.split_once(' ').ok_or_else(|| {
+                OpenAIError::Exception(format!("Invalid rank file line: {}", line))
+            })?;
+
+            let rank = rank
+                .trim()
+                .parse::<Rank>()
+                .map_err(|e| OpenAIError::Exception(format!("Invalid rank {}: {}", rank, e)))?;
+
+            let _ = encoder.insert(general_purpose::STANDARD.decode(token)?, rank);
+        }
+
+        let bpe = CoreBPE::new(
+            encoder.into_iter().collect(),
+            encoding.special_tokens().into_iter().collect(),
+            encoding.pattern(),
+        )
+        .map_err(|e| OpenAIError::Exception(e.to_string()))?;
+
+        Ok(Self {
+            encoding,
+            bpe: Arc::new(bpe),
+        })
+    }
+
+    /// The encoding of the tokenizer.
+    pub fn encoding(&self) -> Encoding {
+        self.encoding
+    }
+
+    /// Encodes the text into tokens. Special tokens are encoded as ordinary text.
+    pub fn encode(&self, text: &str) -> Vec<u32> {
+        self.bpe.encode_ordinary(text)
+    }
+
+    /// Decodes tokens back into text.
+    pub fn decode(&self, tokens: Vec<u32>) -> Result<String, OpenAIError> {
+        self.bpe
+            .decode(tokens)
+            .map_err(|e| OpenAIError::Exception(e.to_string()))
+    }
+
+    /// Counts the tokens of the text.
+    pub fn count(&self, text: &str) -> usize {
+        self.encode(text).len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn encodes_text_correctly() {
+        let cl100k_base = Tokenizer::new(Encoding::Cl100kBase).unwrap();
+        let tokens = cl100k_base.encode("hello world");
+
+        assert_eq!(tokens, vec![15339, 1917]);
+        assert_eq!(cl100k_base.decode(tokens).unwrap(), "hello world");
+
+        let o200k_base = Tokenizer::for_model("gpt-4o-mini").unwrap();
+        assert_eq!(o200k_base.encoding(), Encoding::O200kBase);
+        assert_eq!(o200k_base.encode("hello world"), vec![24912, 2375]);
+    }
+
+    #[test]
+    fn loads_ranks_correctly() {
+        // "a", "b" and "ab" encoded in base64
+        let tokenizer =
+            Tokenizer::from_ranks(Encoding::Cl100kBase, "YQ== 0\nYg== 1\nYWI= 2\n").unwrap();
+
+        assert_eq!(tokenizer.encode("ab"), vec![2]);
+        assert_eq!(tokenizer.encode("ba"), vec![1, 0]);
+    }
+}
@@ -0,0 +1,12 @@
+//! Offline token counting with the BPE encodings used by OpenAI models, see
+//! [tiktoken](https://github.com/openai/tiktoken).
+
+mod encoding;
+
+#[cfg(feature = "chat")]
+mod chat;
+
+pub use self::encoding::*;
+
+#[cfg(feature = "chat")]
+pub use self::chat::*;
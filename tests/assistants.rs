@@ -1,6 +1,6 @@
 use open_ai_client::{
     assistants::{CreateAssistantRequest, ModifyAssistantRequest},
-    ChatModel, OpenAIClient,
+    ChatModel, ListParams, OpenAIClient,
 };
 
 pub async fn assistants_test(client: OpenAIClient) {
@@ -31,11 +31,11 @@ async fn creates_assistant(client: &OpenAIClient) -> String {
 async fn list_assistants(client: &OpenAIClient, assistant_id: &str) {
     let result = client
         .assistants()
-        .list_assistants(None, None, None, None)
+        .list_assistants(ListParams::default())
         .await
         .unwrap();
 
-    assert!(result.data.len() > 0);
+    assert!(!result.data.is_empty());
     assert!(result.data[0].id == assistant_id);
 }
 
@@ -72,6 +72,6 @@ async fn delete_assistant(client: &OpenAIClient, assistant_id: &str) {
         .await
         .unwrap();
 
-    assert!(&result.id == assistant_id);
-    assert!(result.deleted == true);
+    assert!(result.id == assistant_id);
+    assert!(result.deleted);
 }
@@ -39,6 +39,7 @@ pub async fn audio_transcriptions_test(client: OpenAIClient) {
         although the surface appears to be very, very fine-grained as you get close to it. \
         It's almost like a powder. Ground mass is very fine. I'm going to step off the lamb now. \
         That's one small step for man, one giant leap for mankind.".to_string(),
+        duration: None,
     };
 
     assert_eq!(result, expected);
@@ -62,6 +63,7 @@ pub async fn audio_translations_test(client: OpenAIClient) {
         This is the default mode used for all Customer Interactions. \
         Please check the caller's Social Security number before proceeding. Once you have confirmed, \
         click the following button, like this. Very good. Now let's move on to step 2.".to_string(),
+        duration: None,
     };
 
     assert_eq!(result, expected);
@@ -1,5 +1,3 @@
-use std::{thread, time::Duration};
-
 use open_ai_client::{
     chat::{ChatRequestMessage, CreateChatCompletionRequest},
     ChatModel, MessageRole, OpenAIClient,
@@ -11,13 +9,14 @@ pub async fn chat_completion_test(client: OpenAIClient) {
         messages: vec![
             ChatRequestMessage {
                 role: MessageRole::System,
-                content: "You are a helpful assistant, that response only with a yes or a no"
-                    .to_string(),
+                content: Some(
+                    "You are a helpful assistant, that response only with a yes or a no".into(),
+                ),
                 ..Default::default()
             },
             ChatRequestMessage {
                 role: MessageRole::User,
-                content: "Hi, is Washington DC the capital of USA".to_string(),
+                content: Some("Hi, is Washington DC the capital of USA".into()),
                 ..Default::default()
             },
         ],
@@ -30,20 +29,21 @@ pub async fn chat_completion_test(client: OpenAIClient) {
     assert_eq!(result.first_message().unwrap(), "Yes.");
 }
 
-pub async fn _chat_completion_streaming_test(client: OpenAIClient) {
+pub async fn chat_completion_streaming_test(client: OpenAIClient) {
     let request = CreateChatCompletionRequest {
         model: ChatModel::GPT3_5Turbo.to_string(),
         stream: Some(true),
         messages: vec![
             ChatRequestMessage {
                 role: MessageRole::System,
-                content: "You are a helpful assistant, that response only with a yes or a no"
-                    .to_string(),
+                content: Some(
+                    "You are a helpful assistant, that response only with a yes or a no".into(),
+                ),
                 ..Default::default()
             },
             ChatRequestMessage {
                 role: MessageRole::User,
-                content: "Hi, is Washington DC the capital of USA".to_string(),
+                content: Some("Hi, is Washington DC the capital of USA".into()),
                 ..Default::default()
             },
         ],
@@ -58,14 +58,10 @@ pub async fn _chat_completion_streaming_test(client: OpenAIClient) {
 
     let mut messages = vec![];
 
-    for _ in 0..3 {
-        if let Ok(result) = stream.read_values() {
-            if let Some(msg) = result.first_message() {
-                messages.push(msg.clone())
-            }
+    while let Some(result) = stream.next().await {
+        if let Some(msg) = result.unwrap().first_message() {
+            messages.push(msg.clone())
         }
-
-        thread::sleep(Duration::from_secs(1));
     }
 
     assert!(!messages.is_empty());
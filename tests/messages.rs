@@ -1,7 +1,7 @@
 use std::collections::BTreeMap;
 
 use open_ai_client::{
-    messages::{CreateMessageRequest, MessageContent, ModifyMessagesRequest},
+    messages::{CreateMessageRequest, ListMessagesParams, MessageContent, ModifyMessagesRequest},
     threads::CreateThreadRequest,
     OpenAIClient,
 };
@@ -43,11 +43,11 @@ async fn create_message(client: &OpenAIClient, thread_id: &str) -> String {
 async fn list_messages(client: &OpenAIClient, thread_id: &str, message_id: &str) {
     let result = client
         .messages()
-        .list_messages(thread_id, None, None, None, None)
+        .list_messages(thread_id, ListMessagesParams::default())
         .await
         .unwrap();
 
-    assert!(result.data.len() > 0);
+    assert!(!result.data.is_empty());
     assert!(result.data[0].id == message_id);
 }
 
@@ -63,7 +63,7 @@ async fn retrieve_message(client: &OpenAIClient, thread_id: &str, message_id: &s
         MessageContent::Text(text) => {
             assert!(&text.text.value == "Hello, what is AI?");
         }
-        _ => assert!(false),
+        _ => panic!(),
     }
 }
 
@@ -87,13 +87,13 @@ async fn modify_threads_message(client: &OpenAIClient, thread_id: &str, message_
         MessageContent::Text(text) => {
             assert!(&text.text.value == "Hello, what is AI?");
         }
-        _ => assert!(false),
+        _ => panic!(),
     }
 }
 
 async fn delete_thread(client: &OpenAIClient, thread_id: &str) {
     let result = client.threads().delete_thread(thread_id).await.unwrap();
 
-    assert!(&result.id == thread_id);
-    assert!(result.deleted == true);
+    assert!(result.id == thread_id);
+    assert!(result.deleted);
 }
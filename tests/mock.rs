@@ -0,0 +1,960 @@
+use std::time::Duration;
+
+use futures_util::StreamExt;
+use open_ai_client::{
+    batches::{BatchBuilder, BatchStatus},
+    chat::{
+        ChatRequestMessage, ChatStreamOptions, CreateChatCompletionRequest, Function, ToolRegistry,
+        ToolRunOptions, ToolRunStop,
+    },
+    completions::CreateCompletionRequest,
+    files::{ListFilesParams, Purpose},
+    images::CreateImageRequest,
+    ChatModel, ClientBuilder, ImageGenerationModel, MessageRole, MockExpectation, MockRequestKind,
+    MockResponse, MockTransport, ModelInfo, ModelRegistry, OpenAIClient, OpenAIError,
+    StructuredOutputError, ToolSchema, UsageTracker,
+};
+use reqwest::{Method, StatusCode};
+use serde::Deserialize;
+use serde_json::json;
+
+fn create_client(mock: &MockTransport) -> OpenAIClient {
+    ClientBuilder::new("test-token")
+        .transport(mock.clone())
+        .build()
+        .unwrap()
+}
+
+fn chat_request() -> CreateChatCompletionRequest {
+    CreateChatCompletionRequest {
+        model: ChatModel::GPT3_5Turbo.to_string(),
+        messages: vec![ChatRequestMessage {
+            role: MessageRole::User,
+            content: Some("Hi, is Washington DC the capital of USA".into()),
+            ..Default::default()
+        }],
+        ..Default::default()
+    }
+}
+
+#[tokio::test]
+async fn mock_chat_completion() {
+    let mock = MockTransport::new();
+    let _ = mock.expect(
+        MockExpectation::new(Method::POST, "/v1/chat/completions")
+            .with_body(json!({ "model": "gpt-3.5-turbo" }))
+            .respond_with(MockResponse::json(json!({
+                "id": "chatcmpl-123",
+                "object": "chat.completion",
+                "created": 1677652288,
+                "model": "gpt-3.5-turbo-0125",
+                "choices": [{
+                    "index": 0,
+                    "message": { "role": "assistant", "content": "Yes." },
+                    "finish_reason": "stop"
+                }],
+                "usage": { "prompt_tokens": 9, "completion_tokens": 2, "total_tokens": 11 }
+            }))),
+    );
+
+    let client = create_client(&mock);
+    let result = client
+        .chat()
+        .create_chat_completion(chat_request())
+        .await
+        .unwrap();
+
+    assert_eq!(result.first_message().unwrap(), "Yes.");
+    assert_eq!(mock.requests()[0].kind, MockRequestKind::Json);
+    mock.assert_finished();
+}
+
+#[tokio::test]
+async fn mock_chat_completion_streaming() {
+    let chunk = |content: &str| {
+        json!({
+            "id": "chatcmpl-123",
+            "object": "chat.completion.chunk",
+            "created": 1694268190,
+            "model": "gpt-3.5-turbo-0125",
+            "choices": [{
+                "index": 0,
+                "delta": { "role": "assistant", "content": content },
+                "logprobs": null,
+                "finish_reason": null
+            }]
+        })
+    };
+
+    let mock = MockTransport::new();
+    let _ = mock.expect(
+        MockExpectation::new(Method::POST, "/v1/chat/completions")
+            .respond_with(MockResponse::sse([chunk("Ye"), chunk("s.")])),
+    );
+
+    let client = create_client(&mock);
+    let mut stream = client
+        .chat()
+        .create_chat_completion_streaming(CreateChatCompletionRequest {
+            stream: Some(true),
+            ..chat_request()
+        })
+        .await
+        .unwrap();
+
+    let mut messages = String::new();
+
+    while let Some(value) = stream.next().await {
+        messages.push_str(value.unwrap().first_message().unwrap());
+    }
+
+    assert_eq!(messages, "Yes.");
+    assert_eq!(mock.requests()[0].kind, MockRequestKind::Stream);
+}
+
+#[tokio::test]
+async fn mock_chat_completion_stream_collect() {
+    let chunk = json!({
+        "id": "chatcmpl-123",
+        "object": "chat.completion.chunk",
+        "created": 1694268190,
+        "model": "gpt-3.5-turbo-0125",
+        "choices": [{
+            "index": 0,
+            "delta": { "content": "Yes." },
+            "logprobs": null,
+            "finish_reason": "stop"
+        }]
+    });
+
+    let mock = MockTransport::new();
+    let _ = mock.expect(
+        MockExpectation::new(Method::POST, "/v1/chat/completions")
+            .respond_with(MockResponse::sse(vec![chunk; 100])),
+    );
+
+    let client = create_client(&mock);
+    let stream = client
+        .chat()
+        .create_chat_completion_streaming(chat_request())
+        .await
+        .unwrap();
+
+    let values = stream.collect::<Vec<_>>().await;
+
+    assert_eq!(values.len(), 100);
+    assert!(values.iter().all(Result::is_ok));
+}
+
+#[tokio::test]
+async fn mock_stream_error_event() {
+    let mock = MockTransport::new();
+    let _ = mock.expect(
+        MockExpectation::new(Method::POST, "/v1/chat/completions").respond_with(
+            MockResponse::sse_chunks(vec![
+                ": keep-alive\n\n".to_string(),
+                "event: error\ndata: {\"message\": \"The server had an error\", \"type\": \"server_error\"}\n\n".to_string(),
+            ]),
+        ),
+    );
+
+    let client = create_client(&mock);
+    let mut stream = client
+        .chat()
+        .create_chat_completion_streaming(chat_request())
+        .await
+        .unwrap();
+
+    let error = stream.next().await.unwrap().unwrap_err();
+    let api_error = error.api_error().unwrap();
+
+    assert_eq!(api_error.message(), "The server had an error");
+    assert_eq!(api_error.error_type(), Some("server_error"));
+    assert!(stream.next().await.is_none());
+}
+
+#[tokio::test]
+async fn mock_stream_open_error() {
+    let mock = MockTransport::new();
+    let _ = mock.expect(
+        MockExpectation::new(Method::POST, "/v1/chat/completions").respond_with(
+            MockResponse::error(StatusCode::BAD_REQUEST, "Invalid model"),
+        ),
+    );
+
+    let client = create_client(&mock);
+    let error = client
+        .chat()
+        .create_chat_completion_streaming(chat_request())
+        .await
+        .unwrap_err();
+
+    let api_error = error.api_error().unwrap();
+    assert_eq!(api_error.status, StatusCode::BAD_REQUEST);
+    assert_eq!(api_error.message(), "Invalid model");
+}
+
+#[tokio::test]
+async fn mock_api_error() {
+    let mock = MockTransport::new();
+    let _ = mock.expect(
+        MockExpectation::new(Method::POST, "/v1/threads/thread_abc/runs/run_abc/cancel")
+            .respond_with(MockResponse::error(StatusCode::NOT_FOUND, "No run found")),
+    );
+
+    let client = create_client(&mock);
+    let error = client
+        .runs()
+        .cancel_run("thread_abc", "run_abc")
+        .await
+        .unwrap_err();
+
+    let api_error = error.api_error().unwrap();
+    assert_eq!(api_error.status, StatusCode::NOT_FOUND);
+    assert_eq!(api_error.message(), "No run found");
+}
+
+#[tokio::test]
+async fn mock_response_meta() {
+    let mock = MockTransport::new();
+    let _ = mock.expect(
+        MockExpectation::new(Method::GET, "/v1/files").respond_with(
+            MockResponse::json(json!({ "object": "list", "data": [] }))
+                .header("x-request-id", "req_123")
+                .header("x-ratelimit-remaining-requests", "59")
+                .header("x-ratelimit-reset-requests", "1s"),
+        ),
+    );
+
+    let client = create_client(&mock);
+    let (files, meta) = client
+        .with_response_meta(client.files().list_files(ListFilesParams::new()))
+        .await;
+
+    assert!(files.unwrap().data.is_empty());
+
+    let meta = meta.unwrap();
+    assert_eq!(meta.status, StatusCode::OK);
+    assert_eq!(meta.request_id.as_deref(), Some("req_123"));
+
+    let rate_limits = meta.rate_limit_info().unwrap();
+    assert_eq!(rate_limits.remaining_requests, Some(59));
+    assert_eq!(rate_limits.reset_requests, Some(Duration::from_secs(1)));
+
+    let (_, meta) = client.with_response_meta(async {}).await;
+    assert!(meta.is_none());
+}
+
+#[tokio::test]
+async fn mock_unexpected_request() {
+    let mock = MockTransport::new();
+    let client = create_client(&mock);
+
+    let result = client.chat().create_chat_completion(chat_request()).await;
+
+    assert!(result.is_err());
+    assert!(mock.requests().is_empty());
+}
+
+#[tokio::test]
+async fn mock_run_with_tools() {
+    #[derive(Deserialize)]
+    struct WeatherArguments {
+        city: String,
+    }
+
+    let completion = |message: serde_json::Value, finish_reason: &str| {
+        MockResponse::json(json!({
+            "id": "chatcmpl-123",
+            "object": "chat.completion",
+            "created": 1677652288,
+            "model": "gpt-3.5-turbo-0125",
+            "choices": [{ "index": 0, "message": message, "finish_reason": finish_reason }],
+            "usage": { "prompt_tokens": 9, "completion_tokens": 2, "total_tokens": 11 }
+        }))
+    };
+
+    let mock = MockTransport::new();
+    let _ = mock
+        .expect(
+            MockExpectation::new(Method::POST, "/v1/chat/completions")
+                .with_body(json!({ "tools": [{ "type": "function", "function": { "name": "get_weather" } }] }))
+                .respond_with(completion(
+                    json!({
+                        "role": "assistant",
+                        "content": null,
+                        "tool_calls": [
+                            { "id": "call_1", "type": "function", "function": { "name": "get_weather", "arguments": "{\"city\":\"Paris\"}" } },
+                            { "id": "call_2", "type": "function", "function": { "name": "get_time", "arguments": "{}" } }
+                        ]
+                    }),
+                    "tool_calls",
+                )),
+        )
+        .expect(
+            MockExpectation::new(Method::POST, "/v1/chat/completions")
+                .respond_with(completion(
+                    json!({ "role": "assistant", "content": "It's sunny in Paris." }),
+                    "stop",
+                )),
+        );
+
+    let registry = ToolRegistry::new().register(
+        Function {
+            name: "get_weather".to_string(),
+            ..Default::default()
+        },
+        |arguments: WeatherArguments| async move { Ok(format!("Sunny in {}", arguments.city)) },
+    );
+
+    let client = create_client(&mock);
+    let run = client
+        .chat()
+        .run_with_tools(chat_request(), &registry, ToolRunOptions::default())
+        .await
+        .unwrap();
+
+    assert_eq!(run.stop, ToolRunStop::Completed);
+    assert_eq!(run.final_message().unwrap(), "It's sunny in Paris.");
+    assert_eq!(run.messages.len(), 5);
+
+    let second_request = mock.requests()[1].body.clone().unwrap();
+    assert_eq!(
+        second_request["messages"][2],
+        json!({ "role": "tool", "content": "Sunny in Paris", "tool_call_id": "call_1" })
+    );
+    assert_eq!(
+        second_request["messages"][3]["content"],
+        json!({ "error": "Unknown tool: get_time" }).to_string()
+    );
+
+    for parallel in [false, true] {
+        let _ = mock.expect(
+            MockExpectation::new(Method::POST, "/v1/chat/completions").respond_with(completion(
+                json!({
+                    "role": "assistant",
+                    "content": null,
+                    "tool_calls": [
+                        { "id": "call_1", "type": "function", "function": { "name": "get_weather", "arguments": "{\"city\":\"Paris\"}" } },
+                        { "id": "call_2", "type": "function", "function": { "name": "get_time", "arguments": "{}" } }
+                    ]
+                }),
+                "tool_calls",
+            )),
+        );
+
+        let registry = registry.clone().register(
+            Function {
+                name: "get_time".to_string(),
+                ..Default::default()
+            },
+            |_: serde_json::Value| async move {
+                tokio::time::sleep(Duration::from_secs(60)).await;
+                Ok("12:00")
+            },
+        );
+
+        let options = ToolRunOptions {
+            timeout: Some(Duration::from_millis(200)),
+            parallel,
+            ..Default::default()
+        };
+        let run = client
+            .chat()
+            .run_with_tools(chat_request(), &registry, options)
+            .await
+            .unwrap();
+
+        assert_eq!(run.stop, ToolRunStop::Timeout);
+
+        let messages = serde_json::to_value(&run.messages[run.messages.len() - 2..]).unwrap();
+        assert_eq!(
+            messages,
+            json!([
+                { "role": "tool", "content": "Sunny in Paris", "tool_call_id": "call_1" },
+                {
+                    "role": "tool",
+                    "content": json!({ "error": "The tool call timed out" }).to_string(),
+                    "tool_call_id": "call_2"
+                }
+            ]),
+            "every tool call is answered, parallel: {parallel}"
+        );
+    }
+
+    mock.assert_finished();
+}
+
+#[derive(Debug, Deserialize, PartialEq)]
+struct Answer {
+    answer: bool,
+}
+
+impl ToolSchema for Answer {
+    fn json_schema() -> serde_json::Value {
+        json!({ "type": "object", "properties": { "answer": { "type": "boolean" } } })
+    }
+}
+
+fn structured_completion(message: serde_json::Value, finish_reason: &str) -> MockResponse {
+    MockResponse::json(json!({
+        "id": "chatcmpl-123",
+        "object": "chat.completion",
+        "created": 1677652288,
+        "model": "gpt-3.5-turbo-0125",
+        "choices": [{ "index": 0, "message": message, "finish_reason": finish_reason }],
+        "usage": { "prompt_tokens": 9, "completion_tokens": 2, "total_tokens": 11 }
+    }))
+}
+
+#[tokio::test]
+async fn mock_create_parsed() {
+    let mock = MockTransport::new();
+    let _ = mock.expect(
+        MockExpectation::new(Method::POST, "/v1/chat/completions")
+            .with_body(json!({
+                "response_format": {
+                    "type": "json_schema",
+                    "json_schema": {
+                        "name": "Answer",
+                        "schema": {
+                            "type": "object",
+                            "properties": { "answer": { "type": "boolean" } },
+                            "required": ["answer"],
+                            "additionalProperties": false
+                        },
+                        "strict": true
+                    }
+                }
+            }))
+            .respond_with(structured_completion(
+                json!({ "role": "assistant", "content": "{\"answer\":true}" }),
+                "stop",
+            )),
+    );
+
+    let client = create_client(&mock);
+    let answer = client
+        .chat()
+        .create_parsed::<Answer>(chat_request())
+        .await
+        .unwrap();
+
+    assert_eq!(answer, Answer { answer: true });
+}
+
+#[tokio::test]
+async fn mock_create_parsed_errors() {
+    let mock = MockTransport::new();
+    let _ = mock
+        .expect(
+            MockExpectation::new(Method::POST, "/v1/chat/completions").respond_with(
+                structured_completion(
+                    json!({ "role": "assistant", "content": null, "refusal": "I can't help with that." }),
+                    "stop",
+                ),
+            ),
+        )
+        .expect(
+            MockExpectation::new(Method::POST, "/v1/chat/completions").respond_with(
+                structured_completion(json!({ "role": "assistant", "content": "{\"ans" }), "length"),
+            ),
+        )
+        .expect(
+            MockExpectation::new(Method::POST, "/v1/chat/completions").respond_with(
+                structured_completion(json!({ "role": "assistant", "content": null }), "length"),
+            ),
+        )
+        .expect(
+            MockExpectation::new(Method::POST, "/v1/chat/completions").respond_with(
+                structured_completion(json!({ "role": "assistant", "content": "{\"answer\":1}" }), "stop"),
+            ),
+        );
+
+    let client = create_client(&mock);
+    let chat = client.chat();
+
+    let refusal = chat.create_parsed::<Answer>(chat_request()).await;
+    assert!(matches!(
+        refusal,
+        Err(OpenAIError::StructuredOutput(StructuredOutputError::Refusal(refusal))) if refusal == "I can't help with that."
+    ));
+
+    let truncated = chat.create_parsed::<Answer>(chat_request()).await;
+    assert!(matches!(
+        truncated,
+        Err(OpenAIError::StructuredOutput(
+            StructuredOutputError::Truncated(_)
+        ))
+    ));
+
+    let truncated_without_content = chat.create_parsed::<Answer>(chat_request()).await;
+    assert!(matches!(
+        truncated_without_content,
+        Err(OpenAIError::StructuredOutput(
+            StructuredOutputError::Truncated(partial)
+        )) if partial.is_empty()
+    ));
+
+    let mismatch = chat.create_parsed::<Answer>(chat_request()).await;
+    assert!(matches!(
+        mismatch,
+        Err(OpenAIError::StructuredOutput(
+            StructuredOutputError::SchemaMismatch { .. }
+        ))
+    ));
+}
+
+#[tokio::test]
+async fn mock_usage_tracking() {
+    let mock = MockTransport::new();
+    let _ = mock.expect(
+        MockExpectation::new(Method::POST, "/v1/chat/completions").respond_with(
+            MockResponse::json(json!({
+                "id": "chatcmpl-123",
+                "object": "chat.completion",
+                "created": 1677652288,
+                "model": "gpt-4o-mini-2024-07-18",
+                "choices": [{
+                    "index": 0,
+                    "message": { "role": "assistant", "content": "Yes." },
+                    "finish_reason": "stop"
+                }],
+                "usage": { "prompt_tokens": 1000, "completion_tokens": 500, "total_tokens": 1500 }
+            })),
+        ),
+    );
+
+    let client = ClientBuilder::new("test-token")
+        .transport(mock.clone())
+        .usage_tracker(UsageTracker::new())
+        .build()
+        .unwrap();
+
+    let request = CreateChatCompletionRequest {
+        user: Some("user-1".to_string()),
+        ..chat_request()
+    };
+    let _ = UsageTracker::labeled("faq", client.chat().create_chat_completion(request))
+        .await
+        .unwrap();
+
+    let usage = client.usage_tracker().unwrap().snapshot();
+    let model_usage = &usage.by_model["gpt-4o-mini-2024-07-18"];
+
+    assert_eq!(model_usage.prompt_tokens, 1000);
+    assert_eq!(model_usage.completion_tokens, 500);
+    assert!((model_usage.cost - 0.00045).abs() < 1e-12);
+    assert_eq!(usage.by_user["user-1"].requests, 1);
+    assert_eq!(usage.by_label["faq"].requests, 1);
+    mock.assert_finished();
+}
+
+#[tokio::test]
+async fn mock_usage_tracking_with_model_registry() {
+    let mock = MockTransport::new();
+    let _ = mock.expect(
+        MockExpectation::new(Method::POST, "/v1/chat/completions").respond_with(
+            MockResponse::json(json!({
+                "id": "chatcmpl-123",
+                "object": "chat.completion",
+                "created": 1677652288,
+                "model": "my-model",
+                "choices": [{
+                    "index": 0,
+                    "message": { "role": "assistant", "content": "Yes." },
+                    "finish_reason": "stop"
+                }],
+                "usage": { "prompt_tokens": 1000, "completion_tokens": 500, "total_tokens": 1500 }
+            })),
+        ),
+    );
+
+    let client = ClientBuilder::new("test-token")
+        .transport(mock.clone())
+        .model_registry(
+            ModelRegistry::default()
+                .with_model("my-model", ModelInfo::new(8_192).with_prices(1.0, 2.0)),
+        )
+        .usage_tracker(UsageTracker::new())
+        .build()
+        .unwrap();
+
+    let _ = client
+        .chat()
+        .create_chat_completion(chat_request())
+        .await
+        .unwrap();
+
+    let usage = client.usage_tracker().unwrap().snapshot();
+
+    assert!((usage.by_model["my-model"].cost - 0.002).abs() < 1e-12);
+    mock.assert_finished();
+}
+
+#[tokio::test]
+async fn mock_usage_tracking_of_streams_and_runs() {
+    let chunk = |choices: serde_json::Value, usage: serde_json::Value| {
+        json!({
+            "id": "chatcmpl-123",
+            "object": "chat.completion.chunk",
+            "created": 1694268190,
+            "model": "gpt-4o-mini-2024-07-18",
+            "choices": choices,
+            "usage": usage
+        })
+    };
+    let run = json!({
+        "id": "run_abc123",
+        "object": "thread.run",
+        "created_at": 1698107661,
+        "assistant_id": "asst_abc123",
+        "thread_id": "thread_abc123",
+        "status": "completed",
+        "started_at": 1699073476,
+        "expires_at": null,
+        "failed_at": null,
+        "completed_at": 1699073498,
+        "last_error": null,
+        "model": "gpt-4",
+        "instructions": null,
+        "tools": [],
+        "file_ids": [],
+        "metadata": {},
+        "usage": { "prompt_tokens": 200, "completion_tokens": 100, "total_tokens": 300 }
+    });
+
+    let mock = MockTransport::new();
+    let _ = mock.expect(
+        MockExpectation::new(Method::POST, "/v1/chat/completions").respond_with(
+            MockResponse::sse([
+                chunk(
+                    json!([{ "index": 0, "delta": { "role": "assistant", "content": "Yes." }, "finish_reason": "stop" }]),
+                    json!(null),
+                ),
+                chunk(
+                    json!([]),
+                    json!({ "prompt_tokens": 1000, "completion_tokens": 500, "total_tokens": 1500 }),
+                ),
+            ]),
+        ),
+    );
+    for _ in 0..2 {
+        let _ = mock.expect(
+            MockExpectation::new(Method::GET, "/v1/threads/thread_abc123/runs/run_abc123")
+                .respond_with(MockResponse::json(run.clone())),
+        );
+    }
+
+    let client = ClientBuilder::new("test-token")
+        .transport(mock.clone())
+        .usage_tracker(UsageTracker::new())
+        .build()
+        .unwrap();
+
+    let values = UsageTracker::labeled("faq", async {
+        let stream = client
+            .chat()
+            .create_chat_completion_streaming(CreateChatCompletionRequest {
+                stream: Some(true),
+                stream_options: Some(ChatStreamOptions {
+                    include_usage: true,
+                }),
+                user: Some("user-1".to_string()),
+                ..chat_request()
+            })
+            .await
+            .unwrap();
+
+        stream.collect::<Vec<_>>().await
+    })
+    .await;
+    assert!(values.iter().all(Result::is_ok));
+
+    for _ in 0..2 {
+        let _ = client
+            .runs()
+            .retrieve_run("thread_abc123", "run_abc123")
+            .await
+            .unwrap();
+    }
+
+    let usage = client.usage_tracker().unwrap().snapshot();
+
+    assert_eq!(usage.total.requests, 2);
+    assert_eq!(usage.by_user["user-1"].prompt_tokens, 1000);
+    assert_eq!(usage.by_label["faq"].prompt_tokens, 1000);
+    assert!((usage.by_model["gpt-4o-mini-2024-07-18"].cost - 0.00045).abs() < 1e-12);
+    assert_eq!(usage.by_model["gpt-4"].completion_tokens, 100);
+    mock.assert_finished();
+}
+
+#[tokio::test]
+async fn mock_usage_tracking_of_images() {
+    let mock = MockTransport::new();
+    for _ in 0..2 {
+        let _ = mock.expect(
+            MockExpectation::new(Method::POST, "/v1/images/generations").respond_with(
+                MockResponse::json(
+                    json!({ "url": "https://example.com/image.png", "revised_prompt": null }),
+                ),
+            ),
+        );
+    }
+
+    let client = ClientBuilder::new("test-token")
+        .transport(mock.clone())
+        .usage_tracker(UsageTracker::new())
+        .build()
+        .unwrap();
+
+    for quality in ["standard", "hd"] {
+        let _ = client
+            .images()
+            .create_image_request(CreateImageRequest {
+                prompt: "A cute baby sea otter".to_string(),
+                model: ImageGenerationModel::Dalle3,
+                quality: Some(quality.to_string()),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+    }
+
+    let usage = client.usage_tracker().unwrap().snapshot();
+
+    assert_eq!(usage.total.images, 2);
+    assert_eq!(usage.total.unpriced_requests, 1);
+    assert!((usage.total.cost - 0.04).abs() < 1e-12);
+    mock.assert_finished();
+}
+
+#[tokio::test]
+async fn mock_completion_streaming() {
+    let chunk = |text: &str| {
+        json!({
+            "id": "cmpl-123",
+            "object": "text_completion",
+            "created": 1694268190,
+            "model": "gpt-3.5-turbo-instruct",
+            "choices": [{ "text": text, "index": 0, "logprobs": null, "finish_reason": null }]
+        })
+    };
+
+    let mock = MockTransport::new();
+    let _ = mock.expect(
+        MockExpectation::new(Method::POST, "/v1/completions")
+            .with_body(json!({ "model": "gpt-3.5-turbo-instruct", "prompt": "Say this is a test", "stream": true }))
+            .respond_with(MockResponse::sse([chunk("This is"), chunk(" a test.")])),
+    );
+
+    let client = create_client(&mock);
+    let mut stream = client
+        .completions()
+        .create_completion_streaming(CreateCompletionRequest::new(
+            "gpt-3.5-turbo-instruct",
+            "Say this is a test",
+        ))
+        .await
+        .unwrap();
+
+    let mut text = String::new();
+
+    while let Some(value) = stream.next().await {
+        text.push_str(value.unwrap().first_text().unwrap());
+    }
+
+    assert_eq!(text, "This is a test.");
+    assert_eq!(mock.requests()[0].kind, MockRequestKind::Stream);
+    mock.assert_finished();
+}
+
+#[tokio::test]
+async fn mock_batch_workflow() {
+    let batch = |status: &str| {
+        json!({
+            "id": "batch_abc123",
+            "object": "batch",
+            "endpoint": "/v1/chat/completions",
+            "input_file_id": "file-input",
+            "completion_window": "24h",
+            "status": status,
+            "output_file_id": "file-output",
+            "created_at": 1711471533,
+            "request_counts": { "total": 2, "completed": 1, "failed": 1 }
+        })
+    };
+
+    let output = [
+        json!({
+            "id": "batch_req_1",
+            "custom_id": "question-1",
+            "response": {
+                "status_code": 200,
+                "request_id": "req_1",
+                "body": {
+                    "id": "chatcmpl-123",
+                    "object": "chat.completion",
+                    "created": 1677652288,
+                    "model": "gpt-3.5-turbo-0125",
+                    "choices": [{
+                        "index": 0,
+                        "message": { "role": "assistant", "content": "Yes." },
+                        "finish_reason": "stop"
+                    }],
+                    "usage": { "prompt_tokens": 9, "completion_tokens": 2, "total_tokens": 11 }
+                }
+            },
+            "error": null
+        }),
+        json!({
+            "id": "batch_req_2",
+            "custom_id": "question-2",
+            "response": {
+                "status_code": 429,
+                "request_id": "req_2",
+                "body": { "error": { "message": "Rate limit reached", "code": "rate_limit_exceeded" } }
+            },
+            "error": null
+        }),
+    ]
+    .map(|line| line.to_string())
+    .join("\n");
+
+    let mock = MockTransport::new();
+    let _ = mock
+        .expect(
+            MockExpectation::new(Method::POST, "/v1/files").respond_with(MockResponse::json(
+                json!({
+                    "id": "file-input",
+                    "object": "file",
+                    "bytes": 512,
+                    "created_at": 1711471533,
+                    "filename": "batch.jsonl",
+                    "purpose": "batch"
+                }),
+            )),
+        )
+        .expect(
+            MockExpectation::new(Method::POST, "/v1/batches")
+                .with_body(json!({
+                    "input_file_id": "file-input",
+                    "endpoint": "/v1/chat/completions",
+                    "completion_window": "24h"
+                }))
+                .respond_with(MockResponse::json(batch("validating"))),
+        )
+        .expect(
+            MockExpectation::new(Method::GET, "/v1/batches/batch_abc123")
+                .respond_with(MockResponse::json(batch("completed"))),
+        )
+        .expect(
+            MockExpectation::new(Method::GET, "/v1/files/file-output/content")
+                .respond_with(MockResponse::new(StatusCode::OK).body(output)),
+        );
+
+    let builder = BatchBuilder::new()
+        .with_request("question-1", chat_request())
+        .with_request("question-2", chat_request());
+
+    let client = create_client(&mock);
+    let batch = client.batches().submit(&builder).await.unwrap();
+    assert_eq!(batch.status, BatchStatus::Validating);
+
+    let batch = client.batches().retrieve_batch(batch.id).await.unwrap();
+    let results = client
+        .batches()
+        .results::<CreateChatCompletionRequest>(&batch)
+        .await
+        .unwrap();
+
+    assert_eq!(
+        results["question-1"]
+            .as_ref()
+            .unwrap()
+            .first_message()
+            .unwrap(),
+        "Yes."
+    );
+    assert_eq!(
+        results["question-2"].as_ref().unwrap_err().code.as_deref(),
+        Some("rate_limit_exceeded")
+    );
+
+    let requests = mock.requests();
+    assert_eq!(requests[0].kind, MockRequestKind::Multipart);
+    assert!(requests[0]
+        .form
+        .contains(&("purpose".to_string(), "batch".to_string())));
+    assert!(requests[0]
+        .form
+        .contains(&("file".to_string(), "batch_input.jsonl".to_string())));
+    mock.assert_finished();
+}
+
+#[tokio::test]
+async fn mock_auto_paginate() {
+    let file = |id: &str| {
+        json!({
+            "id": id,
+            "object": "file",
+            "bytes": 512,
+            "created_at": 1711471533,
+            "filename": "batch.jsonl",
+            "purpose": "batch"
+        })
+    };
+
+    let mock = MockTransport::new();
+    let _ =
+        mock.expect(MockExpectation::new(Method::GET, "/v1/files").respond_with(
+            MockResponse::json(json!({
+                "object": "list",
+                "data": [file("file-1"), file("file-2")],
+                "first_id": "file-1",
+                "last_id": "file-2",
+                "has_more": true
+            })),
+        ))
+        .expect(
+            MockExpectation::new(Method::GET, "/v1/files").respond_with(MockResponse::json(
+                json!({
+                    "object": "list",
+                    "data": [file("file-3"), file("file-4")],
+                    "first_id": "file-3",
+                    "last_id": "file-4",
+                    "has_more": true
+                }),
+            )),
+        );
+
+    let client = create_client(&mock);
+    let parameters = ListFilesParams::new().limit(2).purpose(Purpose::Batch);
+
+    let ids = client
+        .files()
+        .list_files_auto_paginate(parameters, Some(3))
+        .map(|file| file.unwrap().id)
+        .collect::<Vec<_>>()
+        .await;
+
+    assert_eq!(ids, ["file-1", "file-2", "file-3"]);
+    mock.assert_finished();
+
+    let requests = mock.requests();
+    assert_eq!(
+        requests[0].query,
+        [
+            ("limit".to_string(), "2".to_string()),
+            ("purpose".to_string(), "batch".to_string())
+        ]
+    );
+    assert_eq!(
+        requests[1].query,
+        [
+            ("after".to_string(), "file-2".to_string()),
+            ("limit".to_string(), "2".to_string()),
+            ("purpose".to_string(), "batch".to_string())
+        ]
+    );
+}
@@ -46,12 +46,11 @@ async fn chat_completion() {
     chat::chat_completion_test(client).await;
 }
 
-// Not sure how to fix this yet, but the test works if one awaits it
-// #[tokio::test]
-// async fn chat_completion_streaming() {
-//     let client = create_client();
-//     chat_completion::chat_completion_streaming_test(client).await;
-// }
+#[tokio::test]
+async fn chat_completion_streaming() {
+    let client = create_client();
+    chat::chat_completion_streaming_test(client).await;
+}
 
 #[tokio::test]
 async fn embeddings() {
@@ -3,9 +3,12 @@ use std::collections::BTreeMap;
 use open_ai_client::{
     assistants::CreateAssistantRequest,
     assistants_common::ThreadMessage,
-    runs::{CreateRunRequest, CreateThreadAndRunRequest, ModifyRunRequest, RunStatus},
+    runs::{
+        CreateRunRequest, CreateThreadAndRunRequest, ListRunStepsParams, ModifyRunRequest,
+        RunStatus,
+    },
     threads::CreateThreadRequest,
-    ChatModel, OpenAIClient,
+    ChatModel, ListParams, OpenAIClient,
 };
 
 pub async fn runs_test(client: OpenAIClient) {
@@ -79,24 +82,24 @@ async fn create_and_run(client: &OpenAIClient, assistant_id: &str) {
 async fn list_runs(client: &OpenAIClient, thread_id: &str) {
     let result = client
         .runs()
-        .list_runs(thread_id, None, None, None, None)
+        .list_runs(thread_id, ListParams::default())
         .await
         .unwrap();
 
     assert!(result.object == "list");
-    assert!(result.data.len() > 0);
+    assert!(!result.data.is_empty());
     assert!(result.data.iter().any(|x| x.thread_id == thread_id));
 }
 
 async fn list_run_steps(client: &OpenAIClient, thread_id: &str, run_id: &str) -> String {
     let result = client
         .runs()
-        .list_run_steps(thread_id, run_id)
+        .list_run_steps(thread_id, run_id, ListRunStepsParams::default())
         .await
         .unwrap();
 
     assert!(result.object == "list");
-    assert!(result.data.len() > 0);
+    assert!(!result.data.is_empty());
 
     let run_step_id = result.data.into_iter().find(|x| x.run_id == run_id);
     assert!(run_step_id.is_some());
@@ -147,8 +150,8 @@ async fn modify_run(client: &OpenAIClient, thread_id: &str, run_id: &str) {
 async fn delete_thread(client: &OpenAIClient, thread_id: &str) {
     let result = client.threads().delete_thread(thread_id).await.unwrap();
 
-    assert!(&result.id == thread_id);
-    assert!(result.deleted == true);
+    assert!(result.id == thread_id);
+    assert!(result.deleted);
 }
 
 async fn delete_assistant(client: &OpenAIClient, assistant_id: &str) {
@@ -158,6 +161,6 @@ async fn delete_assistant(client: &OpenAIClient, assistant_id: &str) {
         .await
         .unwrap();
 
-    assert!(&result.id == assistant_id);
-    assert!(result.deleted == true);
+    assert!(result.id == assistant_id);
+    assert!(result.deleted);
 }
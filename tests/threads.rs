@@ -55,6 +55,6 @@ async fn modify_thread(client: &OpenAIClient, thread_id: &str) {
 async fn delete_thread(client: &OpenAIClient, thread_id: &str) {
     let result = client.threads().delete_thread(thread_id).await.unwrap();
 
-    assert!(&result.id == thread_id);
-    assert!(result.deleted == true);
+    assert!(result.id == thread_id);
+    assert!(result.deleted);
 }